@@ -0,0 +1,4 @@
+pub use turf_internals::{
+    raw_css_with_settings, style_sheet_with_settings, CompiledStyleSheet, Error, RawCss, Settings,
+    SettingsBuilder, SettingsError, StyleSheetKind,
+};
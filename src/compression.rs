@@ -0,0 +1,31 @@
+use std::io::Read;
+
+/// Decompresses a style sheet embedded with `codegen.compress = "brotli"`
+///
+/// # Panics
+///
+/// Panics if `compressed` isn't valid brotli data of UTF-8 text. The generated
+/// `decompressed_style_sheet()` functions only pass the css the macros compressed at compile time,
+/// which always decompresses.
+pub fn decompress_brotli(compressed: &[u8]) -> String {
+    let mut css = String::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .read_to_string(&mut css)
+        .expect("turf compresses valid css");
+    css
+}
+
+/// Decompresses a style sheet embedded with `codegen.compress = "gzip"`
+///
+/// # Panics
+///
+/// Panics if `compressed` isn't valid gzip data of UTF-8 text. The generated
+/// `decompressed_style_sheet()` functions only pass the css the macros compressed at compile time,
+/// which always decompresses.
+pub fn decompress_gzip(compressed: &[u8]) -> String {
+    let mut css = String::new();
+    flate2::read::GzDecoder::new(compressed)
+        .read_to_string(&mut css)
+        .expect("turf compresses valid css");
+    css
+}
@@ -0,0 +1,62 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Runtime usage counters of a single style sheet
+///
+/// Counters are only recorded in debug builds of crates that enable the `codegen.debug_stats` configuration option, release builds don't contain them at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleSheetStats {
+    /// The path of the style sheet or the hash of an inline style sheet
+    pub style_sheet: &'static str,
+    /// How often the style sheet was accessed through `style_sheet()` or a `*_values` macro
+    pub injections: usize,
+    /// How often each class name was accessed through its `ClassName` accessor function, keyed by the original class name
+    pub class_name_references: BTreeMap<&'static str, usize>,
+}
+
+static DEBUG_STATS: Mutex<BTreeMap<&'static str, StyleSheetStats>> = Mutex::new(BTreeMap::new());
+
+fn with_style_sheet_stats(style_sheet: &'static str, f: impl FnOnce(&mut StyleSheetStats)) {
+    if let Ok(mut debug_stats) = DEBUG_STATS.lock() {
+        let stats = debug_stats
+            .entry(style_sheet)
+            .or_insert_with(|| StyleSheetStats {
+                style_sheet,
+                injections: 0,
+                class_name_references: BTreeMap::new(),
+            });
+        f(stats);
+    }
+}
+
+pub fn record_style_sheet_injection(style_sheet: &'static str) {
+    with_style_sheet_stats(style_sheet, |stats| stats.injections += 1);
+}
+
+pub fn record_class_name_reference(style_sheet: &'static str, class_name: &'static str) {
+    with_style_sheet_stats(style_sheet, |stats| {
+        *stats.class_name_references.entry(class_name).or_insert(0) += 1;
+    });
+}
+
+/// Returns the runtime usage counters of all style sheets that have been accessed so far
+///
+/// Requires the `codegen.debug_stats` configuration option. The counters help finding style sheets that are injected repeatedly or class names that are never used at runtime. The counters, this function and the generated accessor functions only exist in debug builds, so calls to them have to be compiled out of release builds as well, e.g. with `#[cfg(debug_assertions)]`.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// let style_sheet = style_sheet();
+/// let some_class_name = ClassName::some_class();
+///
+/// for stats in turf::debug_stats() {
+///     println!("{}: injected {} times", stats.style_sheet, stats.injections);
+/// }
+/// ```
+pub fn debug_stats() -> Vec<StyleSheetStats> {
+    DEBUG_STATS
+        .lock()
+        .map(|debug_stats| debug_stats.values().cloned().collect())
+        .unwrap_or_default()
+}
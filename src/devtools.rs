@@ -0,0 +1,183 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use wasm_bindgen::prelude::*;
+
+use crate::web::JsValue;
+
+const PANEL_ID: &str = "turf-devtools";
+const PANEL_STYLE: &str = "position:fixed;right:0;bottom:0;z-index:2147483647;max-width:40rem;max-height:50vh;overflow:auto;padding:.5rem;background:#fff;color:#222;border:1px solid #888;font:12px monospace";
+const FILTER_SCRIPT: &str = "var q=this.value;this.parentNode.querySelectorAll('tr[data-names]').forEach(function(r){r.hidden=!!q&&r.dataset.names.indexOf(q)<0})";
+
+#[wasm_bindgen]
+extern "C" {
+    type Element;
+
+    #[wasm_bindgen(js_namespace = document, js_name = getElementById)]
+    fn get_element_by_id(id: &str) -> Option<Element>;
+
+    #[wasm_bindgen(js_namespace = document, js_name = createElement)]
+    fn create_element(local_name: &str) -> Element;
+
+    #[wasm_bindgen(method, js_name = setAttribute)]
+    fn set_attribute(this: &Element, name: &str, value: &str);
+
+    #[wasm_bindgen(method, setter = innerHTML)]
+    fn set_inner_html(this: &Element, html: &str);
+
+    #[wasm_bindgen(js_namespace = ["document", "body"], js_name = appendChild, catch)]
+    fn append_to_body(element: &Element) -> Result<(), JsValue>;
+}
+
+/// A style sheet registered by the `register_devtools()` function generated by the `style_sheet` and `inline_style_sheet` macros
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StyleSheetEntry {
+    /// The path of the style sheet or the hash of an inline style sheet
+    pub style_sheet: &'static str,
+    /// The compiled CSS
+    pub css: &'static str,
+    /// The `SCOPE_ID` of the style sheet
+    pub scope_id: &'static str,
+    /// Pairs of original and scoped class names
+    pub class_names: &'static [(&'static str, &'static str)],
+    /// Pairs of original class names and their source locations, requires the `codegen.class_name_sources` configuration option
+    pub class_name_sources: &'static [(&'static str, &'static str)],
+}
+
+/// A class name found by [`lookup_class_name`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassNameLookup {
+    /// The path of the style sheet or the hash of an inline style sheet
+    pub style_sheet: &'static str,
+    pub original: &'static str,
+    pub scoped: &'static str,
+    /// The source location of the class name, if available
+    pub source: Option<&'static str>,
+}
+
+static REGISTERED_STYLE_SHEETS: Mutex<BTreeMap<&'static str, StyleSheetEntry>> =
+    Mutex::new(BTreeMap::new());
+
+/// Registers a style sheet for the dev overlay, registering the same style sheet twice has no effect
+pub fn register_style_sheet(entry: StyleSheetEntry) {
+    if let Ok(mut registered_style_sheets) = REGISTERED_STYLE_SHEETS.lock() {
+        registered_style_sheets.insert(entry.style_sheet, entry);
+    }
+}
+
+/// Returns all style sheets that have been registered so far
+pub fn registered_style_sheets() -> Vec<StyleSheetEntry> {
+    REGISTERED_STYLE_SHEETS
+        .lock()
+        .map(|registered_style_sheets| registered_style_sheets.values().copied().collect())
+        .unwrap_or_default()
+}
+
+/// Finds a class name of the registered style sheets by its original or its scoped name
+pub fn lookup_class_name(class_name: &str) -> Vec<ClassNameLookup> {
+    registered_style_sheets()
+        .into_iter()
+        .flat_map(|entry| {
+            entry
+                .class_names
+                .iter()
+                .filter(move |(original, scoped)| *original == class_name || *scoped == class_name)
+                .map(move |(original, scoped)| ClassNameLookup {
+                    style_sheet: entry.style_sheet,
+                    original,
+                    scoped,
+                    source: class_name_source(&entry, original),
+                })
+        })
+        .collect()
+}
+
+fn class_name_source(entry: &StyleSheetEntry, class_name: &str) -> Option<&'static str> {
+    entry
+        .class_name_sources
+        .iter()
+        .find(|(original, _)| *original == class_name)
+        .map(|(_, source)| *source)
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Creates the inner HTML of the dev overlay for the given style sheets
+pub fn panel_html(entries: &[StyleSheetEntry]) -> String {
+    #[cfg(debug_assertions)]
+    let injections: BTreeMap<&str, usize> = crate::debug_stats()
+        .into_iter()
+        .map(|stats| (stats.style_sheet, stats.injections))
+        .collect();
+    #[cfg(not(debug_assertions))]
+    let injections: BTreeMap<&str, usize> = BTreeMap::new();
+
+    let mut html = format!(
+        "<summary>turf: {} style sheets</summary><input type=\"search\" placeholder=\"original or scoped class name\" oninput=\"{}\">",
+        entries.len(),
+        FILTER_SCRIPT,
+    );
+
+    for entry in entries {
+        html.push_str(&format!(
+            "<h4>{}</h4><p>{} bytes, scope id <code>{}</code>",
+            escape_html(entry.style_sheet),
+            entry.css.len(),
+            escape_html(entry.scope_id),
+        ));
+        if let Some(injections) = injections.get(entry.style_sheet) {
+            html.push_str(&format!(", injected {} times", injections));
+        }
+        html.push_str("</p><table><tr><th>original</th><th>scoped</th><th>source</th></tr>");
+
+        for (original, scoped) in entry.class_names {
+            let source = class_name_source(entry, original).unwrap_or_default();
+            html.push_str(&format!(
+                "<tr data-names=\"{original} {scoped}\"><td>{original}</td><td>{scoped}</td><td>{source}</td></tr>",
+                original = escape_html(original),
+                scoped = escape_html(scoped),
+                source = escape_html(source),
+            ));
+        }
+        html.push_str("</table>");
+    }
+
+    html
+}
+
+/// Renders or updates an in-page panel listing the registered style sheets
+///
+/// The panel shows the size and scope id of each style sheet and allows looking up class names by their original or scoped name. If the `codegen.debug_stats` configuration option is enabled, it also shows how often each style sheet was injected. Nothing is rendered in release builds.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// register_devtools();
+/// turf::devtools::render_panel()?;
+/// ```
+pub fn render_panel() -> Result<(), JsValue> {
+    if cfg!(not(debug_assertions)) {
+        return Ok(());
+    }
+
+    let panel = match get_element_by_id(PANEL_ID) {
+        Some(panel) => panel,
+        None => {
+            let panel = create_element("details");
+            panel.set_attribute("id", PANEL_ID);
+            panel.set_attribute("style", PANEL_STYLE);
+            append_to_body(&panel)?;
+            panel
+        }
+    };
+    panel.set_inner_html(&panel_html(&registered_style_sheets()));
+
+    Ok(())
+}
@@ -51,6 +51,7 @@
 //!
 //! ```rust
 //! static STYLE_SHEET: &'static str = "<style_sheet>";
+//! static STYLE_SHEET_VERSION: &'static str = "<style_sheet_version>";
 //! struct ClassName;
 //! impl ClassName {
 //!     pub const TOP_LEVEL_CLASS: &'static str = "<unique_class_name>";
@@ -65,47 +66,211 @@
 //! let some_class_name = ClassName::SOME_CLASS;
 //! ```
 //!
+//! Each constant is documented with its original name, its generated name and the CSS rules that select it, so hovering a constant in your editor shows the styles it applies.
+//!
 //! ### Configuration
 //!
 //! The configuration for turf can be specified in the Cargo.toml file using the `[package.metadata.turf]` and `[package.metadata.turf-dev]` keys. This allows you to conveniently manage your SCSS compilation settings for both development and production builds within your project's manifest.
 //!
-//! Both profiles offer the exact same configuration options. However, if you haven't specified a `[package.metadata.turf-dev]` profile, the `[package.metadata.turf]` settings will also be applied to debug builds. This ensures consistency in the compilation process across different build types unless you explicitly define a separate configuration for the development profile.
+//! Both profiles offer the exact same configuration options. The `[package.metadata.turf-dev]` settings are merged into the `[package.metadata.turf]` settings for debug builds, so the dev profile only needs to contain the settings that differ, e.g. `debug = true`. Nested tables are merged key by key, and if you haven't specified a `[package.metadata.turf-dev]` profile, the `[package.metadata.turf]` settings are applied to debug builds unchanged. This ensures consistency in the compilation process across different build types.
+//!
+//! Additional profiles, e.g. for benchmarks or a release build with link time optimization, are defined as named tables in `[package.metadata.turf.profile]` and selected by setting the `TURF_PROFILE` environment variable to their name, since cargo doesn't tell macros which profile is being built. A named profile only needs to contain the settings that differ from the profile it inherits from, which is named by its `inherits` key and defaults to `"release"`, the `[package.metadata.turf]` settings. `"dev"` refers to the `[package.metadata.turf-dev]` settings merged into the `[package.metadata.turf]` settings, and named profiles can inherit from each other. Nested tables are merged key by key. If `TURF_PROFILE` is set to `"dev"` or `"release"` these settings are used directly, and if no profile with the name exists, the compilation fails with an error naming the profile.
+//!
+//! ```toml
+//! [package.metadata.turf.profile.bench]
+//! inherits = "release"
+//! minify = false
+//!
+//! [package.metadata.turf.profile.release-lto.class_names]
+//! template = "_<id>"
+//! strategy = "frequency"
+//! ```
+//!
+//! The settings can also be kept in a standalone `turf.toml` or `.config/turf.toml` file, e.g. if `Cargo.toml` is generated by another build system. turf looks for the file in the directory of the crate's manifest and then in each of its parent directories, so a single file at the root of a workspace configures all of its crates. The closest file takes precedence over the metadata of the Cargo manifest, which is ignored if a file is found. The file uses the same `turf` and `turf-dev` tables as the manifest, without the `package.metadata` prefix:
+//!
+//! ```toml
+//! [turf]
+//! minify = true
+//!
+//! [turf.class_names]
+//! template = "app-<id>"
+//!
+//! [turf-dev]
+//! minify = false
+//! ```
+//!
+//! Build systems like Bazel or Buck, which sandbox the build and don't set a usable `CARGO_MANIFEST_DIR`, can point turf at its settings with environment variables instead. `TURF_PROJECT_ROOT` sets the directory that style sheets, load paths and output files are resolved against, taking precedence over `CARGO_MANIFEST_DIR`, and `TURF_SETTINGS_FILE` sets the path of a configuration file that is used instead of looking one up. The file has the same `turf` and `turf-dev` tables as `turf.toml`, and is read as JSON if its extension is `.json`, e.g. `{ "turf": { "minify": false } }`. Without a configuration file or Cargo manifest the default settings are used. These variables can also be set with `cargo:rustc-env` in a build script.
+//!
+//! Keys that aren't turf settings are compile errors, so typos don't get ignored silently. The error names the unknown key, the table containing it and the closest valid key, e.g. `minifiy = false` fails with ``The turf setting `minifiy` of the settings table is unknown, did you mean `minify`?``. The tables of named profiles, targets and overrides are checked as well. Environment variables with an unknown key are ignored instead, since other tools might use the `TURF_` prefix too.
+//!
+//! Every setting can also be overridden by an environment variable, e.g. in CI pipelines or build systems that shouldn't patch `Cargo.toml`. The name of the variable is `TURF_` followed by the uppercase key of the setting, with `__` separating the keys of nested settings, e.g. `TURF_MINIFY`, `TURF_DEBUG`, `TURF_LOAD_PATHS` or `TURF_CLASS_NAMES__TEMPLATE` for `class_names.template`, which can also be set with the short name `TURF_CLASS_TEMPLATE`. Variables with the `TURF_` prefix that match no setting are ignored with a warning. Values are read as TOML values and fall back to strings, so `TURF_MINIFY=false` and `TURF_LOAD_PATHS='["styles", "vendor"]'` work as well as `TURF_CLASS_NAMES__TEMPLATE=app-<id>`. The overrides are applied on top of the profile that is used for the build, or on top of the defaults if the manifest has no turf settings. Cargo doesn't rebuild a crate when these variables change, so the crate has to be rebuilt after changing them, e.g. by running `cargo clean -p <crate>`.
+//!
+//! String values of the settings can refer to environment variables as `${NAME}`, e.g. `load_paths = ["${DESIGN_SYSTEM_DIR}/scss"]` for paths that differ between CI and developer machines. The variables are expanded when the settings are loaded and a variable that isn't set is a compile error naming the variable and the setting, while `$${` is kept as a literal `${`. Just like with the overrides, the crate has to be rebuilt after changing the variables.
+//!
+//! Settings that only apply when compiling for a specific target, e.g. aggressive minification and browser targets for WebAssembly builds while native builds and tests keep readable output, are defined in the `target` table of a profile, keyed by the target triple. They are merged into the settings of the profile key by key before the environment overrides are applied. Since cargo only tells build scripts which target is being built, the crate needs a build script that passes the `TARGET` environment variable on to the macros:
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main() {
+//!     println!("cargo:rustc-env=TARGET={}", std::env::var("TARGET").unwrap());
+//! }
+//! ```
+//!
+//! ```toml
+//! [package.metadata.turf]
+//! minify = false
+//!
+//! [package.metadata.turf.target.'wasm32-unknown-unknown']
+//! minify = true
+//!
+//! [package.metadata.turf.target.'wasm32-unknown-unknown'.browser_targets]
+//! chrome = [100]
+//! ```
+//!
+//! Style sheets that need different settings than the rest of the crate, e.g. vendored style sheets whose class names must not be renamed, get them from the `overrides` array of tables. Each override has a glob `pattern` matching the paths of style sheet files relative to the manifest directory, where `*` matches within a directory and `**` across directories, and the settings that differ for the matching style sheets. The settings of an override are merged into the settings of the profile key by key, after the target and environment overrides, and the last override whose pattern matches a style sheet is used. Inline style sheets are never overridden.
+//!
+//! ```toml
+//! [package.metadata.turf]
+//! minify = true
+//!
+//! [[package.metadata.turf.overrides]]
+//! pattern = "styles/vendor/**"
+//! class_names = { excludes = [".*"] }
+//! ```
 //!
 //! Example configuration:
 //!
 //! ```toml
 //! [package.metadata.turf]
 //! minify = true
+//! minify_raw_css = false
+//! max_css_size_kb = 4096
+//! untrusted_input = false
+//! strict = false
 //! load_paths = ["path/to/scss/files", "path/to/other/scss/files"]
+//! scoping = "classes"
+//! scope_element_selectors = true
+//! scope_grid_areas = false
+//! split_dark_color_scheme = false
+//!
+//! [package.metadata.turf.sass]
+//! quiet = false
+//! charset = true
+//! input_syntax = "scss"
+//! extensions = ["scss", "sass", "css"]
 //!
 //! [package.metadata.turf.class_names]
 //! template = "custom-<id>-<original_name>"
 //! excludes = ["exclude-this-class-please", "^abc-[123]{4}"]
+//! excludes_selectors = ["\\.swiper-.*", "#app"]
+//! lockfile = "turf.lock"
+//! crate_prefix = true
+//! bem = false
+//! strategy = "template"
+//! salt = "variant-a"
+//! deny_collisions = true
 //!
 //! [package.metadata.turf.browser_targets]
 //! chrome = [80, 1, 2]
 //! firefox = 65
 //! safari = [12, 3]
 //!
+//! [package.metadata.turf.codegen]
+//! debug_stats = true
+//! class_name_sources = true
+//! class_name_map = true
+//! class_name_lookup = true
+//! class_name_metadata = true
+//! media_sheets = true
+//! css_artifacts_path = "target/turf_css"
+//! values_function = true
+//! style = "struct"
+//! visibility = "pub"
+//! attributes = ["#[allow(dead_code)]"]
+//! derives = ["Default"]
+//! class_name_doc = "The `<original_name>` class"
+//! items = []
+//! identifier_sanitization = "replace"
+//! const_case = "screaming_snake"
+//! field_case = "snake"
+//!
+//! [package.metadata.turf.themes]
+//! attribute = "data-theme"
+//! storage_key = "theme"
+//!
+//! [package.metadata.turf.audits]
+//! forced_colors = true
+//! forced_colors_fallback = true
+//!
+//! [package.metadata.turf.css_parser]
+//! nesting = false
+//! custom_media = false
+//! deep_selector_combinator = false
+//!
 //! [package.metadata.turf.file_output]
 //! global_css_file_path = "path/to/global.css"
+//! global_dark_css_file_path = "path/to/global-dark.css"
+//! global_css_priority = 0
+//! global_css_layer = "components.<file_stem>"
 //! separate_css_files_path = "dir/for/separate/css/"
+//! separate_css_file_naming = "source_path"
+//! separate_css_file_name_template = "<file_stem>.<content_hash_short>.css"
+//! source_maps = true
+//! inline_source_maps = false
+//! manifest = true
+//! typescript_definitions_path = "dir/for/typescript/definitions/"
+//! typescript_const_objects = false
+//! relative_to = "working_dir"
+//! partitioned_css_files_path = "dir/for/partitioned/css/"
+//! partitioned_css_files_url = "/assets/css/"
+//! ast_json_path = "path/to/style_sheets.jsonl"
+//! fragments_path = "target/turf_fragments"
+//! fragments_layer = "components"
+//! global_css_ranges_path = "path/to/global_css_ranges.jsonl"
 //! ```
 //!
 //! The following configuration options are available:
 //!
 //! - `minify` (default: `true`): Specifies whether the generated CSS should be minified or not. If set to true, the CSS output will be compressed and optimized for reduced file size. If set to false, the CSS output will be formatted with indentation and line breaks for improved readability.
 //!
+//! - `minify_raw_css` (default: `false`): Minifies the CSS embedded by the `raw_css` and `raw_css_file` macros. The CSS is only stripped of whitespace and comments and not transformed for the browser targets.
+//!
 //! - `load_paths`: Specifies additional paths to search for SCSS files to include during compilation. It accepts a list of string values, where each value represents a directory path to be included. This option allows you to import SCSS files from multiple directories.
 //!
-//! - `browser_targets`: Defines the target browser versions for compatibility when generating CSS. It expects a structure that contains specific versions for different browsers. Each browser can have its own version specified.
+//! - `sass`: Passes options on to the SCSS compiler. `quiet` (default: `false`) silences the messages of `@warn` and `@debug` rules and deprecation warnings, e.g. of a vendored SCSS library. `charset` (default: `true`) lets the compiler add a `@charset` rule to CSS with non-ASCII characters. `input_syntax` (default: the extension of the file) sets the syntax style sheets are written in, `"scss"` or `"indented"` for the indented `.sass` syntax. Files with a `.sass` extension are read in the indented syntax and inline style sheets as SCSS if it isn't set, while imported files are always read in the syntax of their extension. `extensions` (default: `["scss", "sass", "css"]`) limits the extensions `@use`, `@forward` and `@import` resolve files with, e.g. to `["scss"]` so that a plain `.css` file next to a partial is never loaded. Like in dart-sass, loading a directory resolves its `_index.scss` or `index.scss` file, so `@use "buttons"` loads `buttons/_index.scss`.
+//!
+//! - `browser_targets`: Defines the target browser versions for compatibility when generating CSS. It expects either a [browserslist](https://browsersl.ist) query, e.g. `"defaults, not IE 11"`, `{ from_browserslist = true }` to use the browserslist configuration of the project or a structure that contains specific versions for different browsers. Each browser can have its own version specified. Declarations with colors that some of the targets don't support, e.g. `oklch()` colors or the results of `color-mix()` and the relative color syntax, are preceded by a fallback declaration with the colors converted to sRGB, e.g. `color: red; color: oklch(62.8% .258 29.23)`. Custom properties and declarations using `var()` don't get a fallback, since their colors are only known at runtime.
 //!
 //! - `class_names`: Allows configuration of the CSS class name generation. It expects a structure that contains two values for generating CSS class names and excluding class names from the uniquification process.
 //!
+//! - `scoping` (default: `"classes"`): Specifies how the rules of a style sheet are scoped. `"classes"` replaces class names with generated unique class names. `"data-attribute"` leaves class names untouched and instead appends a `[data-turf-scope~="<scope_id>"]` attribute selector to every selector. The scope id is available as the generated `SCOPE_ID` constant and the attribute name as `turf::SCOPE_ATTRIBUTE`. This is useful for markup whose class names cannot be rewritten, e.g. HTML produced by a CMS. `"wrap"` also leaves class names untouched and nests all rules under a single generated root class, so `.button` becomes `.<root_class> .button` and `:root` becomes `.<root_class>`. The root class is available as the generated `SCOPE_ID` constant and only needs to be added to the element that contains the styled markup, which makes it a light way to migrate existing CSS that relies on its original class names. `"prose"` leaves class names untouched and nests all rules under `:where(.<root_class>)`, so `h1` becomes `:where(.<root_class>) h1` and `html`, `body` and `:root` become the wrapper itself. Since `:where()` has no specificity, the rules keep the specificity of the original selectors and are easily overridden by other style sheets. This is meant for HTML whose markup you don't control, e.g. rendered markdown or content blocks of a CMS. The root class is available as the generated `SCOPE_ID` constant, and the generated `wrap_prose` function wraps a string of HTML in a `div` with the root class. `"shadow"` leaves class names untouched for style sheets that are only used inside of the shadow root of a web component and fails the compilation for selectors that never match inside of a shadow root, such as `:root`, `html` or `body`.
+//!
+//! - `scope_element_selectors` (default: `false`): Rewrites selectors that only consist of element selectors (e.g. `p`, `a:hover` or `ul li`) into descendants of a generated root class (e.g. `.class-abc123 p`), so element styles don't leak into other components when style sheets are aggregated. The root class is available as the generated `SCOPE_ID` constant and needs to be added to the root element of the component. Selectors of the `html` and `body` elements are not rewritten. Only applies to the `"classes"` scoping strategy.
+//!
+//! - `scope_grid_areas` (default: `false`): Scopes the grid area names defined by `grid-template-areas`, `grid-template` and `grid` declarations like class names. References to these areas in `grid-area`, `grid-row` and `grid-column` declarations and their longhands, including the implicit `-start` and `-end` line names, are rewritten as well.
+//!
+//! - `split_dark_color_scheme` (default: `false`): Moves the top level `@media (prefers-color-scheme: dark)` rules of each style sheet out of the `STYLE_SHEET` static into a separate `STYLE_SHEET_DARK` static, which is only generated if a style sheet has such rules. The class names are scoped before the rules are split, so both parts use the same class names. The dark rules keep their `@media` rule, so applications can inject them conditionally, e.g. once the user switches to a dark theme, or load them from a separate file. Media rules that combine the color scheme with other queries are not split.
+//!
+//! - `selector_prefix`: Nests every selector of the style sheet under the given compound selector, e.g. `"#my-extension-root"`, so `.button` becomes `#my-extension-root .button`. `html`, `body` and `:root` at the start of a selector are replaced by the prefix, so `body > .button` becomes `#my-extension-root > .button`. This is meant for browser extension content scripts and other embedded widgets whose styles must only apply below a root node they inject into the page. Unlike the `"wrap"` scoping strategy, the prefix is chosen by you, applies to every rule including global ones and can be combined with any `scoping` strategy. Selectors using the nesting selector `&` and view transition selectors are left unchanged.
+//!
 //! - `debug` (default: `false`): When set to true, this option will enable debug output of the read configuration and the generated CSS class names. This can be helpful for troubleshooting and understanding how the CSS is being generated.
 //!
+//! - `max_css_size_kb`: Fails the compilation of a style sheet if its compiled CSS is larger than the given number of kilobytes. The memory needed to transform a style sheet for the browser targets grows with the size of its CSS, so the limit keeps a loop or mixin that generates far more rules than intended from exhausting the memory of the compiler in that step, e.g. on CI runners with little memory. The SCSS of the style sheet and the files it loads are checked against the limit while they are loaded, before they are compiled, and the compiled CSS is checked again before it is transformed, since loops and mixins can generate far more CSS than their SCSS. The limit therefore doesn't bound the memory used by the SCSS compilation of a small style sheet that generates too many rules.
+//!
+//! - `untrusted_input` (default: `false`): Compiles style sheets in a safe mode for SCSS that isn't written by you, e.g. themes uploaded by the users of a platform. Style sheets can only load files inside of the `load_paths` by `@use`, `@forward`, `@import` or `meta.load-css`, all other files are reported as missing. This doesn't limit the time or memory the compilation takes. turf can't interrupt the compilation of a style sheet, so a style sheet that compiles without end, e.g. because of a mixin that recurses without end, makes the build hang, and one that generates too many rules can exhaust the memory before its compiled CSS is checked against `max_css_size_kb`. Builds of untrusted input should therefore be run with a timeout and a memory limit, e.g. those of the CI job.
+//!
+//! - `strict` (default: `false`): Fails the compilation for CSS the browsers would ignore, e.g. in CI pipelines that should reject broken CSS instead of shipping it. The compile error lists every warning of the CSS parser, every declaration of an unknown property and every value that isn't valid for its property, with the selector of the rule and its line and column in the compiled CSS. Values with `var()` or `env()` references are only known at runtime and are never reported, neither are vendor prefixed properties.
+//!
 //! - `file_output`: Enables output of compiled CSS. It expects a structure that contains two values for a single global CSS file or separate CSS files for each compiled SCSS file.
 //!
+//! - `codegen`: Allows configuration of the generated code. It expects a structure that contains the code generation options described below.
+//!
+//! - `themes`: Enables the generation of the `THEME_BOOTSTRAP` snippet. It expects a structure that contains the theme options described below.
+//!
+//! - `audits`: Enables analysis passes over the compiled CSS that report problems as compiler output. It expects a structure that contains the audit options described below.
+//!
+//! - `css_parser`: Enables draft and non-standard syntax in the CSS parser, which fails the compilation otherwise. It expects a structure that contains the parser options described below.
+//!
 //! #### The `class_names` Key
 //!
 //! - `template` (default: `"class-<id>"`): Specifies the template for generating randomized CSS class names. The template can include placeholders to customize the output:
@@ -115,17 +280,137 @@
 //!     - `<name_hash_short>` will be replaced with the first 5 characters of the hash of the original class name from the SCSS file
 //!     - `<style_sheet_hash>` will be replaced with the hash of the SCSS file
 //!     - `<style_sheet_hash_short>` will be replaced with the first 8 characters of the hash of the SCSS file
+//!     - `<scope_id>` will be replaced with the scope id of the style sheet, which is the same for all of its class names
+//!
+//!     Generated class names are checked against the class names generated for all other style sheets of the crate. A colliding class name is regenerated if the template contains `<id>`, otherwise the compilation fails, see `deny_collisions`.
+//!
+//! - `strategy` (default: `"template"`): Specifies how class names are scoped. `"template"` generates class names from the `template`. `"none"` keeps the original class names in the CSS while still generating the `ClassName` constants, which then map to the original names. The names of keyframes, containers, counter styles, view transitions and grid areas are kept as well. This allows adopting the type-safe constants incrementally before turning on the scoping of class names. `"frequency"` also generates class names from the `template`, but replaces `<id>` with the shortest available id instead of a random one and assigns the shortest ids to the class names that are used most often in the style sheet, e.g. `class-a` for the most common class name. Ids that are already taken by other style sheets are skipped. This reduces the size of both the CSS and the HTML of large applications, especially with a short template like `"_<id>"`. The compiled size of each style sheet and the size it would have with random ids are reported as build output. Since the ids are only unique within a crate, `crate_prefix` should be enabled for libraries.
+//!
+//! - `salt`: A string that is mixed into the hashes used for generating class names, i.e. `<name_hash>`, `<name_hash_short>`, `<style_sheet_hash>` and `<style_sheet_hash_short>`. Two builds of the same application that are deployed side by side, e.g. for A/B tests or embedded widgets, get distinct class names with different salts, while each build stays reproducible. Instead of a string, the salt can reference an environment variable with `salt = { env = "VARIABLE_NAME" }`, in which case compilation fails if the variable is not set. Cargo doesn't rebuild a crate when the variable changes, so a build script of the crate should emit `cargo:rerun-if-env-changed=VARIABLE_NAME`.
 //!
-//! - `excludes`: An array of regex patterns that exclude class names in your SCSS files from the class name uniquification process.
+//! - `excludes`: An array of regex patterns that exclude class names in your SCSS files from the class name uniquification process. The patterns apply to all other scoped names as well.
+//!
+//! - `excludes_selectors`: An array of regex patterns that exclude whole selectors from all transformations. Matching selectors are left completely untouched, so neither their class names are renamed nor are they scoped by the `data-attribute`, `wrap` or `scope_element_selectors` options. The patterns are matched against the minified selector, e.g. `.swiper-slide .title`. This allows integrating the CSS of third-party JavaScript widgets that rely on their original selectors. Class names of excluded selectors are available as `ClassName` constants with their original name, unless they are scoped by another selector of the style sheet.
+//!
+//! - `lockfile`: Specifies the path of a lockfile relative to the project directory in which the generated class names are persisted. Class names found in the lockfile are reused by subsequent builds and only new classes get newly generated names, keeping class names stable for things like end-to-end test selectors or cached HTML. Style sheet files are identified by their path, inline style sheets by the hash of their content.
+//!
+//! - `crate_prefix` (default: `false`): Prepends the sanitized name of the crate's package followed by a `-` to every generated class name. This prevents collisions when the style sheets of several crates are aggregated into a single global CSS file.
+//!
+//! - `deny_collisions` (default: `true`): Fails the compilation with an error naming both style sheets if a generated class name collides with a class name generated for another style sheet of the crate and can't be regenerated. Set it to `false` to only print a warning instead, e.g. for a template without `<id>` that intentionally generates the same class names in several style sheets.
+//!
+//! - `bem` (default: `false`): Treats class names following the BEM naming convention (`block__element--modifier`) as one unit. Only the block is scoped, so all elements and modifiers of a block share its generated name (e.g. `card__title--active` becomes `class-abc123__title--active`). In addition to the `ClassName` constants, a nested `class_name` module reflecting the BEM hierarchy is generated, e.g. `class_name::card::BLOCK`, `class_name::card::LARGE` for `card--large`, `class_name::card::title::ELEMENT` and `class_name::card::title::ACTIVE`. The compilation fails if a modifier is converted to the `BLOCK` or `ELEMENT` constant of its block or element, e.g. `card--block`, or if two blocks, elements or modifiers of the same parent are converted to the same identifier.
 //!
 //! #### The `file_output` Key
 //!
-//! - `global_css_file_path`: Specifies the file path for a global CSS file. If set, a CSS file will be created at the provided path, and all compiled styles will be written to this file. This allows you to have a single CSS file containing all the compiled styles.
+//! - `global_css_file_path`: Specifies the file path for a global CSS file. If set, a CSS file will be created at the provided path, and all compiled styles will be written to this file. This allows you to have a single CSS file containing all the compiled styles. Style sheets annotated with a `/* turf:no-global-file */` comment, e.g. a style sheet for emails or for printing, are still compiled and embedded but left out of the global CSS file. Annotations in imported SCSS files apply to the importing style sheet as well. Inline style sheets can't be annotated, since comments are not part of the macro input. Style sheets that compile to the same CSS, e.g. a style sheet embedded by several macros, are only written once. Whenever a style sheet is written, the file is rewritten to a temporary file next to it, which then replaces it, so the file is never read while it is partially written. Since the file only contains the style sheets compiled by the last build of each crate writing to it, a full rebuild of the crate removes the CSS of deleted style sheets.
+//!
+//! - `global_dark_css_file_path`: Specifies the file path for a global CSS file containing the dark color scheme rules of all style sheets that are split by `split_dark_color_scheme`. These rules are then left out of the `global_css_file_path` file.
+//!
+//! - `global_css_priority` (default: `0`): Specifies the priority of the style sheets in the global CSS files. Style sheets with a lower priority are written first, so the rules of style sheets with a higher priority win over rules with the same specificity, e.g. a reset style sheet can be given a priority of `-1` with an override matching its path. Style sheets with the same priority are written in the order the macros are expanded.
+//!
+//! - `global_css_layer`: Wraps the CSS of every style sheet in the global CSS files in a cascade layer named by the template, e.g. `"components.<file_stem>"` wraps `src/button.scss` in `@layer components.button { ... }`. The template can include the placeholders `<file_stem>` for the name of the SCSS file without its extension, where characters that aren't allowed in layer names are replaced with `-`, or `inline-` followed by the hash of an inline style sheet, and `<crate>` for the name of the crate. The file starts with an `@layer` statement listing the layers ordered by `global_css_priority` and name, so the cascade doesn't depend on the order the macros are expanded in. Since the order and the `@layer` statement move the CSS of other style sheets, their byte ranges in the file are only listed by the `global_css_ranges_path` file, which is rewritten together with it.
 //!
 //! - `separate_css_files_path`: Specifies the directory path for separate CSS files. If set, all compiled CSS files will be saved in the specified directory. Each compiled SCSS file will have its corresponding CSS file in this directory, allowing for modular CSS management. The file name for inline SCSS style definitions will be a 64 bit hash that is computed from the original SCSS style.
 //!
+//! - `separate_css_file_naming` (default: `"file_name"`): Specifies how the names of the separate CSS files are derived. `"file_name"` uses the file name of the SCSS file, while `"source_path"` uses its path relative to the project directory (e.g. `src/components/button.scss` is written to `src_components_button.css`). Compilation fails with an error naming both SCSS files if two different style sheets would be written to the same separate CSS file.
+//!
+//! - `separate_css_file_name_template`: Specifies a template for the names of the separate CSS files, which takes precedence over `separate_css_file_naming`, e.g. `"<file_stem>.<content_hash_short>.css"` writes `src/button.scss` to `button.1f2e3d4c.css`. The template can include the placeholders `<file_stem>` for the name of the SCSS file without its extension or the hash of an inline style sheet, `<content_hash>` for a 64 bit hash of the compiled CSS, `<content_hash_short>` for its first 8 characters and `<crate>` for the name of the crate. Whenever separate CSS files are written, the macros generate a `CSS_FILE_NAME` constant with the name of the style sheet's file, which can be used to build the URLs of `<link>` elements.
+//!
+//! - `source_maps`: Writes a source map next to each separate CSS file, named like the CSS file with an additional `.map` extension, and links it with a `sourceMappingURL` comment. Requires `separate_css_files_path`. Since the SCSS compiler doesn't generate source maps, the maps point to the CSS compiled from the SCSS file, which is embedded in the map as `<path/to/file.scss>.css`, rather than to the SCSS file itself.
+//!
+//! - `inline_source_maps`: Appends the source map to the CSS of each style sheet as a base64 data URL, so the CSS injected by the `STYLE_SHEET` constant can be traced without separate files. Like the separate source maps, it points to the CSS compiled from the SCSS file. `STYLE_SHEET_HASH`, `STYLE_SHEET_VERSION` and the content hash of the manifest are computed from the CSS without the source map.
+//!
+//! - `manifest`: Writes a `turf-manifest.json` file to the `separate_css_files_path` directory, or next to the `global_css_file_path` file if there are no separate CSS files. The manifest maps the source path of each style sheet, relative to the crate's manifest directory, to its generated class names, the hash of its compiled CSS, its `STYLE_SHEET_VERSION` and the name of the CSS file it is written to, so asset pipelines and end-to-end tests can resolve the scoped class names.
+//!
+//! - `typescript_definitions_path`: Specifies a directory for a `.d.ts` file per style sheet, which declares the default export `classNames` mapping the original class names to the generated ones, so JavaScript interop code can refer to the scoped class names. The files are named like the separate CSS files, e.g. `src/button.scss` is declared in `button.d.ts`, and follow `separate_css_file_naming`.
+//!
+//! - `typescript_const_objects`: Additionally writes a `.ts` file per style sheet to the `typescript_definitions_path` directory, which exports the `classNames` mapping as a const object for code that is bundled from TypeScript.
+//!
+//! - `relative_to` (default: `"working_dir"`): Specifies the directory the output paths are relative to. `"target_dir"` resolves them relative to `CARGO_TARGET_DIR` or the `target` directory of the workspace, e.g. `global_css_file_path = "turf/global.css"` writes `target/turf/global.css`, and `"out_dir"` relative to the `OUT_DIR` of the crate's build script.
+//!
+//! - `partitioned_css_files_path`: Specifies the directory path for partitioned CSS files. If set, the compiled CSS of every style sheet that is annotated with partitions (see [Style Sheet Partitions](#style-sheet-partitions)) is written to a `<partition>.css` file in the specified directory for each of its partitions.
+//!
+//! - `partitioned_css_files_url`: Specifies the URL the directory of the partitioned CSS files is served from. If set, the `style_sheet` macro generates a `PARTITION_URLS` static containing the URLs of the partitions of the style sheet.
+//!
+//! - `ast_json_path`: Specifies the file path for a JSON Lines file describing the rules of the compiled style sheets. If set, one JSON object per style sheet is written to this file, containing the path of the style sheet and its style rules. Each rule lists its selectors, the preludes of the at-rules it is nested in (e.g. `"@media (width >= 600px)"`), its declarations with their property, value and `!important` flag and its line in the CSS compiled from the SCSS, before turf transformed it. The rules are taken from the exact CSS that turf generated, so external tools can run their own audits without parsing the CSS again.
+//!
+//! - `fragments_path`: Specifies a directory into which the compiled CSS of each style sheet is written as a fragment, together with its path, its hash, its `STYLE_SHEET_VERSION` and the `fragments_layer`. Every crate writes its fragments to a separate file named after its package, the crate and whether it is a binary, so all crates of a workspace, including the binaries and tests of a package, can share the same directory. The fragments are linked into one bundle by `turf::link::link_fragments`, see [Workspace Bundles](#workspace-bundles). Style sheets annotated with `/* turf:no-global-file */` are left out.
+//!
+//! - `fragments_layer`: Specifies the name of the cascade layer the fragments of the crate are wrapped in when they are linked into a bundle.
+//!
+//! - `global_css_ranges_path`: Specifies the file path for a JSON Lines file listing the byte range of every style sheet in the global CSS file, with one `{"style_sheet": ..., "start": ..., "end": ...}` object per style sheet. Style sheets with the same CSS share a range and the file is rewritten together with the global CSS file, so the ranges always match the file written by the same build. The ranges aren't generated as constants, since the range of a style sheet changes whenever a style sheet compiled later is placed before it. This allows a streaming SSR server to send only the parts of a large global CSS file that belong to the components it has already rendered, e.g. by slicing the file or using HTTP range requests.
+//!
+//! The crates of a workspace are compiled in parallel, so the output files are locked while they are written. The global CSS files, the ranges file, the AST JSON file, the partitioned CSS files and the manifest can be shared by several crates: every crate stores its style sheets in the `turf` directory of the target directory and the files are rewritten from the style sheets of all crates, ordered by `global_css_priority` and by crate. A rebuild of a crate replaces its style sheets, the style sheets of crates that no longer write to a file are kept until `cargo clean`. The directories of the separate CSS files and the TypeScript definitions can be shared as well, a rebuild of a crate only removes the files it wrote to them.
+//!
+//! #### The `codegen` Key
+//!
+//! - `debug_stats` (default: `false`): Generates a `style_sheet()` function and a snake case accessor function for each class name (e.g. `ClassName::some_class()`) next to the `STYLE_SHEET` static and the `ClassName` constants. These functions and the `*_values` macros record how often a style sheet was injected and how often each class name was referenced. The counters are available through `turf::debug_stats()` and help finding style sheets that are injected repeatedly or never used at runtime. The functions, the counters and `turf::debug_stats()` only exist in debug builds and are strictly compiled out of release builds, so code calling them has to be compiled out as well, e.g. with `#[cfg(debug_assertions)]`.
+//!
+//! - `class_name_sources` (default: `false`): Generates a `_SOURCE` constant for each class name in debug builds (e.g. `ClassName::TITLE_SOURCE`) containing the location of the class name's first occurrence in the style sheet file as `path:line:column`. Dev tools can use it to link from a class name in the DOM to its definition in the editor. Class names that don't occur literally in the style sheet file, e.g. names built with the SCSS parent selector `&` or defined in imported files, are located by the file path only. Inline style sheets don't generate source constants.
+//!
+//! - `class_name_map` (default: `false`): Generates a `CLASS_NAME_MAP` static next to the `STYLE_SHEET` static, containing all pairs of original and generated class names of the style sheet as `&'static [(&'static str, &'static str)]`, sorted by the original name. Unlike the `lookup` and `iter` functions, the slice can be passed around as plain data, e.g. to debug overlays, test id generators or analytics.
+//!
+//! - `class_name_lookup` (default: `false`): Generates the `lookup`, `get` and `iter` functions for looking up generated class names by their original name at runtime next to the class name constants, see [Class Name Lookup](#class-name-lookup). The option requires the `class_name_lookup` feature of turf, which provides the perfect hash map the lookup is generated with.
+//!
+//! - `class_name_metadata` (default: `false`): Generates a `CLASS_NAME_METADATA` static next to the `STYLE_SHEET` static, containing a `turf::ClassNameMetadata` for every class name of the style sheet, sorted by the original name. It lists the queries of the `@media` rules the class name is selected in, as they are printed in the compiled CSS (e.g. `(width >= 768px)`), and the pseudo classes it is selected with (e.g. `:hover`). Component libraries can use it to document the responsive and interactive states of their components or to warn about props that have no matching CSS.
+//!
+//! - `media_sheets` (default: `false`): Generates a `STYLE_SHEET_BASE` static with the rules of the style sheet that are not nested in an `@media` rule and a `MEDIA_SHEETS` static of type `&[(&str, &str)]` with the CSS of the top level `@media` rules, keyed by their query as it is printed in the compiled CSS (e.g. `(width >= 768px)`). Adjacent rules with the same query are collected in the same entry and keep their `@media` rule, so each entry can be injected on its own. This allows server side rendering to inline the base styles and load large breakpoint specific styles lazily. The entries have to be injected after the base styles and in their order to keep the cascade order of the style sheet, so the compilation fails if splitting would move a rule past another one, i.e. if a rule that isn't nested in an `@media` rule follows one that is or if the rules of a query aren't adjacent. `STYLE_SHEET` still contains the complete CSS.
+//!
+//! - `css_artifacts_path`: Specifies a directory relative to the project directory to which the compiled CSS of each style sheet is written, together with the values that change with it. The generated `STYLE_SHEET`, `STYLE_SHEET_VERSION`, `STYLE_SHEET_HASH`, `SCOPE_ID`, `CSS_FILE_NAME`, `STYLE_SHEET_DARK` and `FORCED_COLORS_FALLBACK` items then embed these files with `include_str!` and the documentation of the class name constants no longer contains their CSS rules, so edits that only change styles leave the tokens generated by the macros unchanged and rustc sees a changed file instead. Combined with class names that don't change with the content of the style sheet, i.e. a `lockfile` or a `template` without `<id>` and `<style_sheet_hash>`, this allows incremental compilation to reuse the code that uses the class names of a style sheet after style-only edits. The directory has to be a UTF-8 path, and the option can't be combined with `media_sheets`, `class_name_sources`, `class_name_metadata` or `compress`, whose items describe the CSS.
+//!
+//! - `compress`: Compresses the CSS embedded by the macros with `"brotli"` or `"gzip"`, which shrinks binaries that embed large style sheets, e.g. wasm bundles. The `STYLE_SHEET` static is replaced by a `STYLE_SHEET_COMPRESSED` static of type `&'static [u8]` and a `decompressed_style_sheet()` function, which decompresses it on its first call and returns the CSS as `&'static str`. The `values()` function, the `*_values` macros and the functions generated by `debug_stats` and the `web` and `devtools` features use the decompressed CSS, while other items like `STYLE_SHEET_BASE` and `STYLE_SHEET_DARK` are embedded uncompressed. The option requires the `compression` feature of turf, which provides the decompression in `turf::compression`.
+//!
+//! - `values_function` (default: `false`): Generates a `ClassNames` struct and a `values()` function next to the `STYLE_SHEET` static and the `ClassName` constants of the `style_sheet` and `inline_style_sheet` macros. `values()` returns the same `(STYLE_SHEET, ClassNames)` tuple as the `style_sheet_values` and `inline_style_sheet_values` macros, so code bases using both styles only need to compile each style sheet once.
+//!
+//! - `style` (default: `"struct"`): Specifies how the class name constants are generated. `"struct"` generates the constants as associated constants of the `ClassName` struct (e.g. `ClassName::SOME_CLASS`). `"module"` generates them in a `class` module instead (e.g. `class::SOME_CLASS`), which allows importing all class names with `use style::class::*;` and re-exporting them across crates. `"enum"` generates a `ClassName` enum with a variant for each class name (e.g. `ClassName::SomeClass`), which enables exhaustive matching and passing class names through APIs without bare strings. The enum implements `as_str()`, `Display` and `FromStr` for the generated class names and lists all variants in the `ClassName::ALL` constant. The accessor functions and source constants of the `debug_stats` and `class_name_sources` options are generated in the same module or enum implementation.
+//!
+//! - `visibility` (default: `"pub"`): Specifies the visibility of the items generated by the `style_sheet` and `inline_style_sheet` macros, i.e. `"pub"`, `"pub(crate)"` or `"private"`. Libraries can use `"pub(crate)"` to keep the generated statics, constants and functions out of their public API, which also prevents `missing_docs` warnings for them.
+//!
+//! - `attributes`: An array of attributes that are added to every item generated by the `style_sheet` and `inline_style_sheet` macros, e.g. `["#[allow(dead_code)]"]` or `["#[cfg(target_arch = \"wasm32\")]"]`.
+//!
+//! - `derives`: An array of derive macros that are added to the generated `ClassName` struct or enum and the `ClassNames` struct, e.g. `["Default", "serde::Serialize"]`. Traits a type already derives or implements, i.e. `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and `Hash` for the `ClassName` enum and `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and `Default` for the `ClassNames` struct, are left out for that type.
+//!
+//! - `class_name_doc`: A template for the documentation of the generated class name constants or variants, replacing the default documentation with the original name, the generated name and the rules of the class name. `<original_name>`, `<name>` and `<rules>` are replaced with the original class name, the generated class name and the rules of the class name respectively, e.g. `"Styles the <original_name> element of the component"`.
+//!
+//! - `items`: An array of Rust items that are added to the items generated by the `style_sheet` and `inline_style_sheet` macros, e.g. `["impl my_crate::Styled for ClassName { fn style_sheet() -> &'static str { STYLE_SHEET } }"]`. The items can refer to all generated items and get the same `attributes` and `visibility`. Together with `derives`, `class_name_doc` and `attributes`, this adapts the generated code to the requirements of a project without separate settings for each of them.
+//!
+//! - `identifier_sanitization` (default: `"replace"`): Specifies how names that aren't valid Rust identifiers are turned into the names of the generated constants, variants, fields and functions. `"replace"` replaces invalid characters with `_` (e.g. `is-active!` becomes `ClassName::IS_ACTIVE_`), while `"strip"` removes them (e.g. `ClassName::IS_ACTIVE`). Valid characters are the ones Unicode allows in identifiers, like Rust itself. With both strategies, names starting with a character that can't start an identifier, such as a digit, are prefixed with `_` (e.g. `2col` becomes `ClassName::_2_COL`), keywords are suffixed with `_` (e.g. the `type` field of `ClassNames`) and names without any valid characters, such as emojis, are named after their code points. A compile message lists all renamed class names, and the compilation fails if two class names are turned into the same identifier, e.g. `is-active` and `is_active`, or two names of the other generated structs, e.g. the keyframes `fade-in` and `fade_in`.
+//!
+//! - `const_case` (default: `"screaming_snake"`): Specifies the case of the class name constants of the `"struct"` and `"module"` styles, i.e. `"screaming_snake"`, `"snake"`, `"pascal"` or `"camel"`. For example, `"camel"` turns the class name `btn-primary` into `ClassName::btnPrimary`. The lint for constants that aren't upper case is allowed for constants of another case. The variants of the `"enum"` style are always pascal case. With `"snake"`, the constants clash with the accessor functions of `debug_stats`.
+//!
+//! - `field_case` (default: `"snake"`): Specifies the case of the fields of the `ClassNames` struct generated by the `*_values` macros and the `values_function` option, with the same values as `const_case`.
+//!
+//! #### The `themes` Key
+//!
+//! - `attribute` (default: `"data-theme"`): Specifies the attribute of the root element that selects the active theme, e.g. `[data-theme="dark"]` in your style sheets.
+//!
+//! - `storage_key` (default: `"theme"`): Specifies the `localStorage` key the selected theme is stored under.
+//!
+//! #### The `audits` Key
+//!
+//! - `forced_colors` (default: `false`): Reports declarations that break forced colors modes like Windows High Contrast, for example removed outlines or background images and box shadows that indicate a state in rules with `:hover`, `:focus`, `:checked` or similar selectors. Rules whose selector is also styled in a `@media (forced-colors: active)` rule are not reported.
+//!
+//! - `forced_colors_fallback` (default: `false`): Generates a `FORCED_COLORS_FALLBACK` static containing a `@media (forced-colors: active)` skeleton with a suggested fallback for every reported declaration. The skeleton is meant to be reviewed and copied into the style sheet.
+//!
+//! #### The `css_parser` Key
+//!
+//! - `nesting` (default: `false`): Parses the [CSS nesting](https://www.w3.org/TR/css-nesting-1/) draft syntax of the compiled CSS.
+//!
+//! - `custom_media` (default: `false`): Parses [custom media queries](https://drafts.csswg.org/mediaqueries-5/#custom-mq), e.g. `@custom-media --narrow (max-width: 30em);`, and replaces their references like `@media (--narrow)` with the media query they define. Resolving them also merges the declarations of each rule, e.g. `margin-top`, `margin-right`, `margin-bottom` and `margin-left` into `margin`.
+//!
+//! - `deep_selector_combinator` (default: `false`): Parses the non-standard `>>>` and `/deep/` selector combinators used by Vue and Angular, which are kept in the compiled CSS.
+//!
 //! #### Browser Versions
 //!
+//! Instead of the versions of each browser, `browser_targets` can be set to a browserslist query, which is resolved with the browser usage data bundled with turf at build time:
+//!
+//! ```toml
+//! [package.metadata.turf]
+//! browser_targets = "defaults, not IE 11"
+//! ```
+//!
+//! In projects that also contain JavaScript, `browser_targets = { from_browserslist = true }` reads the targets from the browserslist configuration the JavaScript tooling uses, i.e. the `browserslist` key of a `package.json` or a `.browserslistrc` or `browserslist` file in the directory of the crate's manifest or one of its parent directories. The `BROWSERSLIST`, `BROWSERSLIST_CONFIG` and `BROWSERSLIST_ENV` environment variables are respected like by browserslist itself. Cargo doesn't rebuild a crate when the browserslist configuration changes, so the crate has to be rebuilt after changing it.
+//!
 //! The available browsers are as follows:
 //!
 //! - android
@@ -147,6 +432,313 @@
 //! | Use a single integer to specify the major version number. | Use an array `[major, minor]` to specify both the major and minor version numbers. | Use an array `[major, minor, patch]` to specify the major, minor, and patch version numbers. |
 //! | Example: `1` or `[1]` represent version `1.0.0` | Example: `[1, 2]` represents version `1.2.0` | Example: `[1, 2, 3]` represents version `1.2.3`. |
 //!
+//! ### Class Name Composition
+//!
+//! Like in CSS Modules, a rule can reuse the styles of other class names with `composes` declarations. The generated constant of the composing class name then contains the scoped class names of all composed classes, separated by spaces, so the element gets all of them without concatenating class names manually. `composes` declarations are removed from the compiled CSS.
+//!
+//! ```scss
+//! .base {
+//!     padding: 0;
+//! }
+//!
+//! .button {
+//!     composes: base;
+//!     composes: focus-ring from global;
+//!     color: red;
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! // e.g. "class-abc123 class-def456 focus-ring"
+//! let class = ClassName::BUTTON;
+//! ```
+//!
+//! Composed class names must be defined in the same style sheet, unless they are marked with `from global`. Compositions are resolved transitively and are only allowed in rules whose selectors are single class names, e.g. `.button`, but not `.button:hover`.
+//!
+//! ### Scoped Names
+//!
+//! Besides class names, turf also scopes the following names using the `class_names` configuration:
+//!
+//! - Container names used in `container-name` and `container` declarations and `@container` rules are available as constants of the `ContainerName` struct (e.g. `ContainerName::SIDEBAR`).
+//! - Counter styles defined by `@counter-style` rules and referenced in `list-style-type` and `list-style` declarations are available as constants of the `CounterStyle` struct.
+//! - Animations defined by `@keyframes` rules and referenced in `animation-name` and `animation` declarations are available as constants of the `Keyframes` struct.
+//! - View transition names used in `view-transition-name` declarations and `::view-transition-*()` pseudo elements are available as constants of the `ViewTransitionName` struct.
+//!
+//! Structs for scoped names are only generated if the style sheet contains such names.
+//!
+//! Names of shadow parts referenced by `::part()` pseudo elements are not scoped, since they are the public styling interface of a web component. They are still collected into the constants of a generated `Parts` struct (e.g. `Parts::LABEL`), so Rust code setting `part` or `exportparts` attributes stays in sync with the style sheet.
+//!
+//! Custom properties are not scoped either. Every custom property declared in the style sheet, either by a declaration like `--progress: 0%` or by an `@property` rule, is collected into the constants of a generated `CssVariables` struct (e.g. `CssVariables::PROGRESS` with the value `"--progress"`), so typos in Rust code setting them via `element.style.setProperty` fail the compilation instead of being silently ignored. Custom properties that are only referenced by `var()` are not included.
+//!
+//! Font families defined by `@font-face` rules are not scoped either, since fonts are looked up by their exact family name. They are collected into the constants of a generated `FontFamilies` struct (e.g. `FontFamilies::INTER_VARIABLE` with the value `"Inter Variable"`), so Rust code rendering text on a canvas or with WebGL uses the same family names as the style sheet.
+//!
+//! Grid area names defined by `grid-template-areas`, `grid-template` and `grid` declarations are only scoped if `scope_grid_areas` is enabled. Either way they are collected into the constants of a generated `GridAreas` struct (e.g. `GridAreas::SIDEBAR`), so Rust code assigning `grid-area` inline styles can't reference an area that doesn't exist.
+//!
+//! ### Class Name Lookup
+//!
+//! Class names that are only known at runtime, e.g. names from templates or data files, can be resolved with `ClassName::lookup` if `codegen.class_name_lookup` is enabled. It looks up the generated class name for an original class name in a perfect hash map that is generated at compile time without allocating, `ClassName::get` is an alias of it and `ClassName::iter` returns all pairs of original and generated class names, sorted by the original name. With `codegen.style = "module"`, the functions are generated as `class::lookup`, `class::get` and `class::iter`. Since the functions share their namespace with the class name constants and the accessor functions of `debug_stats`, the compilation fails if a class name is turned into the identifier `lookup`, `get` or `iter`, e.g. with `const_case = "snake"`.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! let some_class_name: Option<&'static str> = ClassName::lookup("some-class");
+//!
+//! for (original_class_name, class_name) in ClassName::iter() {
+//!     println!("{original_class_name} = {class_name}");
+//! }
+//! ```
+//!
+//! ### Scoping Directives
+//!
+//! The scoping of rules can also be controlled from within a style sheet, without touching the `class_names.excludes` configuration. A `/* turf:global */` comment turns off the scoping of all following rules, and a `/* turf:scope */` comment turns it back on. Class names of global rules are neither renamed nor scoped by the `data-attribute` and `wrap` strategies and are available as `ClassName` constants with their original name, unless they are scoped by another rule of the style sheet. Directives apply to whole rules, so nested rules of a global rule are global as well. Inline style sheets can't contain directives, since comments are not part of the macro input.
+//!
+//! ```scss
+//! .card {
+//!     color: red;
+//! }
+//!
+//! /* turf:global */
+//! .markdown h1 {
+//!     color: blue;
+//! }
+//!
+//! /* turf:scope */
+//! .footer {
+//!     color: green;
+//! }
+//! ```
+//!
+//! ### Scope Ids
+//!
+//! Regardless of the `scoping` strategy, the `style_sheet` and `inline_style_sheet` macros generate a `SCOPE_ID` constant with the scope id of the macro invocation. The scope id is derived from the hash of the style sheet and `class_names.salt`, so it is the same in every build of the style sheet. With the `data-attribute` strategy it is the value of the attribute selectors, and with the `wrap` and `prose` strategies and `scope_element_selectors` it is the root class the selectors are nested under. With the `classes` strategy the class names only contain it if the `class_names.template` includes the `<scope_id>` placeholder, e.g. `"<scope_id>-<original_name>"`. The scope id can be used to derive related identifiers, such as the ids of portal containers or test ids.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! let portal_id = format!("{SCOPE_ID}-portal");
+//! ```
+//!
+//! ### Style Sheet Versions
+//!
+//! The `style_sheet` and `inline_style_sheet` macros also generate a `STYLE_SHEET_VERSION` static. It is derived from the hash of the compiled CSS and the generated class names and therefore changes whenever either of them changes. Applications can store it on the client, for example next to cached CSS or persisted adopted style sheets, and use `turf::style_sheet_version_changed` to decide when those caches need to be invalidated after a deploy. Since the version is derived from the content instead of being counted up, two versions are only compared for equality. The version is also written to the `turf-manifest.json` file of `file_output.manifest` and to the fragments of `file_output.fragments_path`, so deploy tooling can tell which style sheets changed without running the application.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! if turf::style_sheet_version_changed(stored_version.as_deref(), STYLE_SHEET_VERSION) {
+//!     // invalidate cached styles
+//! }
+//! ```
+//!
+//! The macros also generate a `STYLE_SHEET_HASH` constant with a hash of the compiled CSS alone. It only changes when the CSS itself changes, which makes it suitable for versioning `<style>` tags or building hashed URLs when serving the CSS as a file.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! let href = format!("/styles.{STYLE_SHEET_HASH}.css");
+//! ```
+//!
+//! ### Theme Bootstrap
+//!
+//! If the `themes` key is configured, the `style_sheet` and `inline_style_sheet` macros also generate a `THEME_BOOTSTRAP` static. It contains a minimal JavaScript snippet that sets the configured attribute of the root element to the theme stored in `localStorage`, falling back to `dark` or `light` based on the `prefers-color-scheme` media query. Inlining it in a `<script>` element in the `<head>` of server-side rendered pages applies the stored theme before the first paint and avoids a flash of the wrong theme.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! let head = format!("<script>{THEME_BOOTSTRAP}</script><style>{STYLE_SHEET}</style>");
+//! ```
+//!
+//! ### Sharing Values with Rust
+//!
+//! Like the `:export` blocks of CSS Modules, top level `:export` blocks in a style sheet pass SCSS values on to Rust. Every declaration in such a block becomes a constant of the generated `Exports` struct containing the compiled value. If the value is a number with an optional unit, like `768px` or `1.5`, an additional `f64` constant with the suffix `_NUMBER` contains the number without its unit. The compilation fails for numbers that are out of the range of an `f64` and for exports whose constants would have the same name, e.g. `gap` and `gap-number`. The `:export` blocks are removed from the compiled CSS.
+//!
+//! ```scss
+//! $md: 768px;
+//! $primary-color: #ff0000;
+//!
+//! :export {
+//!     breakpointMd: $md;
+//!     primary: $primary-color;
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! assert_eq!(Exports::BREAKPOINT_MD, "768px");
+//! assert_eq!(Exports::BREAKPOINT_MD_NUMBER, 768.0);
+//! assert_eq!(Exports::PRIMARY, "#ff0000");
+//! ```
+//!
+//! Breakpoints are shared the same way. If a style sheet or one of the files it imports defines a global `$turf-breakpoints` map, every entry of the map becomes a constant of the generated `Breakpoints` struct containing a `min-width` media query, which can be passed to `matchMedia` or used by other responsive logic in Rust.
+//!
+//! ```scss
+//! $turf-breakpoints: (sm: 640px, md: 768px);
+//!
+//! .sidebar {
+//!     @media (min-width: map-get($turf-breakpoints, md)) {
+//!         display: block;
+//!     }
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! assert_eq!(Breakpoints::SM, "(min-width: 640px)");
+//! assert_eq!(Breakpoints::MD, "(min-width: 768px)");
+//! ```
+//!
+//! Style sheets with a `/* turf:theme */` annotation reflect their top level variables into the generated `Theme` struct and the `THEME` constant holding their values. Numbers without a unit become `f32` fields, numbers with a unit become `turf::Length` fields with a `value` and a `unit` and all other values, like colors, become `&'static str` fields. The compilation fails for numbers that are out of the range of an `f32`. Private variables starting with `-` or `_` are left out.
+//!
+//! ```scss
+//! /* turf:theme */
+//! $primary: #123456;
+//! $gap: 8px;
+//! $line-height: 1.5;
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! assert_eq!(THEME.primary, "#123456");
+//! assert_eq!(THEME.gap.to_string(), "8px");
+//! assert_eq!(THEME.line_height, 1.5);
+//! ```
+//!
+//! ### Web Components
+//!
+//! Style sheets compiled with `scoping = "shadow"` are meant to be adopted by the shadow roots of web components. If the `web` feature of turf is enabled, the `style_sheet` and `inline_style_sheet` macros generate a `register_constructable_stylesheet` function for such style sheets. It constructs a `CSSStyleSheet` from the compiled CSS once and adds it to the `adoptedStyleSheets` of the given shadow root, so all instances of a component share the same style sheet.
+//!
+//! ```toml
+//! [dependencies]
+//! turf = { version = "..", features = ["web"] }
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! register_constructable_stylesheet(&shadow_root)?;
+//! ```
+//!
+//! For style sheets using other scoping strategies, `turf::web::adopt_style_sheet(&shadow_root, STYLE_SHEET)` can be used directly.
+//!
+//! ### Injection Order
+//!
+//! When components inject their style sheets at runtime, the order of the injected styles depends on the order in which the components are mounted. Since later rules win the cascade over earlier rules of the same specificity, this can make the resulting styles depend on rendering order. With the `web` feature enabled, `turf::web::inject_style_sheet(STYLE_SHEET, priority)` adds a style sheet to the document's head as a `<style>` element and keeps all injected style sheets ordered by their priority. Style sheets with a higher priority are always placed after style sheets with a lower priority, style sheets with the same priority keep the order they were injected in and injecting the same style sheet twice has no effect.
+//!
+//! ```rust,ignore
+//! mod base {
+//!     turf::style_sheet!("scss/base.scss");
+//! }
+//! mod button {
+//!     turf::style_sheet!("scss/button.scss");
+//! }
+//!
+//! turf::web::inject_style_sheet(button::STYLE_SHEET, 10);
+//! // inserted before the button styles, so the button styles still override the base styles
+//! turf::web::inject_style_sheet(base::STYLE_SHEET, 0);
+//! ```
+//!
+//! ### Style Sheet Partitions
+//!
+//! Code-split applications can load the CSS of a route together with its lazily loaded code. Style sheets are assigned to one or more partitions with a `/* turf:partition <names> */` annotation, where multiple partition names are separated by commas or whitespace. Annotations in imported SCSS files apply to the importing style sheet as well. Inline style sheets can't be annotated, since comments are not part of the macro input.
+//!
+//! ```scss
+//! /* turf:partition settings, profile */
+//! .settings-page {
+//!     color: red;
+//! }
+//! ```
+//!
+//! The `style_sheet` macro generates a `PARTITIONS` static listing the partitions of the style sheet, and the `file_output.partitioned_css_files_path` configuration option aggregates the compiled CSS of each partition into a separate file.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! assert_eq!(PARTITIONS, &["profile", "settings"]);
+//! ```
+//!
+//! If the `web` feature is enabled and `file_output.partitioned_css_files_url` is configured, the macro also generates a `load_partitions` function. It loads the CSS files of all partitions of the style sheet the first time it is called, for example when a lazily loaded component is rendered for the first time, and returns a promise that resolves as soon as the styles are ready. Every partition is only loaded once, no matter how many components belong to it. `turf::web::load_style_sheet` and `turf::web::load_style_sheets` can be used to load CSS files by URL directly.
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! let ready = load_partitions();
+//! ```
+//!
+//! ### Dev Overlay
+//!
+//! The `devtools` feature of turf, which includes the `web` feature, adds an in-page panel for inspecting the style sheets of an application during development. With the feature enabled, the `style_sheet` and `inline_style_sheet` macros generate a `register_devtools` function that registers the style sheet together with its class names in debug builds. `turf::devtools::render_panel()` then renders a panel listing all registered style sheets with their size and scope id and a search field to look up class names by their original or scoped name. The panel also shows the source location of each class name if `codegen.class_name_sources` is enabled and how often each style sheet was injected if `codegen.debug_stats` is enabled. Nothing is registered or rendered in release builds.
+//!
+//! ```toml
+//! [dependencies]
+//! turf = { version = "..", features = ["devtools"] }
+//! ```
+//!
+//! ```rust,ignore
+//! turf::style_sheet!("scss/file/path.scss");
+//!
+//! register_devtools();
+//! turf::devtools::render_panel()?;
+//! ```
+//!
+//! The registered style sheets are also available through `turf::devtools::registered_style_sheets()` and `turf::devtools::lookup_class_name()`.
+//!
+//! ### Diagnostics in CI
+//!
+//! If the `TURF_DIAGNOSTICS_SARIF` environment variable is set to a file path, every warning and error of the macros is also written to that file in the SARIF format, which GitHub code scanning shows as annotations on the style sheets of a pull request. Errors of the SCSS compilation are located by the line and column of the SCSS that caused them, warnings about class names by the line and column of the class name and all other diagnostics by the path of the style sheet. The diagnostics of all crates of a build are collected in the same file, so it should be deleted before a build and only clean builds report every diagnostic.
+//!
+//! ```yaml
+//! - run: cargo build
+//!   env:
+//!     TURF_DIAGNOSTICS_SARIF: turf.sarif
+//! - uses: github/codeql-action/upload-sarif@v3
+//!   with:
+//!     sarif_file: turf.sarif
+//! ```
+//!
+//! ### Workspace Bundles
+//!
+//! The `global_css_file_path` of a single crate can't describe the CSS of a whole site if several crates of a workspace contribute styles, since each crate is compiled separately and in no guaranteed order. Instead, all crates can write their style sheets as fragments to a shared `file_output.fragments_path` directory, which a final link step turns into one bundle after the build, e.g. in an xtask. `turf::link::link_fragments`, available with the `link` feature, orders the fragments by package, starting with the packages passed in the given order followed by all other packages in alphabetical order, and by crate and style sheet within each package. Fragments with identical CSS are only included once. If crates set a `fragments_layer`, their CSS is wrapped in that cascade layer and the bundle starts with an `@layer` statement declaring the layers in the order they first appear, so the layer order is fixed no matter which rules come first. `@import` rules are moved to the start of the bundle, since they aren't allowed anywhere else, and a fragment with a layer can't contain them. The returned bundle contains the CSS and its hash, e.g. for a hashed file name.
+//!
+//! ```toml
+//! [dependencies]
+//! turf = { version = "..", features = ["link"] }
+//! ```
+//!
+//! ```rust,ignore
+//! let bundle = turf::link::link_fragments(Path::new("target/turf_fragments"), &["design-system", "app"])?;
+//! std::fs::write(format!("dist/site.{}.css", bundle.hash), bundle.css)?;
+//! ```
+//!
+//! Since the link step reads whatever fragments the last builds left behind, it should run after a build of the whole workspace.
+//!
+//! ### Build Scripts and Tools
+//!
+//! Build scripts and tools can compile style sheets without the settings of a Cargo manifest with the `build` feature, e.g. to generate the CSS of a static site or to check style sheets in a custom pipeline. `turf::build::Settings::builder()` creates the settings, starting from the defaults, and `Settings::from_table` reads them from a TOML table with the same keys as `[package.metadata.turf]`. `turf::build::style_sheet_with_settings` compiles a style sheet file or inline SCSS with them and returns the CSS and the generated names, while `raw_css_with_settings` reads raw CSS like `raw_css!`. Relative paths are resolved against the `CARGO_MANIFEST_DIR` environment variable if it is set, like in build scripts, and against the working directory otherwise.
+//!
+//! ```toml
+//! [build-dependencies]
+//! turf = { version = "..", features = ["build"] }
+//! ```
+//!
+//! ```rust,ignore
+//! // build.rs
+//! use turf::build::{style_sheet_with_settings, Settings, StyleSheetKind};
+//!
+//! let settings = Settings::builder()
+//!     .minify(false)
+//!     .load_path("styles")
+//!     .class_name_template("site-<id>")
+//!     .browser_targets("defaults")
+//!     .build()?;
+//! let compiled = style_sheet_with_settings(StyleSheetKind::File("styles/site.scss".into()), settings)?;
+//! std::fs::write("dist/site.css", compiled.css)?;
+//! ```
+//!
 //! ### Additional Macros
 //!
 //! turf provides a few additional macros for other use cases.
@@ -163,6 +755,8 @@
 //! let some_class_name = class_names.some_class;
 //! ```
 //!
+//! The generated `ClassNames` struct implements `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and `Default`, and its `new()` function is a `const fn`, so the class names can be compared in tests and used in constant expressions.
+//!
 //! #### The `inline_style_sheet` Macro
 //!
 //! If you don't want your style sheet to live in another file, you can use the `turf::inline_style_sheet` macro. It allows you to write inline SCSS which will then be compiled to CSS.
@@ -203,6 +797,30 @@
 //! };
 //! let some_class_name = class_names.some_class;
 //! ```
+//!
+//! #### The `raw_css` and `raw_css_file` Macros
+//!
+//! Vendor CSS that must stay byte-identical to its upstream version can be embedded with the `turf::raw_css_file` macro, or the `turf::raw_css` macro for a string literal. The CSS is neither compiled as SCSS nor scoped nor transformed for the browser targets and is only minified if `minify_raw_css` is enabled. It is still written to the configured output files like the CSS of other style sheets, and changes to the file trigger a rebuild. The macros generate the `STYLE_SHEET`, `STYLE_SHEET_VERSION` and `STYLE_SHEET_HASH` items, the `CSS_FILE_NAME` constant if separate CSS files are written and the `register_devtools` function if the `devtools` feature is enabled.
+//!
+//! **Usage:**
+//!
+//! ```rust,ignore
+//! turf::raw_css_file!("vendor/normalize.css");
+//! // or
+//! turf::raw_css!(".vendor-widget { color: red; }");
+//!
+//! let style_sheet_str = STYLE_SHEET;
+//! ```
+//!
+//! #### The `check_settings` Macro
+//!
+//! The `turf::check_settings` macro loads and validates the settings without compiling a style sheet. Invalid settings fail the compilation at the location of the macro, and the style sheet macros that are expanded afterwards only refer to that error instead of repeating it. Besides the errors of loading the settings, it reports an unset `class_names.salt` environment variable, load paths that don't exist and invalid `excludes` or `excludes_selectors` patterns. Settings that have no effect, like a `file_output.global_dark_css_file_path` without `split_dark_color_scheme`, are reported as warnings. The macro is meant to be called once, e.g. at the top of the crate root.
+//!
+//! **Usage:**
+//!
+//! ```rust,ignore
+//! turf::check_settings!();
+//! ```
 
 /// Generates the static variable `STYLE_SHEET` and the `ClassName` struct with default settings or the settings specified in the `Cargo.toml`
 ///
@@ -273,3 +891,134 @@ pub use turf_macros::inline_style_sheet;
 /// let some_class_name = class_names.some_class;
 /// ```
 pub use turf_macros::inline_style_sheet_values;
+
+/// Generates the static variable `STYLE_SHEET` from a CSS file that is embedded verbatim
+///
+/// The CSS is neither compiled as SCSS nor scoped nor transformed for the browser targets, so vendor CSS stays byte-identical to its upstream version. It is only minified if `minify_raw_css` is enabled. The CSS is still written to the configured output files and changes to the file trigger a rebuild.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::raw_css_file!("vendor/normalize.css");
+///
+/// let style_sheet_str = STYLE_SHEET;
+/// ```
+pub use turf_macros::raw_css_file;
+
+/// Generates the static variable `STYLE_SHEET` from a string of CSS that is embedded verbatim
+///
+/// Like the `raw_css_file` macro, but for CSS given as a string literal.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::raw_css!(".vendor-widget { color: red; }");
+///
+/// let style_sheet_str = STYLE_SHEET;
+/// ```
+pub use turf_macros::raw_css;
+
+/// Loads and validates the settings without compiling a style sheet
+///
+/// Invalid settings fail the compilation at the location of the macro, the style sheet macros expanded afterwards only refer to that error. Settings that have no effect are reported as warnings.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::check_settings!();
+/// ```
+pub use turf_macros::check_settings;
+
+#[cfg(feature = "build")]
+pub mod build;
+#[cfg(feature = "compression")]
+pub mod compression;
+#[cfg(debug_assertions)]
+mod debug_stats;
+#[cfg(feature = "devtools")]
+pub mod devtools;
+#[cfg(feature = "link")]
+pub mod link;
+#[cfg(feature = "web")]
+pub mod web;
+
+#[cfg(debug_assertions)]
+pub use debug_stats::{debug_stats, StyleSheetStats};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(debug_assertions)]
+    pub use crate::debug_stats::{record_class_name_reference, record_style_sheet_injection};
+    #[cfg(feature = "class_name_lookup")]
+    pub use phf;
+}
+
+/// Returns whether a previously stored style sheet version differs from the current one
+///
+/// The `style_sheet` and `inline_style_sheet` macros generate a `STYLE_SHEET_VERSION` static that changes whenever the compiled CSS or the generated class names change. Comparing it with a version stored on the client allows deciding whether cached CSS or persisted adopted style sheets need to be invalidated.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// if turf::style_sheet_version_changed(stored_version.as_deref(), STYLE_SHEET_VERSION) {
+///     // invalidate cached styles
+/// }
+/// ```
+pub fn style_sheet_version_changed(stored_version: Option<&str>, current_version: &str) -> bool {
+    stored_version != Some(current_version)
+}
+
+/// The name of the attribute that scopes the rules of style sheets compiled with `scoping = "data-attribute"`
+///
+/// Every rule of such a style sheet only applies to elements whose `data-turf-scope` attribute contains the style sheet's `SCOPE_ID` in its whitespace separated list of values.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// let html = format!(r#"<div {}="{}">...</div>"#, turf::SCOPE_ATTRIBUTE, SCOPE_ID);
+/// ```
+pub const SCOPE_ATTRIBUTE: &str = "data-turf-scope";
+
+/// The error returned when parsing a string that is not a class name of the style sheet into a `ClassName` enum
+///
+/// `ClassName` enums are generated with the `codegen.style = "enum"` configuration option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseClassNameError(pub String);
+
+impl std::fmt::Display for ParseClassNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a class name of the style sheet", self.0)
+    }
+}
+
+impl std::error::Error for ParseClassNameError {}
+
+/// A number with a unit, the type of the `Theme` fields of style sheets with a `/* turf:theme */` annotation whose variable holds a length like `8px` or `50%`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Length {
+    pub value: f32,
+    pub unit: &'static str,
+}
+
+impl std::fmt::Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+/// The media queries and pseudo classes a class name of a style sheet is selected under
+///
+/// A `CLASS_NAME_METADATA` static with an entry for every class name of a style sheet is generated with the `codegen.class_name_metadata` configuration option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassNameMetadata {
+    /// The original class name
+    pub class_name: &'static str,
+    /// The media queries of the `@media` rules the class name is selected in
+    pub media_queries: &'static [&'static str],
+    /// The pseudo classes the class name is selected with, e.g. `:hover`
+    pub pseudo_classes: &'static [&'static str],
+}
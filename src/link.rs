@@ -0,0 +1 @@
+pub use turf_internals::{link_fragments, LinkError, LinkedBundle};
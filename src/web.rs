@@ -0,0 +1,181 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use wasm_bindgen::prelude::*;
+
+pub use js_sys::Promise;
+pub use wasm_bindgen::JsValue;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = CSSStyleSheet)]
+    #[derive(Clone)]
+    type CssStyleSheet;
+
+    #[wasm_bindgen(constructor, js_class = "CSSStyleSheet", catch)]
+    fn new() -> Result<CssStyleSheet, JsValue>;
+
+    #[wasm_bindgen(method, js_class = "CSSStyleSheet", js_name = replaceSync, catch)]
+    fn replace_sync(this: &CssStyleSheet, text: &str) -> Result<(), JsValue>;
+
+    type Element;
+
+    #[wasm_bindgen(js_namespace = document, js_name = createElement)]
+    fn create_element(local_name: &str) -> Element;
+
+    #[wasm_bindgen(method, js_name = setAttribute)]
+    fn set_attribute(this: &Element, name: &str, value: &str);
+
+    #[wasm_bindgen(method, setter = onload)]
+    fn set_onload(this: &Element, listener: &js_sys::Function);
+
+    #[wasm_bindgen(method, setter = onerror)]
+    fn set_onerror(this: &Element, listener: &js_sys::Function);
+
+    #[wasm_bindgen(method, setter = textContent)]
+    fn set_text_content(this: &Element, text: &str);
+
+    #[wasm_bindgen(js_namespace = ["document", "head"], js_name = appendChild)]
+    fn append_to_head(element: &Element);
+
+    #[wasm_bindgen(js_namespace = ["document", "head"], js_name = insertBefore)]
+    fn insert_into_head_before(element: &Element, reference: &Element);
+}
+
+/// A `<style>` element added by [`inject_style_sheet`]
+struct InjectedStyleSheet {
+    css: &'static str,
+    priority: i32,
+    element: Element,
+}
+
+thread_local! {
+    static CONSTRUCTED_STYLE_SHEETS: RefCell<HashMap<&'static str, CssStyleSheet>> =
+        RefCell::new(HashMap::new());
+    static LOADED_STYLE_SHEETS: RefCell<HashMap<String, Promise>> = RefCell::new(HashMap::new());
+    static INJECTED_STYLE_SHEETS: RefCell<Vec<InjectedStyleSheet>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+fn constructed_style_sheet(css: &'static str) -> Result<CssStyleSheet, JsValue> {
+    CONSTRUCTED_STYLE_SHEETS.with(|style_sheets| {
+        if let Some(style_sheet) = style_sheets.borrow().get(css) {
+            return Ok(style_sheet.clone());
+        }
+
+        let style_sheet = CssStyleSheet::new()?;
+        style_sheet.replace_sync(css)?;
+        style_sheets.borrow_mut().insert(css, style_sheet.clone());
+
+        Ok(style_sheet)
+    })
+}
+
+/// Adds the CSS to the `adoptedStyleSheets` of a shadow root or document
+///
+/// The `CSSStyleSheet` is constructed once per style sheet and shared by all roots it is adopted by. Adopting the same style sheet twice has no effect.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// turf::web::adopt_style_sheet(&shadow_root, STYLE_SHEET)?;
+/// ```
+pub fn adopt_style_sheet(root: &JsValue, css: &'static str) -> Result<(), JsValue> {
+    let style_sheet = constructed_style_sheet(css)?;
+
+    let adopted_style_sheets_key = JsValue::from_str("adoptedStyleSheets");
+    let adopted_style_sheets =
+        js_sys::Array::from(&js_sys::Reflect::get(root, &adopted_style_sheets_key)?);
+
+    if !adopted_style_sheets.includes(&style_sheet, 0) {
+        adopted_style_sheets.push(&style_sheet);
+        js_sys::Reflect::set(root, &adopted_style_sheets_key, &adopted_style_sheets)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the CSS to the document's head as a `<style>` element, ordered by priority
+///
+/// Style sheets with a higher priority are placed after style sheets with a lower priority and therefore win the cascade for selectors of the same specificity, no matter in which order they are injected. Style sheets with the same priority keep the order they were injected in. Injecting the same style sheet twice has no effect.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// turf::style_sheet!("scss/file/path.scss");
+///
+/// turf::web::inject_style_sheet(STYLE_SHEET, 10);
+/// ```
+pub fn inject_style_sheet(css: &'static str, priority: i32) {
+    INJECTED_STYLE_SHEETS.with(|injected_style_sheets| {
+        let mut injected_style_sheets = injected_style_sheets.borrow_mut();
+        if injected_style_sheets
+            .iter()
+            .any(|injected_style_sheet| injected_style_sheet.css == css)
+        {
+            return;
+        }
+
+        let element = create_element("style");
+        element.set_attribute("data-turf-priority", &priority.to_string());
+        element.set_text_content(css);
+
+        let position = injected_style_sheets
+            .iter()
+            .position(|injected_style_sheet| injected_style_sheet.priority > priority);
+        match position {
+            Some(position) => {
+                insert_into_head_before(&element, &injected_style_sheets[position].element)
+            }
+            None => append_to_head(&element),
+        }
+
+        let index = position.unwrap_or(injected_style_sheets.len());
+        injected_style_sheets.insert(
+            index,
+            InjectedStyleSheet {
+                css,
+                priority,
+                element,
+            },
+        );
+    })
+}
+
+/// Loads a CSS file by adding a `<link rel="stylesheet">` element to the document's head
+///
+/// Each URL is only loaded once. The returned promise resolves as soon as the style sheet has been loaded and is shared by all calls for the same URL, so components can wait for their styles before they are rendered.
+///
+/// **Usage:**
+///
+/// ```rust,ignore
+/// let ready = turf::web::load_style_sheet("/assets/css/settings.css");
+/// ```
+pub fn load_style_sheet(url: &str) -> Promise {
+    LOADED_STYLE_SHEETS.with(|loaded_style_sheets| {
+        loaded_style_sheets
+            .borrow_mut()
+            .entry(url.to_owned())
+            .or_insert_with(|| {
+                Promise::new(&mut |resolve, reject| {
+                    let link = create_element("link");
+                    link.set_attribute("rel", "stylesheet");
+                    link.set_attribute("href", url);
+                    link.set_onload(&resolve);
+                    link.set_onerror(&reject);
+                    append_to_head(&link);
+                })
+            })
+            .clone()
+    })
+}
+
+/// Loads multiple CSS files, see [`load_style_sheet`]
+///
+/// The returned promise resolves as soon as all style sheets have been loaded.
+pub fn load_style_sheets(urls: &[&str]) -> Promise {
+    let promises: js_sys::Array = urls.iter().map(|url| load_style_sheet(url)).collect();
+
+    Promise::all(&promises)
+}
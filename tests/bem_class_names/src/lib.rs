@@ -0,0 +1,18 @@
+#[test]
+fn bem_class_names() {
+    turf::style_sheet!("src/card.scss");
+
+    let block = class_name::card::BLOCK;
+
+    assert_eq!(block, ClassName::CARD);
+    assert_eq!(class_name::card::LARGE, format!("{block}--large"));
+    assert_eq!(class_name::card::title::ELEMENT, format!("{block}__title"));
+    assert_eq!(
+        class_name::card::title::ACTIVE,
+        format!("{block}__title--active")
+    );
+    assert_eq!(
+        class_name::card::title::ACTIVE,
+        ClassName::CARD_TITLE_ACTIVE
+    );
+}
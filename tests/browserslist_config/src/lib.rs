@@ -0,0 +1,10 @@
+#[test]
+fn browserslist_config() {
+    turf::inline_style_sheet! {
+        .accent {
+            color: oklch(62.8% 0.258 29.23);
+        }
+    }
+
+    assert!(STYLE_SHEET.contains("color:red;color:oklch(62.8% .258 29.23)"));
+}
@@ -0,0 +1,25 @@
+#[test]
+fn class_name_enum() {
+    turf::style_sheet!("src/style.scss");
+
+    assert_eq!(ClassName::ALL, &[ClassName::Other, ClassName::TestClass]);
+    assert!(ClassName::TestClass.as_str().starts_with("class-"));
+    assert_eq!(
+        ClassName::TestClass.to_string(),
+        ClassName::TestClass.as_str()
+    );
+    assert_eq!(
+        ClassName::TestClass.as_str().parse::<ClassName>(),
+        Ok(ClassName::TestClass)
+    );
+    assert_eq!(
+        "test-class".parse::<ClassName>(),
+        Err(turf::ParseClassNameError(String::from("test-class")))
+    );
+    assert_eq!(
+        ClassName::lookup("test-class"),
+        Some(ClassName::TestClass.as_str())
+    );
+    assert_eq!(ClassName::iter().count(), 2);
+    assert!(STYLE_SHEET.contains(&format!(".{}", ClassName::Other)));
+}
@@ -0,0 +1,19 @@
+#[test]
+fn reuse_locked_class_names() {
+    turf::style_sheet!("src/mystyle.scss");
+    assert_eq!(ClassName::TEST, "class-locked");
+    assert!(STYLE_SHEET.starts_with(".class-locked{"));
+}
+
+#[test]
+fn embed_css_artifact() {
+    turf::style_sheet!("src/mystyle.scss");
+
+    let artifacts = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/target/turf_css"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "css"))
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(artifacts, vec![STYLE_SHEET]);
+}
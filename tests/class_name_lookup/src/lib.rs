@@ -0,0 +1,18 @@
+#[test]
+fn class_name_lookup() {
+    turf::style_sheet!("src/style.scss");
+
+    assert_eq!(ClassName::lookup("test"), Some(ClassName::TEST));
+    assert_eq!(ClassName::lookup("2col"), Some(ClassName::_2_COL));
+    assert_eq!(ClassName::lookup("is-active!"), Some(ClassName::IS_ACTIVE_));
+    assert_eq!(ClassName::lookup("unknown"), None);
+    assert_eq!(ClassName::get("test"), ClassName::lookup("test"));
+    assert_eq!(
+        ClassName::iter().collect::<Vec<_>>(),
+        vec![
+            ("2col", ClassName::_2_COL),
+            ("is-active!", ClassName::IS_ACTIVE_),
+            ("test", ClassName::TEST)
+        ]
+    );
+}
@@ -0,0 +1,58 @@
+mod style {
+    turf::style_sheet!("src/style.scss");
+}
+
+#[test]
+fn class_name_module() {
+    use style::class::*;
+
+    assert!(TEST_CLASS.starts_with("class-"));
+    assert_eq!(style::class::test_class(), TEST_CLASS);
+    assert_eq!(style::class::lookup("test-class"), Some(TEST_CLASS));
+    assert!(style::STYLE_SHEET.starts_with(&format!(".{TEST_CLASS}")));
+}
+
+#[test]
+fn class_name_map() {
+    assert_eq!(
+        style::CLASS_NAME_MAP,
+        &[("test-class", style::class::TEST_CLASS)]
+    );
+}
+
+#[test]
+fn values_function() {
+    let (style_sheet, class_names) = style::values();
+
+    assert_eq!(style_sheet, style::STYLE_SHEET);
+    assert_eq!(class_names.test_class, style::class::TEST_CLASS);
+}
+
+#[test]
+fn class_name_metadata() {
+    assert_eq!(
+        style::CLASS_NAME_METADATA,
+        &[turf::ClassNameMetadata {
+            class_name: "test-class",
+            media_queries: &["(width >= 768px)"],
+            pseudo_classes: &[":hover"],
+        }]
+    );
+}
+
+#[test]
+fn media_sheets() {
+    let test_class = style::class::TEST_CLASS;
+
+    assert_eq!(
+        style::STYLE_SHEET_BASE,
+        format!(".{test_class}{{color:red}}.{test_class}:hover{{color:#00f}}")
+    );
+    assert_eq!(
+        style::MEDIA_SHEETS,
+        &[(
+            "(width >= 768px)",
+            format!("@media (width>=768px){{.{test_class}{{color:green}}}}").as_str()
+        )]
+    );
+}
@@ -0,0 +1,7 @@
+#[test]
+fn class_name_sources() {
+    turf::style_sheet!("src/card.scss");
+
+    assert!(ClassName::CARD_SOURCE.ends_with("class_name_sources/src/card.scss:1:1"));
+    assert!(ClassName::TITLE_SOURCE.ends_with("class_name_sources/src/card.scss:4:5"));
+}
@@ -0,0 +1,13 @@
+turf::style_sheet!("src/style.scss");
+
+#[test]
+fn split_dark_color_scheme() {
+    assert_eq!(STYLE_SHEET, format!(".{}{{color:#000}}", ClassName::CARD));
+    assert_eq!(
+        STYLE_SHEET_DARK,
+        format!(
+            "@media (prefers-color-scheme:dark){{.{}{{color:#fff}}}}",
+            ClassName::CARD
+        )
+    );
+}
@@ -0,0 +1,17 @@
+#[test]
+fn record_debug_stats() {
+    turf::inline_style_sheet! {
+        .test-class {
+            color: red;
+        }
+    };
+
+    assert_eq!(style_sheet(), STYLE_SHEET);
+    assert_eq!(ClassName::test_class(), ClassName::TEST_CLASS);
+    assert_eq!(ClassName::test_class(), ClassName::TEST_CLASS);
+
+    let stats = turf::debug_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].injections, 1);
+    assert_eq!(stats[0].class_name_references.get("test-class"), Some(&2));
+}
@@ -11,3 +11,16 @@ fn inline_scss_style() {
     assert!(STYLE_SHEET.ends_with(" {\n  color: #69e69d;\n}\n"));
     assert!(STYLE_SHEET.starts_with(&format!(".{}", ClassName::TEST)));
 }
+
+#[test]
+fn inline_scss_style_values() {
+    let (style_sheet, class_names) = turf::inline_style_sheet_values! {
+        .test-class {
+            color: red;
+        }
+    };
+    assert!(style_sheet.starts_with(&format!(".{}", class_names.test_class)));
+    assert_eq!(class_names, class_names.clone());
+    assert_eq!(class_names, Default::default());
+    assert!(format!("{class_names:?}").contains("test_class"));
+}
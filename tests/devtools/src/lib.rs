@@ -0,0 +1,25 @@
+#[test]
+fn devtools() {
+    turf::style_sheet!("src/card.scss");
+
+    register_devtools();
+    register_devtools();
+
+    let entries = turf::devtools::registered_style_sheets();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].css, STYLE_SHEET);
+    assert_eq!(entries[0].scope_id, SCOPE_ID);
+
+    let lookup = turf::devtools::lookup_class_name(ClassName::TITLE);
+    assert_eq!(lookup.len(), 1);
+    assert_eq!(lookup[0].original, "title");
+    assert_eq!(lookup[0].source, Some(ClassName::TITLE_SOURCE));
+    assert_eq!(
+        turf::devtools::lookup_class_name("card")[0].scoped,
+        ClassName::CARD
+    );
+
+    let html = turf::devtools::panel_html(&entries);
+    assert!(html.contains("turf: 1 style sheets"));
+    assert!(html.contains(&format!("<td>title</td><td>{}</td>", ClassName::TITLE)));
+}
@@ -0,0 +1,37 @@
+mod button {
+    turf::style_sheet!("src/button.scss");
+}
+
+mod card {
+    turf::style_sheet!("src/card.scss");
+}
+
+/// Reads an output file from the target directory, which contains the test binary
+fn read_output_file(file_name: &str) -> String {
+    let test_binary = std::env::current_exe().unwrap();
+    let path = test_binary
+        .ancestors()
+        .map(|directory| directory.join("turf/global_css_ranges").join(file_name))
+        .find(|path| path.is_file())
+        .unwrap();
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn global_css_ranges() {
+    let global_css = read_output_file("global.css");
+    let ranges = read_output_file("global_css_ranges.jsonl");
+
+    let style_sheets = ranges
+        .lines()
+        .map(|line| {
+            let range: serde_json::Value = serde_json::from_str(line).unwrap();
+            let start = range["start"].as_u64().unwrap() as usize;
+            let end = range["end"].as_u64().unwrap() as usize;
+            &global_css[start..end]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(style_sheets.len(), 2);
+    assert!(style_sheets.contains(&button::STYLE_SHEET));
+    assert!(style_sheets.contains(&card::STYLE_SHEET));
+}
@@ -0,0 +1,20 @@
+//! Generated items don't add to the public API of a library with `visibility = "pub(crate)"`
+#![deny(missing_docs)]
+
+/// Styles of the library
+pub mod style {
+    turf::style_sheet!("src/style.scss");
+}
+
+#[test]
+fn item_options() {
+    assert!(style::ClassName::TEST_CLASS.starts_with("class-"));
+    assert!(style::STYLE_SHEET.contains(style::ClassName::TEST_CLASS));
+}
+
+#[test]
+fn codegen_extensions() {
+    let class_name = style::ClassName::default();
+    assert_eq!(format!("{class_name:?}"), "ClassName");
+    assert_eq!(class_name.style_sheet(), style::STYLE_SHEET);
+}
@@ -1,3 +1,5 @@
+turf::check_settings!();
+
 #[test]
 fn load_paths_from_cargo_manifest() {
     turf::style_sheet!("src/mystyle.scss");
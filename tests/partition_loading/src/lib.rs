@@ -0,0 +1,9 @@
+#[test]
+fn partition_loading() {
+    turf::style_sheet!("src/settings.scss");
+
+    let _: fn() -> turf::web::Promise = load_partitions;
+
+    assert_eq!(PARTITIONS, &["settings"]);
+    assert_eq!(PARTITION_URLS, &["/assets/css/settings.css"]);
+}
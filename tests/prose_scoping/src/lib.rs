@@ -0,0 +1,14 @@
+#[test]
+fn prose_scoping() {
+    turf::inline_style_sheet! {
+        h1 {
+            color: red;
+        }
+    }
+
+    assert_eq!(STYLE_SHEET, format!(":where(.{SCOPE_ID}) h1{{color:red}}"));
+    assert_eq!(
+        wrap_prose("<h1>Title</h1>"),
+        format!("<div class=\"{SCOPE_ID}\"><h1>Title</h1></div>")
+    );
+}
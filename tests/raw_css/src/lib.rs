@@ -0,0 +1,13 @@
+mod vendor {
+    turf::raw_css_file!("src/vendor.css");
+}
+
+mod widget {
+    turf::raw_css!(".widget { color: red; }\n");
+}
+
+#[test]
+fn raw_css() {
+    assert_eq!(vendor::STYLE_SHEET, include_str!("vendor.css"));
+    assert_eq!(widget::STYLE_SHEET, ".widget { color: red; }\n");
+}
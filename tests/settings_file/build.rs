@@ -0,0 +1,4 @@
+fn main() {
+    println!("cargo:rustc-env=TURF_SETTINGS_FILE=turf-settings.json");
+    println!("cargo:rerun-if-changed=turf-settings.json");
+}
@@ -0,0 +1,10 @@
+#[test]
+fn settings_file_takes_precedence_over_manifest() {
+    turf::inline_style_sheet! {
+        .test {
+            color: red;
+        }
+    }
+
+    assert_eq!(ClassName::TEST, "from-json-test");
+}
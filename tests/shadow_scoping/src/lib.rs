@@ -0,0 +1,18 @@
+#[test]
+fn shadow_scoping() {
+    turf::inline_style_sheet! {
+        :host {
+            display: block;
+        }
+
+        .test-class {
+            color: red;
+        }
+    };
+
+    let _: fn(&turf::web::JsValue) -> Result<(), turf::web::JsValue> =
+        register_constructable_stylesheet;
+
+    assert_eq!(ClassName::TEST_CLASS, "test-class");
+    assert_eq!(STYLE_SHEET, ":host{display:block}.test-class{color:red}");
+}
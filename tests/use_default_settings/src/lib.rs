@@ -5,3 +5,108 @@ fn use_default_settings() {
     assert!(STYLE_SHEET.starts_with(".class-"));
     assert!(STYLE_SHEET.starts_with(&format!(".{}", ClassName::TEST)));
 }
+
+#[test]
+fn style_sheet_version() {
+    turf::style_sheet!("src/mystyle.scss");
+    assert_eq!(STYLE_SHEET_VERSION.len(), 16);
+    assert!(!turf::style_sheet_version_changed(
+        Some(STYLE_SHEET_VERSION),
+        STYLE_SHEET_VERSION
+    ));
+    assert!(turf::style_sheet_version_changed(None, STYLE_SHEET_VERSION));
+}
+
+#[test]
+fn style_sheet_hash() {
+    turf::style_sheet!("src/mystyle.scss");
+    assert_eq!(STYLE_SHEET_HASH.len(), 16);
+    assert!(STYLE_SHEET_HASH.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(STYLE_SHEET_HASH, STYLE_SHEET_VERSION);
+}
+
+#[test]
+fn container_names() {
+    turf::style_sheet!("src/container.scss");
+    assert!(ContainerName::SIDEBAR.starts_with("class-"));
+    assert!(STYLE_SHEET.contains(&format!("container-name:{}", ContainerName::SIDEBAR)));
+    assert!(STYLE_SHEET.contains(&format!("@container {} ", ContainerName::SIDEBAR)));
+}
+
+#[test]
+fn partitions() {
+    turf::style_sheet!("src/partitioned.scss");
+    assert_eq!(PARTITIONS, &["profile", "settings"]);
+    assert!(!STYLE_SHEET.contains("turf:partition"));
+}
+
+#[test]
+fn scoping_directives() {
+    turf::style_sheet!("src/directives.scss");
+    assert!(ClassName::CARD.starts_with("class-"));
+    assert_eq!(ClassName::MARKDOWN, "markdown");
+    assert!(ClassName::FOOTER.starts_with("class-"));
+    assert!(STYLE_SHEET.contains(".markdown .card{"));
+}
+
+#[test]
+fn scope_id() {
+    turf::style_sheet!("src/mystyle.scss");
+    assert_eq!(SCOPE_ID.len(), 6);
+    assert!(!STYLE_SHEET.contains(SCOPE_ID));
+}
+
+#[test]
+fn invalid_identifiers() {
+    turf::style_sheet!("src/identifiers.scss");
+    assert!(STYLE_SHEET.contains(ClassName::_2_COL));
+    assert!(STYLE_SHEET.contains(ClassName::IS_ACTIVE_));
+}
+
+#[test]
+fn scss_exports() {
+    turf::style_sheet!("src/exports.scss");
+    assert_eq!(Exports::BREAKPOINT_MD, "768px");
+    assert_eq!(Exports::BREAKPOINT_MD_NUMBER, 768.0);
+    assert_eq!(Exports::PRIMARY, "#ff0000");
+    assert!(!STYLE_SHEET.contains("export"));
+}
+
+#[test]
+fn breakpoints() {
+    turf::style_sheet!("src/breakpoints.scss");
+    assert_eq!(Breakpoints::SM, "(min-width: 640px)");
+    assert_eq!(Breakpoints::MD, "(min-width: 768px)");
+    assert!(!STYLE_SHEET.contains("turf-breakpoint"));
+}
+
+#[test]
+fn class_name_composition() {
+    turf::style_sheet!("src/composition.scss");
+    assert_eq!(ClassName::BUTTON.split(' ').nth(1), Some(ClassName::BASE));
+    assert!(!STYLE_SHEET.contains("composes"));
+}
+
+#[test]
+fn theme() {
+    turf::style_sheet!("src/theme.scss");
+    assert_eq!(THEME.primary, "#123456");
+    assert_eq!(
+        THEME.gap,
+        turf::Length {
+            value: 8.0,
+            unit: "px"
+        }
+    );
+    assert_eq!(THEME.gap.to_string(), "8px");
+    assert_eq!(THEME.line_height, 1.5);
+    assert_eq!(THEME.font, "Helvetica");
+}
+
+#[test]
+fn grid_areas() {
+    turf::style_sheet!("src/grid.scss");
+    assert_eq!(GridAreas::HEADER, "header");
+    assert_eq!(GridAreas::SIDEBAR, "sidebar");
+    assert_eq!(GridAreas::MAIN, "main");
+}
@@ -0,0 +1,149 @@
+use std::convert::Infallible;
+
+use lightningcss::{
+    declaration::DeclarationBlock,
+    printer::PrinterOptions,
+    rules::CssRule,
+    stylesheet::StyleSheet,
+    traits::ToCss,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+use serde::Serialize;
+
+/// A style rule of a style sheet as it is written to the `file_output.ast_json_path` file
+#[derive(Debug, Serialize)]
+pub struct AstRule {
+    selectors: Vec<String>,
+    /// The preludes of the at-rules the rule is nested in
+    at_rules: Vec<String>,
+    declarations: Vec<AstDeclaration>,
+    /// The line of the rule in the css compiled from the scss, before it was transformed
+    line: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct AstDeclaration {
+    property: String,
+    value: String,
+    important: bool,
+}
+
+/// Collects the style rules of a style sheet, together with the conditions of the at-rules they
+/// are nested in
+#[derive(Default)]
+struct AstJsonCollector {
+    at_rules: Vec<String>,
+    rules: Vec<AstRule>,
+}
+
+impl<'i> Visitor<'i> for AstJsonCollector {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        match rule {
+            CssRule::Style(style_rule) => {
+                let selectors = style_rule
+                    .selectors
+                    .0
+                    .iter()
+                    .map(|selector| {
+                        selector
+                            .to_css_string(PrinterOptions::default())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+
+                self.rules.push(AstRule {
+                    selectors,
+                    at_rules: self.at_rules.clone(),
+                    declarations: ast_declarations(&style_rule.declarations),
+                    line: style_rule.loc.line + 1,
+                });
+
+                rule.visit_children(self)
+            }
+            CssRule::Media(_)
+            | CssRule::Supports(_)
+            | CssRule::Container(_)
+            | CssRule::LayerBlock(_)
+            | CssRule::Scope(_)
+            | CssRule::StartingStyle(_) => {
+                let formatted_rule = rule
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default();
+                let prelude = formatted_rule
+                    .split_once('{')
+                    .map_or(formatted_rule.as_str(), |(prelude, _)| prelude);
+                self.at_rules.push(prelude.trim().to_owned());
+                rule.visit_children(self)?;
+                self.at_rules.pop();
+
+                Ok(())
+            }
+            _ => rule.visit_children(self),
+        }
+    }
+}
+
+fn ast_declarations(declarations: &DeclarationBlock) -> Vec<AstDeclaration> {
+    let normal = declarations
+        .declarations
+        .iter()
+        .map(|property| (property, false));
+    let important = declarations
+        .important_declarations
+        .iter()
+        .map(|property| (property, true));
+
+    normal
+        .chain(important)
+        .map(|(property, important)| AstDeclaration {
+            property: property.property_id().name().to_owned(),
+            value: property
+                .value_to_css_string(PrinterOptions::default())
+                .unwrap_or_default(),
+            important,
+        })
+        .collect()
+}
+
+/// Collects the style rules of a transformed style sheet
+///
+/// Each style rule has its selectors, the preludes of the at-rules it is nested in, its
+/// declarations and its line. Since lightningcss keeps the locations the rules were parsed at, the
+/// line refers to the css compiled from the scss before it was transformed, not to the css turf
+/// outputs.
+pub fn style_sheet_rules(style_sheet: &mut StyleSheet) -> Vec<AstRule> {
+    let mut collector = AstJsonCollector::default();
+    style_sheet
+        .visit(&mut collector)
+        .expect("css visitor never fails");
+
+    collector.rules
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::style_sheet_rules;
+
+    #[test]
+    fn serialize_rules() {
+        let css = ".a,.b{color:red}@media (width>=600px){.a{margin:0!important}}";
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&style_sheet_rules(&mut style_sheet)).unwrap(),
+            concat!(
+                r#"[{"selectors":[".a",".b"],"at_rules":[],"declarations":[{"property":"color","value":"red","important":false}],"line":1},"#,
+                r#"{"selectors":[".a"],"at_rules":["@media (width >= 600px)"],"declarations":[{"property":"margin","value":"0","important":true}],"line":1}]"#,
+            )
+        );
+    }
+}
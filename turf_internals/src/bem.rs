@@ -0,0 +1,68 @@
+/// A class name split into the parts of the BEM naming convention (`block__element--modifier`)
+#[derive(Debug, PartialEq)]
+pub struct BemClassName<'a> {
+    pub block: &'a str,
+    pub element: Option<&'a str>,
+    pub modifier: Option<&'a str>,
+}
+
+pub fn parse_bem_class_name(class_name: &str) -> BemClassName<'_> {
+    let (block_and_element, modifier) = match class_name.split_once("--") {
+        Some((block_and_element, modifier)) if !block_and_element.is_empty() => {
+            (block_and_element, Some(modifier))
+        }
+        _ => (class_name, None),
+    };
+
+    let (block, element) = match block_and_element.split_once("__") {
+        Some((block, element)) if !block.is_empty() => (block, Some(element)),
+        _ => (block_and_element, None),
+    };
+
+    BemClassName {
+        block,
+        element,
+        modifier,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bem_class_name, BemClassName};
+
+    #[test]
+    fn parse_bem_class_names() {
+        assert_eq!(
+            parse_bem_class_name("card"),
+            BemClassName {
+                block: "card",
+                element: None,
+                modifier: None,
+            }
+        );
+        assert_eq!(
+            parse_bem_class_name("card--large"),
+            BemClassName {
+                block: "card",
+                element: None,
+                modifier: Some("large"),
+            }
+        );
+        assert_eq!(
+            parse_bem_class_name("card__title--active"),
+            BemClassName {
+                block: "card",
+                element: Some("title"),
+                modifier: Some("active"),
+            }
+        );
+        assert_eq!(
+            parse_bem_class_name("--custom"),
+            BemClassName {
+                block: "--custom",
+                element: None,
+                modifier: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+const BREAKPOINT_EXPORT_PREFIX: &str = "turf-breakpoint-";
+
+/// Appended to every style sheet to export the entries of the `$turf-breakpoints` map, which are
+/// then collected by [`take_breakpoints`]
+pub const BREAKPOINTS_EXPORT: &str = r#"
+@if global-variable-exists(turf-breakpoints) {
+  :export {
+    @each $name, $width in $turf-breakpoints {
+      turf-breakpoint-#{$name}: $width;
+    }
+  }
+}
+"#;
+
+/// [`BREAKPOINTS_EXPORT`] in the indented syntax, appended to style sheets written in it
+pub const BREAKPOINTS_EXPORT_INDENTED: &str = r#"
+@if global-variable-exists(turf-breakpoints)
+  :export
+    @each $name, $width in $turf-breakpoints
+      turf-breakpoint-#{$name}: $width
+"#;
+
+/// Returns the breakpoints export in the syntax of a style sheet
+///
+/// Plain css can't define breakpoints, so nothing is appended to it.
+pub fn breakpoints_export(syntax: grass::InputSyntax) -> &'static str {
+    match syntax {
+        grass::InputSyntax::Sass => BREAKPOINTS_EXPORT_INDENTED,
+        grass::InputSyntax::Css => "",
+        _ => BREAKPOINTS_EXPORT,
+    }
+}
+
+/// Removes the breakpoints exported by [`BREAKPOINTS_EXPORT`] from the exports of a style sheet
+/// and returns the media query of each breakpoint
+pub fn take_breakpoints(exports: &mut BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let names: Vec<String> = exports
+        .keys()
+        .filter(|name| name.starts_with(BREAKPOINT_EXPORT_PREFIX))
+        .cloned()
+        .collect();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let width = exports.remove(&name)?;
+            let breakpoint = name.strip_prefix(BREAKPOINT_EXPORT_PREFIX)?.to_owned();
+            Some((breakpoint, format!("(min-width: {width})")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::take_breakpoints;
+
+    #[test]
+    fn take_breakpoint_exports() {
+        let mut exports = BTreeMap::from([
+            (String::from("primary"), String::from("#ff0000")),
+            (String::from("turf-breakpoint-sm"), String::from("640px")),
+            (String::from("turf-breakpoint-md"), String::from("768px")),
+        ]);
+
+        assert_eq!(
+            take_breakpoints(&mut exports),
+            BTreeMap::from([
+                (String::from("md"), String::from("(min-width: 768px)")),
+                (String::from("sm"), String::from("(min-width: 640px)")),
+            ])
+        );
+        assert_eq!(
+            exports,
+            BTreeMap::from([(String::from("primary"), String::from("#ff0000"))])
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::{collections::BTreeMap, convert::Infallible};
+
+use lightningcss::{
+    selector::{Component, Selector},
+    stylesheet::StyleSheet,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+/// Characters of generated ids, the first character of an id must be a letter or `_`
+const CHARSET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_-";
+const FIRST_CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+
+#[derive(Default)]
+struct ClassNameCounter {
+    counts: BTreeMap<String, usize>,
+}
+
+impl<'i> Visitor<'i> for ClassNameCounter {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(SELECTORS)
+    }
+
+    fn visit_selector(&mut self, selector: &mut Selector<'i>) -> Result<(), Self::Error> {
+        for component in selector.iter_raw_match_order() {
+            if let Component::Class(class_name) = component {
+                *self.counts.entry(class_name.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts how often each class name is used by the selectors of a style sheet
+pub fn class_name_counts(style_sheet: &mut StyleSheet) -> BTreeMap<String, usize> {
+    let mut counter = ClassNameCounter::default();
+    style_sheet
+        .visit(&mut counter)
+        .expect("css visitor never fails");
+
+    counter.counts
+}
+
+/// Orders class names from the most to the least frequently used one
+pub fn by_frequency(counts: &BTreeMap<String, usize>) -> Vec<&str> {
+    let mut class_names: Vec<(&str, usize)> = counts
+        .iter()
+        .map(|(class_name, count)| (class_name.as_str(), *count))
+        .collect();
+    // The sort is stable, so class names used equally often stay in alphabetical order
+    class_names.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    class_names
+        .into_iter()
+        .map(|(class_name, _)| class_name)
+        .collect()
+}
+
+/// Returns the `index`-th shortest id, e.g. `a`, `b`, ..., `_`, `a0`, `a1`, ...
+pub fn short_id(mut index: usize) -> String {
+    let mut length = 1;
+    let mut ids_of_length = FIRST_CHARSET.len();
+    while index >= ids_of_length {
+        index -= ids_of_length;
+        ids_of_length *= CHARSET.len();
+        length += 1;
+    }
+
+    let mut id = vec![0u8; length];
+    for position in (1..length).rev() {
+        id[position] = CHARSET[index % CHARSET.len()];
+        index /= CHARSET.len();
+    }
+    id[0] = FIRST_CHARSET[index];
+
+    String::from_utf8(id).expect("ids only consist of ascii characters")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::{by_frequency, class_name_counts, short_id};
+
+    #[test]
+    fn count_class_names() {
+        let css = ".a .b, .b.c {color: red} .b:hover {color: blue} .c {color: green}";
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+        let counts = class_name_counts(&mut style_sheet);
+
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                (String::from("a"), 1),
+                (String::from("b"), 3),
+                (String::from("c"), 2),
+            ])
+        );
+        assert_eq!(by_frequency(&counts), vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn generate_short_ids() {
+        assert_eq!(short_id(0), "a");
+        assert_eq!(short_id(52), "_");
+        assert_eq!(short_id(53), "a0");
+        assert_eq!(short_id(53 + 64), "b0");
+        assert_eq!(short_id(53 + 53 * 64), "a00");
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+};
+
+use lightningcss::{
+    printer::PrinterOptions,
+    rules::CssRule,
+    selector::{Component, Selector},
+    stylesheet::StyleSheet,
+    traits::ToCss,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+/// The media queries and pseudo classes a class name is selected under
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassNameMetadata {
+    pub media_queries: BTreeSet<String>,
+    pub pseudo_classes: BTreeSet<String>,
+}
+
+#[derive(Default)]
+struct ClassNameMetadataCollector {
+    media_queries: Vec<String>,
+    metadata: BTreeMap<String, ClassNameMetadata>,
+}
+
+impl<'i> Visitor<'i> for ClassNameMetadataCollector {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        match rule {
+            CssRule::Media(media_rule) => {
+                let query = media_rule
+                    .query
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default();
+                self.media_queries.push(query);
+                rule.visit_children(self)?;
+                self.media_queries.pop();
+                return Ok(());
+            }
+            CssRule::Style(style_rule) => {
+                for selector in style_rule.selectors.0.iter() {
+                    // Pseudo classes only apply to the class names of their own compound selector
+                    let mut class_names = Vec::new();
+                    let mut pseudo_classes = Vec::new();
+                    for component in selector.iter_raw_match_order() {
+                        match component {
+                            Component::Class(class_name) => {
+                                class_names.push(class_name.to_string())
+                            }
+                            Component::NonTSPseudoClass(_) => pseudo_classes.push(
+                                Selector::from(vec![component.clone()])
+                                    .to_css_string(PrinterOptions::default())
+                                    .unwrap_or_default(),
+                            ),
+                            Component::Combinator(_) => {
+                                self.collect(&class_names, &pseudo_classes);
+                                class_names.clear();
+                                pseudo_classes.clear();
+                            }
+                            _ => (),
+                        }
+                    }
+                    self.collect(&class_names, &pseudo_classes);
+                }
+            }
+            _ => (),
+        }
+
+        rule.visit_children(self)
+    }
+}
+
+impl ClassNameMetadataCollector {
+    fn collect(&mut self, class_names: &[String], pseudo_classes: &[String]) {
+        for class_name in class_names {
+            let metadata = self.metadata.entry(class_name.clone()).or_default();
+            metadata
+                .media_queries
+                .extend(self.media_queries.iter().cloned());
+            metadata
+                .pseudo_classes
+                .extend(pseudo_classes.iter().cloned());
+        }
+    }
+}
+
+/// Collects the media queries and pseudo classes each class name of a transformed style sheet is
+/// selected under, keyed by the original class name
+///
+/// Nested media queries are collected separately.
+pub fn class_name_metadata(
+    style_sheet: &mut StyleSheet,
+    class_names: &BTreeMap<String, String>,
+) -> BTreeMap<String, ClassNameMetadata> {
+    let mut collector = ClassNameMetadataCollector::default();
+    style_sheet
+        .visit(&mut collector)
+        .expect("css visitor never fails");
+
+    class_names
+        .iter()
+        .map(|(original_class_name, class_name)| {
+            let metadata = collector
+                .metadata
+                .get(class_name)
+                .cloned()
+                .unwrap_or_default();
+            (original_class_name.clone(), metadata)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::class_name_metadata;
+
+    #[test]
+    fn collect_class_name_metadata() {
+        let css = ".class-abc{color:red}.class-abc:hover .class-def:focus-visible{color:blue}@media (min-width: 768px){.class-def{color:green}}";
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+        let class_names = BTreeMap::from([
+            (String::from("card"), String::from("class-abc")),
+            (String::from("title"), String::from("class-def")),
+        ]);
+
+        let metadata = class_name_metadata(&mut style_sheet, &class_names);
+
+        let card = metadata.get("card").unwrap();
+        assert!(card.media_queries.is_empty());
+        assert_eq!(
+            card.pseudo_classes,
+            BTreeSet::from([String::from(":hover")])
+        );
+
+        let title = metadata.get("title").unwrap();
+        assert_eq!(
+            title.media_queries,
+            BTreeSet::from([String::from("(width >= 768px)")])
+        );
+        assert_eq!(
+            title.pseudo_classes,
+            BTreeSet::from([String::from(":focus-visible")])
+        );
+    }
+}
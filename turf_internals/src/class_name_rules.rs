@@ -0,0 +1,108 @@
+use std::{collections::BTreeMap, convert::Infallible};
+
+use lightningcss::{
+    printer::PrinterOptions,
+    rules::CssRule,
+    selector::Component,
+    stylesheet::StyleSheet,
+    traits::ToCss,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+#[derive(Default)]
+struct ClassNameRuleCollector {
+    rules: BTreeMap<String, Vec<String>>,
+}
+
+impl<'i> Visitor<'i> for ClassNameRuleCollector {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        if let CssRule::Style(style_rule) = rule {
+            let mut class_names: Vec<String> = style_rule
+                .selectors
+                .0
+                .iter()
+                .flat_map(|selector| selector.iter_raw_match_order())
+                .filter_map(|component| match component {
+                    Component::Class(class_name) => Some(class_name.to_string()),
+                    _ => None,
+                })
+                .collect();
+            class_names.sort();
+            class_names.dedup();
+
+            if !class_names.is_empty() {
+                let formatted_rule = rule
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default();
+                for class_name in class_names {
+                    self.rules
+                        .entry(class_name)
+                        .or_default()
+                        .push(formatted_rule.clone());
+                }
+            }
+        }
+
+        rule.visit_children(self)
+    }
+}
+
+/// Formats the rules of a transformed style sheet that select each class name
+///
+/// The rules are keyed by the original class name and are formatted without minification, so
+/// they can be shown in the documentation of the generated class name constants.
+pub fn class_name_rules(
+    style_sheet: &mut StyleSheet,
+    class_names: &BTreeMap<String, String>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut collector = ClassNameRuleCollector::default();
+    style_sheet
+        .visit(&mut collector)
+        .expect("css visitor never fails");
+
+    class_names
+        .iter()
+        .filter_map(|(original_class_name, class_name)| {
+            collector
+                .rules
+                .get(class_name)
+                .map(|rules| (original_class_name.clone(), rules.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::class_name_rules;
+
+    #[test]
+    fn collect_class_name_rules() {
+        let css = ".class-abc{color:red}.class-abc:hover,.class-def{color:blue}p{margin:0}";
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+        let mut class_names = BTreeMap::new();
+        class_names.insert(String::from("test"), String::from("class-abc"));
+        class_names.insert(String::from("other"), String::from("class-def"));
+
+        let rules = class_name_rules(&mut style_sheet, &class_names);
+
+        assert_eq!(
+            rules.get("test").unwrap(),
+            &vec![
+                String::from(".class-abc {\n  color: red;\n}"),
+                String::from(".class-abc:hover, .class-def {\n  color: #00f;\n}"),
+            ]
+        );
+        assert_eq!(rules.get("other").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,164 @@
+use std::convert::Infallible;
+
+use lightningcss::{
+    declaration::DeclarationBlock,
+    properties::Property,
+    stylesheet::StyleSheet,
+    targets::Browsers,
+    traits::IsCompatible,
+    values::color::CssColor,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+/// Adds a declaration with the colors converted to sRGB before every declaration with a color the
+/// browser targets don't support, e.g. `oklch()` colors or the results of `color-mix()` and the
+/// relative color syntax
+///
+/// Browsers that support the modern color use the original declaration, which comes last, while
+/// older browsers drop it as invalid and keep the fallback. Custom properties and declarations
+/// with `var()` references are left unchanged, since their colors are only known at runtime.
+pub fn add_color_fallbacks(style_sheet: &mut StyleSheet, browsers: Browsers) {
+    style_sheet
+        .visit(&mut ColorFallbacks { browsers })
+        .expect("css visitor never fails");
+}
+
+struct ColorFallbacks {
+    browsers: Browsers,
+}
+
+impl ColorFallbacks {
+    fn declarations_with_fallbacks<'i>(
+        &self,
+        declarations: Vec<Property<'i>>,
+    ) -> Vec<Property<'i>> {
+        let mut result = Vec::with_capacity(declarations.len());
+
+        for declaration in declarations {
+            if let Some(fallback) = self.fallback(&declaration) {
+                result.push(fallback);
+            }
+            result.push(declaration);
+        }
+
+        result
+    }
+
+    fn fallback<'i>(&self, declaration: &Property<'i>) -> Option<Property<'i>> {
+        if matches!(declaration, Property::Custom(_) | Property::Unparsed(_)) {
+            return None;
+        }
+
+        let mut fallback = declaration.clone();
+        let mut conversion = SrgbConversion {
+            browsers: self.browsers,
+            converted: false,
+        };
+        fallback
+            .visit(&mut conversion)
+            .expect("css visitor never fails");
+
+        conversion.converted.then_some(fallback)
+    }
+}
+
+impl<'i> Visitor<'i> for ColorFallbacks {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES | PROPERTIES)
+    }
+
+    fn visit_declaration_block(
+        &mut self,
+        declaration_block: &mut DeclarationBlock<'i>,
+    ) -> Result<(), Self::Error> {
+        declaration_block.declarations =
+            self.declarations_with_fallbacks(std::mem::take(&mut declaration_block.declarations));
+        declaration_block.important_declarations = self.declarations_with_fallbacks(
+            std::mem::take(&mut declaration_block.important_declarations),
+        );
+
+        Ok(())
+    }
+}
+
+/// Converts the colors the browser targets don't support to sRGB
+struct SrgbConversion {
+    browsers: Browsers,
+    converted: bool,
+}
+
+impl<'i> Visitor<'i> for SrgbConversion {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(COLORS)
+    }
+
+    fn visit_color(&mut self, color: &mut CssColor) -> Result<(), Self::Error> {
+        if color.is_compatible(self.browsers) {
+            return Ok(());
+        }
+        if let Ok(rgb_color) = color.to_rgb() {
+            *color = rgb_color;
+            self.converted = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::{
+        printer::PrinterOptions,
+        stylesheet::{ParserOptions, StyleSheet},
+        targets::Browsers,
+    };
+
+    use super::add_color_fallbacks;
+
+    fn css_with_fallbacks(css: &str, browsers: Browsers) -> String {
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+        add_color_fallbacks(&mut style_sheet, browsers);
+
+        style_sheet
+            .to_css(PrinterOptions {
+                minify: true,
+                ..PrinterOptions::default()
+            })
+            .unwrap()
+            .code
+    }
+
+    #[test]
+    fn srgb_fallbacks() {
+        let old_safari = Browsers {
+            safari: Some(12 << 16),
+            ..Browsers::default()
+        };
+
+        assert_eq!(
+            css_with_fallbacks(
+                ".a{color:oklch(62.8% .258 29.23);border:1px solid color-mix(in oklch,red,blue)}.b{--accent:oklch(62.8% .258 29.23);background:var(--accent);color:red}",
+                old_safari,
+            ),
+            ".a{color:red;color:oklch(62.8% .258 29.23);border:1px solid #b600bd;border:1px solid oklch(53.9985% .285449 326.643)}.b{--accent:oklch(62.8% .258 29.23);background:var(--accent);color:red}"
+        );
+    }
+
+    #[test]
+    fn keep_supported_colors() {
+        let new_safari = Browsers {
+            safari: Some(17 << 16),
+            ..Browsers::default()
+        };
+
+        assert_eq!(
+            css_with_fallbacks(".a{color:oklch(62.8% .258 29.23)}", new_safari),
+            ".a{color:oklch(62.8% .258 29.23)}"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use lightningcss::{
+    printer::PrinterOptions, rules::CssRule, stylesheet::StyleSheet, traits::ToCss,
+};
+
+const DARK_COLOR_SCHEME_QUERY: &str = "(prefers-color-scheme: dark)";
+
+/// Removes the top level `@media (prefers-color-scheme: dark)` rules from a transformed style
+/// sheet and returns their css
+///
+/// Returns `None` if the style sheet has no such rules. The rules keep their `@media` rule, so the
+/// returned css only applies in dark mode even if it is injected unconditionally.
+pub fn split_dark_color_scheme(
+    style_sheet: &mut StyleSheet,
+    settings: &crate::Settings,
+) -> Result<Option<String>, String> {
+    let (dark_rules, rules) = std::mem::take(&mut style_sheet.rules.0)
+        .into_iter()
+        .partition::<Vec<_>, _>(is_dark_color_scheme_rule);
+    style_sheet.rules.0 = rules;
+
+    if dark_rules.is_empty() {
+        return Ok(None);
+    }
+
+    crate::media_sheets::rules_css(dark_rules, settings).map(Some)
+}
+
+fn is_dark_color_scheme_rule(rule: &CssRule) -> bool {
+    let CssRule::Media(media_rule) = rule else {
+        return false;
+    };
+
+    media_rule
+        .query
+        .to_css_string(PrinterOptions::default())
+        .is_ok_and(|query| query == DARK_COLOR_SCHEME_QUERY)
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::{
+        printer::PrinterOptions,
+        stylesheet::{ParserOptions, StyleSheet},
+    };
+
+    use crate::Settings;
+
+    use super::split_dark_color_scheme;
+
+    #[test]
+    fn split_dark_rules() {
+        let css = ".a{color:#000}@media (prefers-color-scheme: dark){.a{color:#fff}}@media (prefers-color-scheme: light){.a{color:#111}}@media (prefers-color-scheme: dark) and (min-width: 768px){.a{color:#eee}}";
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+
+        let dark_css = split_dark_color_scheme(&mut style_sheet, &Settings::default()).unwrap();
+
+        let css = style_sheet
+            .to_css(PrinterOptions {
+                minify: true,
+                ..PrinterOptions::default()
+            })
+            .unwrap()
+            .code;
+        assert_eq!(
+            dark_css.as_deref(),
+            Some("@media (prefers-color-scheme:dark){.a{color:#fff}}")
+        );
+        assert_eq!(
+            css,
+            ".a{color:#000}@media (prefers-color-scheme:light){.a{color:#111}}@media (prefers-color-scheme:dark) and (width>=768px){.a{color:#eee}}"
+        );
+    }
+
+    #[test]
+    fn keep_style_sheets_without_dark_rules() {
+        let mut style_sheet =
+            StyleSheet::parse(".a{color:#000}", ParserOptions::default()).unwrap();
+
+        let dark_css = split_dark_color_scheme(&mut style_sheet, &Settings::default()).unwrap();
+
+        assert_eq!(dark_css, None);
+        assert_eq!(style_sheet.rules.0.len(), 1);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use lightningcss::{
+    printer::PrinterOptions,
+    properties::{Property, PropertyId},
+    rules::style::StyleRule,
+    selector::Component,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompositionError {
+    #[error("`composes` is only supported in rules whose selectors are single class names, but was used in '{0}'")]
+    InvalidSelector(String),
+    #[error("`composes: {0}` is not supported, class names can only be composed from the same style sheet or `from global`")]
+    UnsupportedSource(String),
+    #[error(
+        "class name '{composed}' composed by '{class_name}' is not defined in the style sheet"
+    )]
+    UnknownClassName {
+        class_name: String,
+        composed: String,
+    },
+    #[error("class name '{0}' composes itself")]
+    Cycle(String),
+}
+
+/// A class name referenced by a `composes` declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComposedClassName {
+    pub name: String,
+    pub global: bool,
+}
+
+fn composes_value(property: &Property) -> Option<String> {
+    match property {
+        // Without the css modules option of lightningcss, `composes` is kept as an unparsed
+        // property
+        Property::Unparsed(unparsed) if unparsed.property_id == PropertyId::Composes => {
+            property.value_to_css_string(PrinterOptions::default()).ok()
+        }
+        _ => None,
+    }
+}
+
+fn parse_composes_value(value: &str) -> Result<Vec<ComposedClassName>, CompositionError> {
+    let mut words = value.split_whitespace();
+    let names: Vec<&str> = words.by_ref().take_while(|word| *word != "from").collect();
+    let global = match words.collect::<Vec<_>>().as_slice() {
+        [] => false,
+        ["global"] => true,
+        _ => return Err(CompositionError::UnsupportedSource(value.to_owned())),
+    };
+
+    Ok(names
+        .into_iter()
+        .map(|name| ComposedClassName {
+            name: name.to_owned(),
+            global,
+        })
+        .collect())
+}
+
+/// Removes the `composes` declarations of a style rule and returns the class name of the rule
+/// together with the class names it composes
+pub fn take_compositions(
+    style_rule: &mut StyleRule,
+) -> Result<Option<(String, Vec<ComposedClassName>)>, CompositionError> {
+    let mut values = Vec::new();
+    for declarations in [
+        &mut style_rule.declarations.declarations,
+        &mut style_rule.declarations.important_declarations,
+    ] {
+        declarations.retain(|property| match composes_value(property) {
+            Some(value) => {
+                values.push(value);
+                false
+            }
+            None => true,
+        });
+    }
+    if values.is_empty() {
+        return Ok(None);
+    }
+
+    let mut class_name = None;
+    for selector in style_rule.selectors.0.iter() {
+        match selector
+            .iter_raw_match_order()
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [Component::Class(name)] if class_name.is_none() => class_name = Some(name.to_string()),
+            _ => {
+                return Err(CompositionError::InvalidSelector(
+                    lightningcss::traits::ToCss::to_css_string(
+                        &style_rule.selectors,
+                        PrinterOptions::default(),
+                    )
+                    .unwrap_or_default(),
+                ))
+            }
+        }
+    }
+    let Some(class_name) = class_name else {
+        return Ok(None);
+    };
+
+    let mut composed = Vec::new();
+    for value in values {
+        composed.extend(parse_composes_value(&value)?);
+    }
+
+    Ok(Some((class_name, composed)))
+}
+
+fn resolve_composition(
+    class_name: &str,
+    compositions: &BTreeMap<String, Vec<ComposedClassName>>,
+    classes: &BTreeMap<String, String>,
+    resolving: &mut Vec<String>,
+    resolved: &mut Vec<String>,
+) -> Result<(), CompositionError> {
+    if resolving.iter().any(|name| name == class_name) {
+        return Err(CompositionError::Cycle(class_name.to_owned()));
+    }
+    resolving.push(class_name.to_owned());
+
+    for composed in compositions.get(class_name).into_iter().flatten() {
+        let scoped_name = match composed.global {
+            true => composed.name.clone(),
+            false => classes.get(&composed.name).cloned().ok_or_else(|| {
+                CompositionError::UnknownClassName {
+                    class_name: class_name.to_owned(),
+                    composed: composed.name.clone(),
+                }
+            })?,
+        };
+        if !resolved.contains(&scoped_name) {
+            resolved.push(scoped_name);
+        }
+        if !composed.global {
+            resolve_composition(&composed.name, compositions, classes, resolving, resolved)?;
+        }
+    }
+
+    resolving.pop();
+    Ok(())
+}
+
+/// Returns the class names of composing classes as space separated lists of their own scoped
+/// class name and the scoped class names they compose, including indirectly composed ones
+pub fn composed_class_names(
+    compositions: &BTreeMap<String, Vec<ComposedClassName>>,
+    classes: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, CompositionError> {
+    compositions
+        .keys()
+        .filter_map(|class_name| Some((class_name, classes.get(class_name)?)))
+        .map(|(class_name, scoped_name)| {
+            let mut resolved = vec![scoped_name.clone()];
+            resolve_composition(
+                class_name,
+                compositions,
+                classes,
+                &mut Vec::new(),
+                &mut resolved,
+            )?;
+
+            Ok((class_name.clone(), resolved.join(" ")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{composed_class_names, parse_composes_value, ComposedClassName, CompositionError};
+
+    fn composed(name: &str, global: bool) -> ComposedClassName {
+        ComposedClassName {
+            name: name.to_owned(),
+            global,
+        }
+    }
+
+    #[test]
+    fn parse_composes_values() {
+        assert_eq!(
+            parse_composes_value("a b").unwrap(),
+            vec![composed("a", false), composed("b", false)]
+        );
+        assert_eq!(
+            parse_composes_value("a from global").unwrap(),
+            vec![composed("a", true)]
+        );
+        assert!(matches!(
+            parse_composes_value("a from \"./other.scss\""),
+            Err(CompositionError::UnsupportedSource(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_indirect_compositions() {
+        let compositions = BTreeMap::from([
+            (String::from("a"), vec![composed("b", false)]),
+            (
+                String::from("b"),
+                vec![composed("c", false), composed("d", true)],
+            ),
+        ]);
+        let classes = BTreeMap::from([
+            (String::from("a"), String::from("class-a")),
+            (String::from("b"), String::from("class-b")),
+            (String::from("c"), String::from("class-c")),
+        ]);
+
+        assert_eq!(
+            composed_class_names(&compositions, &classes).unwrap(),
+            BTreeMap::from([
+                (String::from("a"), String::from("class-a class-b class-c d")),
+                (String::from("b"), String::from("class-b class-c d")),
+            ])
+        );
+    }
+
+    #[test]
+    fn detect_composition_cycles() {
+        let compositions = BTreeMap::from([
+            (String::from("a"), vec![composed("b", false)]),
+            (String::from("b"), vec![composed("a", false)]),
+        ]);
+        let classes = BTreeMap::from([
+            (String::from("a"), String::from("class-a")),
+            (String::from("b"), String::from("class-b")),
+        ]);
+
+        assert!(matches!(
+            composed_class_names(&compositions, &classes),
+            Err(CompositionError::Cycle(_))
+        ));
+    }
+}
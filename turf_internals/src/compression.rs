@@ -0,0 +1,65 @@
+use crate::settings::Compression;
+
+/// Compresses the css embedded by the macros with the codec of `codegen.compress`
+#[cfg(feature = "compression")]
+pub(crate) fn compress_css(css: &str, compression: Option<Compression>) -> Option<Vec<u8>> {
+    use std::io::Write;
+
+    let compressed = match compression? {
+        Compression::Brotli => {
+            let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+            writer
+                .write_all(css.as_bytes())
+                .expect("writing to a vector doesn't fail");
+            writer.into_inner()
+        }
+        Compression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder
+                .write_all(css.as_bytes())
+                .expect("writing to a vector doesn't fail");
+            encoder.finish().expect("writing to a vector doesn't fail")
+        }
+    };
+
+    Some(compressed)
+}
+
+/// Without the codecs, `Codegen::check_compression` rejects `codegen.compress` before any css is
+/// compiled
+#[cfg(not(feature = "compression"))]
+pub(crate) fn compress_css(_css: &str, _compression: Option<Compression>) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use std::io::Read;
+
+    use crate::settings::Compression;
+
+    use super::compress_css;
+
+    #[test]
+    fn compressed_css() {
+        let css = ".button{color:red}".repeat(100);
+        assert_eq!(compress_css(&css, None), None);
+
+        let brotli = compress_css(&css, Some(Compression::Brotli)).unwrap();
+        let mut decompressed = String::new();
+        brotli::Decompressor::new(brotli.as_slice(), 4096)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, css);
+        assert!(brotli.len() < css.len());
+
+        let gzip = compress_css(&css, Some(Compression::Gzip)).unwrap();
+        let mut decompressed = String::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, css);
+        assert!(gzip.len() < css.len());
+    }
+}
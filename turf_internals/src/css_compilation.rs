@@ -1,6 +1,15 @@
-use std::path::{Path, PathBuf};
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+};
 
-use crate::{path_utils, Settings, StyleSheetKind};
+use crate::{
+    breakpoints::breakpoints_export,
+    input_encoding::{InputFs, INPUT_FS},
+    path_utils,
+    settings::SassExtension,
+    theme_variables, Settings, StyleSheetKind,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum CssCompilationError {
@@ -10,6 +19,8 @@ pub enum CssCompilationError {
     Inline(#[from] Box<grass::Error>),
     #[error(transparent)]
     PathResolutionError(#[from] path_utils::PathResolutionError),
+    #[error("the scss of {style_sheet} and the files it loads are larger than the limit of {limit_kb} KB set by `max_css_size_kb` - split it into multiple style sheets or raise the limit")]
+    InputSizeLimit { style_sheet: String, limit_kb: u64 },
 }
 
 impl<P> From<(Box<grass::Error>, P)> for CssCompilationError
@@ -30,15 +41,424 @@ where
     }
 }
 
+/// Reads files like its inner file system, but appends a suffix to the compiled style sheet
+/// itself, so it is not added to the files it imports
+#[derive(Debug)]
+struct EntryFs<'a> {
+    fs: &'a dyn grass::Fs,
+    entry: &'a Path,
+    suffix: &'static str,
+}
+
+impl grass::Fs for EntryFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.fs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        let mut content = self.fs.read(path)?;
+        if path == self.entry {
+            content.extend_from_slice(self.suffix.as_bytes());
+        }
+
+        Ok(content)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        self.fs.canonicalize(path)
+    }
+}
+
+/// Reads files like [`INPUT_FS`], but hides every file other than the compiled style sheet itself
+/// and the files inside of the load paths
+///
+/// Used for untrusted input, so a style sheet can't load arbitrary files of the machine it is
+/// compiled on. Hidden files are reported as missing, since grass can't handle read errors of
+/// loaded files.
+#[derive(Debug)]
+struct AllowlistFs {
+    entry: Option<PathBuf>,
+    roots: Vec<PathBuf>,
+}
+
+impl AllowlistFs {
+    fn is_allowed(&self, path: &Path) -> bool {
+        let Ok(path) = std::fs::canonicalize(path) else {
+            return false;
+        };
+
+        self.entry.as_deref() == Some(path.as_path())
+            || self.roots.iter().any(|root| path.starts_with(root))
+    }
+}
+
+impl grass::Fs for AllowlistFs {
+    fn is_dir(&self, path: &Path) -> bool {
+        INPUT_FS.is_dir(path) && self.is_allowed(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        INPUT_FS.is_file(path) && self.is_allowed(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        if !self.is_allowed(path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "'{}' is outside of the load paths, which untrusted input can't load files from",
+                    path.display()
+                ),
+            ));
+        }
+
+        INPUT_FS.read(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        INPUT_FS.canonicalize(path)
+    }
+}
+
+/// Reads files like its inner file system, but hides the style sheets with an extension that isn't
+/// one of the `sass.extensions`, so loaded files only resolve to the enabled extensions
+#[derive(Debug)]
+struct ExtensionsFs {
+    fs: Box<dyn grass::Fs>,
+    extensions: Vec<SassExtension>,
+}
+
+impl grass::Fs for ExtensionsFs {
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        let is_enabled = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension @ ("scss" | "sass" | "css")) => self
+                .extensions
+                .iter()
+                .any(|enabled| enabled.as_str() == extension),
+            _ => true,
+        };
+
+        is_enabled && self.fs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        self.fs.read(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        self.fs.canonicalize(path)
+    }
+}
+
+/// Reads files like its inner file system, but fails once the style sheet and the files it loads
+/// are larger than `max_css_size_kb` together, so they aren't compiled
+#[derive(Debug)]
+struct SizeLimitFs<'a> {
+    fs: &'a dyn grass::Fs,
+    limit_kb: Option<u64>,
+    /// The number of bytes read so far, including the inline style sheet
+    size: Cell<u64>,
+}
+
+impl<'a> SizeLimitFs<'a> {
+    fn new(fs: &'a dyn grass::Fs, settings: &Settings, inline_size: usize) -> Self {
+        Self {
+            fs,
+            limit_kb: settings.max_css_size_kb,
+            size: Cell::new(inline_size as u64),
+        }
+    }
+
+    fn is_exceeded(&self) -> bool {
+        self.limit_kb
+            .is_some_and(|limit_kb| self.size.get() > limit_kb.saturating_mul(1024))
+    }
+
+    /// Returns the error of the exceeded limit, which replaces the result of grass, since the
+    /// files after the limit were read as empty
+    fn exceeded_error(&self, style_sheet: &StyleSheetKind) -> Option<CssCompilationError> {
+        Some(CssCompilationError::InputSizeLimit {
+            style_sheet: style_sheet.to_string(),
+            limit_kb: self.limit_kb.filter(|_| self.is_exceeded())?,
+        })
+    }
+}
+
+impl grass::Fs for SizeLimitFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.fs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        // grass panics on io errors of imported files, so files that exceed the limit are read as
+        // empty instead, and the exceeded limit is reported once grass returns
+        if self.is_exceeded() {
+            return Ok(Vec::new());
+        }
+        let content = self.fs.read(path)?;
+        self.size.set(self.size.get() + content.len() as u64);
+        if self.is_exceeded() {
+            return Ok(Vec::new());
+        }
+
+        Ok(content)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        self.fs.canonicalize(path)
+    }
+}
+
+/// Returns the file system the style sheet is read with, which only allows loading files inside
+/// of the load paths for untrusted input and only resolves the enabled extensions
+fn input_fs(
+    entry: Option<&Path>,
+    settings: &Settings,
+) -> Result<Box<dyn grass::Fs>, CssCompilationError> {
+    let fs: Box<dyn grass::Fs> = match settings.untrusted_input {
+        true => Box::new(AllowlistFs {
+            entry: entry.map(Path::to_path_buf),
+            roots: settings.canonicalized_load_paths()?,
+        }),
+        false => Box::new(InputFs),
+    };
+
+    Ok(match settings.sass.has_all_extensions() {
+        true => fs,
+        false => Box::new(ExtensionsFs {
+            fs,
+            extensions: settings.sass.extensions.clone(),
+        }),
+    })
+}
+
+/// Returns the syntax grass parses a style sheet with, which is the `sass.input_syntax` setting or
+/// otherwise determined by the extension of the file like grass does
+fn style_sheet_syntax(style_sheet: &StyleSheetKind, settings: &Settings) -> grass::InputSyntax {
+    if let Some(input_syntax) = settings.sass.input_syntax {
+        return input_syntax.into();
+    }
+
+    match style_sheet {
+        StyleSheetKind::File(path) => match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("sass") => grass::InputSyntax::Sass,
+            Some("css") => grass::InputSyntax::Css,
+            _ => grass::InputSyntax::Scss,
+        },
+        StyleSheetKind::Inline(_) => grass::InputSyntax::Scss,
+    }
+}
+
 pub fn compile_style_sheet(
     style_sheet: &StyleSheetKind,
     settings: &Settings,
 ) -> Result<String, CssCompilationError> {
+    let options: grass::Options = settings.clone().try_into()?;
+    let suffix = breakpoints_export(style_sheet_syntax(style_sheet, settings));
+
+    // The size of the style sheet and the files it loads is checked while grass loads them, before
+    // they are compiled
     Ok(match style_sheet {
-        StyleSheetKind::File(ref path) => grass::from_path(path, &settings.clone().try_into()?)
-            .map_err(|e| CssCompilationError::from((e, path.clone())))?,
-        StyleSheetKind::Inline(ref style_sheet) => {
-            grass::from_string(style_sheet, &settings.clone().try_into()?)?
+        StyleSheetKind::File(ref path) => {
+            let input_fs = input_fs(Some(path), settings)?;
+            let entry_fs = EntryFs {
+                fs: input_fs.as_ref(),
+                entry: path,
+                suffix,
+            };
+            let size_limit_fs = SizeLimitFs::new(&entry_fs, settings, 0);
+            let result = grass::from_path(path, &options.fs(&size_limit_fs));
+            if let Some(error) = size_limit_fs.exceeded_error(style_sheet) {
+                return Err(error);
+            }
+            result.map_err(|e| CssCompilationError::from((e, path.clone())))?
+        }
+        StyleSheetKind::Inline(ref inline_style_sheet) => {
+            let input_fs = input_fs(None, settings)?;
+            let size_limit_fs =
+                SizeLimitFs::new(input_fs.as_ref(), settings, inline_style_sheet.len());
+            if let Some(error) = size_limit_fs.exceeded_error(style_sheet) {
+                return Err(error);
+            }
+            let result = grass::from_string(
+                format!("{inline_style_sheet}{suffix}"),
+                &options.fs(&size_limit_fs),
+            );
+            if let Some(error) = size_limit_fs.exceeded_error(style_sheet) {
+                return Err(error);
+            }
+            result?
         }
     })
 }
+
+/// Compiles the style sheet that exports the top level variables of the style sheet at the path
+pub fn compile_theme_export(
+    path: &Path,
+    settings: &Settings,
+) -> Result<String, CssCompilationError> {
+    // The export is written in SCSS, whichever syntax the theme itself uses
+    let options =
+        grass::Options::try_from(settings.clone())?.input_syntax(grass::InputSyntax::Scss);
+    let input_fs = input_fs(Some(path), settings)?;
+
+    grass::from_string(
+        theme_variables::theme_export(path),
+        &options.fs(input_fs.as_ref()),
+    )
+    .map_err(|e| CssCompilationError::from((e, path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        settings::{Sass, SassExtension, SassInputSyntax},
+        Settings, StyleSheetKind,
+    };
+
+    use super::{compile_style_sheet, CssCompilationError};
+
+    #[test]
+    fn untrusted_input_imports() {
+        let dir = std::env::temp_dir().join("turf_untrusted_input_imports");
+        let load_path = dir.join("themes");
+        std::fs::create_dir_all(&load_path).unwrap();
+        std::fs::write(dir.join("secret.scss"), ".secret { color: red; }").unwrap();
+        std::fs::write(load_path.join("shared.scss"), ".shared { color: blue; }").unwrap();
+        let entry = load_path.join("theme.scss");
+
+        let settings = Settings {
+            load_paths: vec![load_path.clone()],
+            untrusted_input: true,
+            ..Default::default()
+        };
+
+        std::fs::write(&entry, "@import \"shared\";").unwrap();
+        let css = compile_style_sheet(&StyleSheetKind::File(entry.clone()), &settings).unwrap();
+        assert!(css.contains(".shared"));
+
+        std::fs::write(&entry, "@import \"../secret\";").unwrap();
+        assert!(compile_style_sheet(&StyleSheetKind::File(entry.clone()), &settings).is_err());
+        let trusted_settings = Settings {
+            untrusted_input: false,
+            ..settings
+        };
+        let css = compile_style_sheet(&StyleSheetKind::File(entry), &trusted_settings).unwrap();
+        assert!(css.contains(".secret"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn indented_syntax() {
+        let dir = std::env::temp_dir().join("turf_indented_syntax");
+        std::fs::create_dir_all(&dir).unwrap();
+        let entry = dir.join("style.sass");
+        std::fs::write(
+            &entry,
+            "$turf-breakpoints: (md: 768px)\n.button\n  color: red",
+        )
+        .unwrap();
+
+        let css = compile_style_sheet(&StyleSheetKind::File(entry), &Settings::default()).unwrap();
+        assert!(css.contains(".button {\n  color: red;\n}"));
+        assert!(css.contains("turf-breakpoint-md: 768px"));
+
+        let settings = Settings {
+            sass: Sass {
+                input_syntax: Some(SassInputSyntax::Indented),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let css = compile_style_sheet(
+            &StyleSheetKind::Inline(String::from(".button\n  color: red")),
+            &settings,
+        )
+        .unwrap();
+        assert!(css.contains(".button {\n  color: red;\n}"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn index_files_and_extensions() {
+        let dir = std::env::temp_dir().join("turf_index_files_and_extensions");
+        std::fs::create_dir_all(dir.join("buttons")).unwrap();
+        std::fs::write(dir.join("buttons/_index.scss"), ".button { color: red; }").unwrap();
+        std::fs::write(dir.join("_links.css"), ".link { color: blue; }").unwrap();
+        let entry = dir.join("style.scss");
+        std::fs::write(&entry, "@use \"buttons\";\n@use \"links\";").unwrap();
+
+        let css = compile_style_sheet(&StyleSheetKind::File(entry.clone()), &Settings::default())
+            .unwrap();
+        assert!(css.contains(".button"));
+        assert!(css.contains(".link"));
+
+        let settings = Settings {
+            sass: Sass {
+                extensions: vec![SassExtension::Scss],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(compile_style_sheet(&StyleSheetKind::File(entry), &settings).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn input_size_limit() {
+        let dir = std::env::temp_dir().join("turf_input_size_limit");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("_large.scss"),
+            format!(".large {{ content: \"{}\"; }}", "a".repeat(1024)),
+        )
+        .unwrap();
+        let entry = dir.join("style.scss");
+        std::fs::write(&entry, "@use \"large\";").unwrap();
+
+        let settings = Settings {
+            max_css_size_kb: Some(1),
+            ..Default::default()
+        };
+
+        // The loaded file is larger than the limit, so the style sheet isn't compiled
+        assert!(matches!(
+            compile_style_sheet(&StyleSheetKind::File(entry.clone()), &settings),
+            Err(CssCompilationError::InputSizeLimit { limit_kb: 1, .. })
+        ));
+        let inline_style_sheet =
+            StyleSheetKind::Inline(format!(".a {{ content: \"{}\"; }}", "a".repeat(1024)));
+        assert!(matches!(
+            compile_style_sheet(&inline_style_sheet, &settings),
+            Err(CssCompilationError::InputSizeLimit { limit_kb: 1, .. })
+        ));
+
+        let settings = Settings {
+            max_css_size_kb: Some(2),
+            ..settings
+        };
+        assert!(compile_style_sheet(&StyleSheetKind::File(entry), &settings).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use std::convert::Infallible;
+
+use lightningcss::{
+    error::{Error, ParserError},
+    printer::PrinterOptions,
+    properties::{custom::CustomPropertyName, custom::TokenList, custom::TokenOrValue, Property},
+    rules::CssRule,
+    stylesheet::StyleSheet,
+    traits::ToCss,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+/// Keywords every property accepts, which lightningcss doesn't parse for some properties
+const CSS_WIDE_KEYWORDS: [&str; 5] = ["inherit", "initial", "unset", "revert", "revert-layer"];
+
+/// Returns a message for each warning of the parser and each declaration with an unknown property
+/// or an invalid value, with its line and column in the compiled css
+///
+/// lightningcss keeps unknown properties and values it can't parse as they are, so these are
+/// found by visiting the declarations. Values with `var()` or `env()` references are only known at
+/// runtime and are never reported as invalid, neither are vendor prefixed properties.
+pub fn css_warnings(
+    style_sheet: &mut StyleSheet,
+    parser_warnings: Vec<Error<ParserError>>,
+) -> Vec<String> {
+    let mut warnings: Vec<String> = parser_warnings
+        .into_iter()
+        .map(|warning| match warning.loc {
+            Some(loc) => format!(
+                "{} (line {}, column {})",
+                warning.kind,
+                loc.line + 1,
+                loc.column
+            ),
+            None => warning.kind.to_string(),
+        })
+        .collect();
+
+    let mut collector = DeclarationWarnings::default();
+    style_sheet
+        .visit(&mut collector)
+        .expect("css visitor never fails");
+    warnings.extend(collector.warnings);
+
+    warnings
+}
+
+#[derive(Default)]
+struct DeclarationWarnings {
+    warnings: Vec<String>,
+}
+
+impl<'i> Visitor<'i> for DeclarationWarnings {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        if let CssRule::Style(style_rule) = rule {
+            let selectors = style_rule
+                .selectors
+                .to_css_string(PrinterOptions::default())
+                .unwrap_or_default();
+
+            for declaration in style_rule
+                .declarations
+                .declarations
+                .iter()
+                .chain(&style_rule.declarations.important_declarations)
+            {
+                if let Some(warning) = declaration_warning(declaration) {
+                    self.warnings.push(format!(
+                        "{warning} in `{selectors}` (line {}, column {})",
+                        style_rule.loc.line + 1,
+                        style_rule.loc.column
+                    ));
+                }
+            }
+        }
+
+        rule.visit_children(self)
+    }
+}
+
+fn declaration_warning(declaration: &Property) -> Option<String> {
+    match declaration {
+        Property::Custom(custom) => match &custom.name {
+            CustomPropertyName::Unknown(name) if !name.starts_with('-') => {
+                Some(format!("unknown property `{}`", name.as_ref()))
+            }
+            _ => None,
+        },
+        Property::Unparsed(unparsed) => {
+            let name = unparsed.property_id.name();
+            let value = declaration
+                .value_to_css_string(PrinterOptions::default())
+                .unwrap_or_default();
+            if has_runtime_value(&unparsed.value) || is_css_wide_keyword(&value) {
+                return None;
+            }
+            if name.starts_with('-') {
+                return None;
+            }
+
+            Some(format!("invalid value `{value}` of property `{name}`"))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether the value references `var()` or `env()`
+fn has_runtime_value(value: &TokenList) -> bool {
+    value.0.iter().any(|token| match token {
+        TokenOrValue::Var(_) | TokenOrValue::Env(_) | TokenOrValue::UnresolvedColor(_) => true,
+        TokenOrValue::Function(function) => has_runtime_value(&function.arguments),
+        _ => false,
+    })
+}
+
+fn is_css_wide_keyword(value: &str) -> bool {
+    CSS_WIDE_KEYWORDS
+        .iter()
+        .any(|keyword| value.trim().eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::css_warnings;
+
+    fn warnings_of(css: &str) -> Vec<String> {
+        let parser_warnings = Arc::new(RwLock::new(Vec::new()));
+        let mut style_sheet = StyleSheet::parse(
+            css,
+            ParserOptions {
+                warnings: Some(parser_warnings.clone()),
+                ..ParserOptions::default()
+            },
+        )
+        .unwrap();
+        let parser_warnings = parser_warnings.read().unwrap().clone();
+
+        css_warnings(&mut style_sheet, parser_warnings)
+    }
+
+    #[test]
+    fn declaration_warnings() {
+        assert_eq!(
+            warnings_of(".a {\n  colr: red;\n  width: 10 pixels;\n}\n.b {\n  color: blu;\n}"),
+            vec![
+                "unknown property `colr` in `.a` (line 1, column 1)",
+                "invalid value `10 pixels` of property `width` in `.a` (line 1, column 1)",
+                "invalid value `blu` of property `color` in `.b` (line 5, column 1)",
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_declarations() {
+        assert_eq!(
+            warnings_of(
+                ".a { color: var(--accent); width: calc(100% - env(safe-area-inset-left)); --size: 1x; -webkit-box-reflect: below; display: inherit; }"
+            ),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{css_compilation::CssCompilationError, file_output, StyleSheetKind};
+
+/// The environment variable holding the path of the SARIF file diagnostics are mirrored into
+pub(crate) const DIAGNOSTICS_SARIF_ENV: &str = "TURF_DIAGNOSTICS_SARIF";
+
+/// Closes the results array and the run of the SARIF file, every result is inserted before it
+const SARIF_END: &str = "\n]}]}\n";
+
+/// The position of a diagnostic in a style sheet file
+///
+/// Lines and columns start at 1. Diagnostics that can't be narrowed down to a position, e.g.
+/// because they are found in the compiled css, are located by the file path only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub position: Option<(usize, usize)>,
+}
+
+impl SourceLocation {
+    pub fn file(path: PathBuf) -> Self {
+        Self {
+            path,
+            position: None,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{}:{line}:{column}", self.path.display()),
+            None => write!(f, "{}", self.path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    Warning,
+    Error,
+}
+
+impl DiagnosticLevel {
+    fn sarif_level(self) -> &'static str {
+        match self {
+            DiagnosticLevel::Warning => "warning",
+            DiagnosticLevel::Error => "error",
+        }
+    }
+}
+
+/// Returns the location of a style sheet file, inline style sheets have no location
+pub fn style_sheet_location(style_sheet: &StyleSheetKind) -> Option<SourceLocation> {
+    match style_sheet {
+        StyleSheetKind::File(path) => Some(SourceLocation::file(
+            crate::path_utils::canonicalize(path).unwrap_or_else(|_| path.clone()),
+        )),
+        StyleSheetKind::Inline(_) => None,
+    }
+}
+
+impl crate::Error {
+    /// Returns the position of the SCSS that caused the error, if the error knows it
+    pub fn location(&self) -> Option<SourceLocation> {
+        let crate::Error::CssCompilation(error) = self else {
+            return None;
+        };
+
+        match error {
+            CssCompilationError::File(error, path) => Some(
+                sass_error_location(error).unwrap_or_else(|| SourceLocation::file(path.clone())),
+            ),
+            CssCompilationError::Inline(error) => sass_error_location(error),
+            CssCompilationError::PathResolutionError(error) => {
+                Some(SourceLocation::file(error.path.clone()))
+            }
+            CssCompilationError::InputSizeLimit { .. } => None,
+        }
+    }
+}
+
+fn sass_error_location(error: &grass::Error) -> Option<SourceLocation> {
+    let grass::ErrorKind::ParseError { loc, .. } = error.clone().kind() else {
+        return None;
+    };
+    let path = Path::new(loc.file.name());
+
+    path.is_file().then(|| SourceLocation {
+        path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+        position: Some((loc.begin.line + 1, loc.begin.column + 1)),
+    })
+}
+
+/// Mirrors an error that fails a style sheet into the SARIF file
+///
+/// The location of the error itself is preferred over the location of the style sheet.
+pub fn record_error(error: &crate::Error, style_sheet_location: Option<&SourceLocation>) {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(current_error) = source {
+        message.push_str(&format!("\nCaused by: {current_error}"));
+        source = current_error.source();
+    }
+
+    let location = error.location();
+    record_diagnostic(
+        DiagnosticLevel::Error,
+        &message,
+        location.as_ref().or(style_sheet_location),
+    );
+}
+
+/// Mirrors a diagnostic into the SARIF file if the `TURF_DIAGNOSTICS_SARIF` environment variable
+/// is set
+///
+/// Results are inserted into the existing file, so the diagnostics of all style sheets and crates
+/// of a build end up in the same file. Failing to write the file doesn't fail the build.
+pub fn record_diagnostic(level: DiagnosticLevel, message: &str, location: Option<&SourceLocation>) {
+    let Some(sarif_path) = std::env::var_os(DIAGNOSTICS_SARIF_ENV) else {
+        return;
+    };
+    let sarif_path = PathBuf::from(sarif_path);
+
+    if let Err(error) = append_sarif_result(&sarif_path, &sarif_result(level, message, location)) {
+        println!(
+            "🌱 turf [WARN]: failed to write diagnostics to {} - {error}",
+            sarif_path.display()
+        );
+    }
+}
+
+/// Inserts a result into the SARIF file, which is locked like the output files since the crates
+/// of a workspace are compiled in parallel
+fn append_sarif_result(sarif_path: &Path, result: &str) -> std::io::Result<()> {
+    if let Some(parent) = sarif_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _lock = file_output::lock_output_file(sarif_path).map_err(std::io::Error::other)?;
+    let mut sarif_file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(sarif_path)?;
+
+    let mut content = String::new();
+    sarif_file.read_to_string(&mut content)?;
+
+    match content.strip_suffix(SARIF_END) {
+        Some(start) if !start.trim().is_empty() => {
+            sarif_file.set_len(start.len() as u64)?;
+            sarif_file.seek(SeekFrom::End(0))?;
+            sarif_file.write_all(format!(",\n{result}{SARIF_END}").as_bytes())?;
+        }
+        _ => {
+            sarif_file.set_len(0)?;
+            sarif_file.rewind()?;
+            sarif_file.write_all(format!("{}{result}{SARIF_END}", sarif_start()).as_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn sarif_start() -> String {
+    format!(
+        r#"{{"version":"2.1.0","$schema":"https://json.schemastore.org/sarif-2.1.0.json","runs":[{{"tool":{{"driver":{{"name":"turf","informationUri":"https://github.com/lukidoescode/turf","version":{}}}}},"results":[
+"#,
+        serde_json::json!(env!("CARGO_PKG_VERSION"))
+    )
+}
+
+fn sarif_result(
+    level: DiagnosticLevel,
+    message: &str,
+    location: Option<&SourceLocation>,
+) -> String {
+    let mut result = serde_json::json!({
+        "ruleId": "turf",
+        "level": level.sarif_level(),
+        "message": { "text": message },
+    });
+    if let Some(location) = location {
+        let mut physical_location = serde_json::json!({
+            "artifactLocation": { "uri": file_uri(&location.path) },
+        });
+        if let Some((line, column)) = location.position {
+            physical_location["region"] = serde_json::json!({
+                "startLine": line,
+                "startColumn": column,
+            });
+        }
+        result["locations"] = serde_json::json!([{ "physicalLocation": physical_location }]);
+    }
+
+    result.to_string()
+}
+
+/// Converts a path to a `file` URI, percent-encoding every byte that isn't allowed in a URI path
+fn file_uri(path: &Path) -> String {
+    let mut uri_path = String::new();
+    for &byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'\\' if cfg!(windows) => uri_path.push('/'),
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@' => uri_path.push(byte as char),
+            byte => uri_path.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    match uri_path.starts_with('/') {
+        true => format!("file://{uri_path}"),
+        false => format!("file:///{uri_path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        append_sarif_result, file_uri, sarif_result, sass_error_location, DiagnosticLevel,
+        SourceLocation,
+    };
+
+    #[test]
+    fn sarif_results() {
+        let location = SourceLocation {
+            path: PathBuf::from("/project/style.scss"),
+            position: Some((3, 5)),
+        };
+
+        let result = |level, message, location| -> serde_json::Value {
+            serde_json::from_str(&sarif_result(level, message, location)).unwrap()
+        };
+
+        assert_eq!(
+            result(DiagnosticLevel::Warning, "a \"warning\"\n", Some(&location)),
+            serde_json::json!({
+                "ruleId": "turf",
+                "level": "warning",
+                "message": { "text": "a \"warning\"\n" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "file:///project/style.scss" },
+                        "region": { "startLine": 3, "startColumn": 5 },
+                    },
+                }],
+            })
+        );
+        assert_eq!(
+            result(DiagnosticLevel::Error, "an error", None),
+            serde_json::json!({
+                "ruleId": "turf",
+                "level": "error",
+                "message": { "text": "an error" },
+            })
+        );
+    }
+
+    #[test]
+    fn file_uris() {
+        assert_eq!(
+            file_uri(&PathBuf::from("/project/my styles/ä#1%?.scss")),
+            "file:///project/my%20styles/%C3%A4%231%25%3F.scss"
+        );
+    }
+
+    #[test]
+    fn append_sarif_results() {
+        let sarif_path = std::env::temp_dir().join("turf_append_sarif_results.sarif");
+        let _ = std::fs::remove_file(&sarif_path);
+
+        append_sarif_result(&sarif_path, "{\"first\":1}").unwrap();
+        append_sarif_result(&sarif_path, "{\"second\":2}").unwrap();
+
+        let sarif = std::fs::read_to_string(&sarif_path).unwrap();
+        std::fs::remove_file(&sarif_path).unwrap();
+        assert!(sarif.starts_with(r#"{"version":"2.1.0""#));
+        assert!(sarif.ends_with("\"results\":[\n{\"first\":1},\n{\"second\":2}\n]}]}\n"));
+    }
+
+    #[test]
+    fn sass_error_locations() {
+        let path = std::env::temp_dir().join("turf_sass_error_locations.scss");
+        std::fs::write(&path, ".test {\n  color: red;\n  width: 1px + 1em;\n}\n").unwrap();
+
+        let error = grass::from_path(&path, &grass::Options::default()).unwrap_err();
+        let location = sass_error_location(&error).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(location.position, Some((3, 10)));
+    }
+}
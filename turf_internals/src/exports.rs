@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+static EXPORT_BLOCK: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+static EXPORT_NUMBER: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+
+/// Removes the top level `:export` blocks from the compiled css and collects their declarations
+///
+/// The values are kept as they were compiled, e.g. `768px` or `#ff0000`.
+pub fn extract_exports(css: &str) -> (String, BTreeMap<String, String>) {
+    let export_block = EXPORT_BLOCK.get_or_init(|| {
+        Regex::new(r"(?m)^:export\s*\{([^}]*)\}\s*").expect("export block regex is valid")
+    });
+
+    let mut exports = BTreeMap::new();
+    for captures in export_block.captures_iter(css) {
+        for declaration in captures[1].split(';') {
+            if let Some((name, value)) = declaration.split_once(':') {
+                let (name, value) = (name.trim(), value.trim());
+                if !name.is_empty() {
+                    exports.insert(name.to_owned(), value.to_owned());
+                }
+            }
+        }
+    }
+
+    if exports.is_empty() {
+        return (css.to_owned(), exports);
+    }
+
+    (export_block.replace_all(css, "").into_owned(), exports)
+}
+
+/// Parses the number of an exported value like `768px`, `1.5` or `50%`, ignoring its unit
+pub fn parse_export_number(value: &str) -> Option<f64> {
+    let export_number = EXPORT_NUMBER.get_or_init(|| {
+        Regex::new(r"^([+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?)(?:[a-zA-Z]+|%)?$")
+            .expect("export number regex is valid")
+    });
+
+    export_number
+        .captures(value)
+        .and_then(|captures| captures[1].parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{extract_exports, parse_export_number};
+
+    #[test]
+    fn extract_export_blocks() {
+        let css = ".test {\n  color: red;\n}\n\n:export {\n  breakpointMd: 768px;\n  primary: #ff0000;\n}\n";
+        let (css, exports) = extract_exports(css);
+
+        assert_eq!(css, ".test {\n  color: red;\n}\n\n");
+        assert_eq!(
+            exports,
+            BTreeMap::from([
+                (String::from("breakpointMd"), String::from("768px")),
+                (String::from("primary"), String::from("#ff0000")),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(parse_export_number("768px"), Some(768.0));
+        assert_eq!(parse_export_number("-1.5"), Some(-1.5));
+        assert_eq!(parse_export_number(".5em"), Some(0.5));
+        assert_eq!(parse_export_number("50%"), Some(50.0));
+        assert_eq!(parse_export_number("#ff0000"), None);
+        assert_eq!(parse_export_number("1px solid"), None);
+    }
+}
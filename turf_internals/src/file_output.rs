@@ -1,106 +1,1243 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
     fs::{create_dir_all, File},
     io::Write,
-    path::PathBuf,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
 };
 
-use crate::{settings::FileOutput, StyleSheetKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-static DIRS_RESET: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+use crate::{
+    ast_json::AstRule,
+    link::{Fragment, FragmentsFile, FRAGMENTS_FILE_EXTENSION},
+    path_utils,
+    registry::{self, RegistryError},
+    settings::{FileOutput, OutputPathBase, SeparateCssFileNaming},
+    StyleSheetKind,
+};
+
+/// The name of the file in the store of a crate listing the files the crate wrote to the output
+/// directories
+const OWNED_FILES_FILE_NAME: &str = "owned_files";
+
+static RESET_CRATES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+static PREPARED_OUTPUT_DIRS: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+static NO_GLOBAL_FILE_ANNOTATION: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+static GLOBAL_CSS_FILES: Mutex<BTreeMap<(String, PathBuf), GlobalCssFile>> =
+    Mutex::new(BTreeMap::new());
 
 #[derive(Debug, thiserror::Error)]
 #[error("error writing css file '{0}' - {1}")]
 pub struct CssFileWriteError(PathBuf, std::io::Error);
 
-fn reset_file_output(output_paths: &FileOutput) -> Result<(), CssFileWriteError> {
-    if let Some(path) = &output_paths.global_css_file_path {
-        if let Err(error) = std::fs::remove_file(path) {
-            match error.kind() {
-                std::io::ErrorKind::NotFound => {}
-                _ => Err(CssFileWriteError(path.clone(), error))?,
+#[derive(Debug, thiserror::Error)]
+pub enum FileOutputError {
+    #[error(transparent)]
+    Write(#[from] CssFileWriteError),
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error("Could not read internal state")]
+    Mutex,
+    #[error("error generating the source map of the css file '{0}' - {1}")]
+    SourceMap(PathBuf, parcel_sourcemap::SourceMapError),
+    #[error("error serializing the output manifest '{0}' - {1}")]
+    OutputManifest(PathBuf, serde_json::Error),
+    #[error("`file_output.relative_to = \"out_dir\"` requires `OUT_DIR`, which cargo only sets for packages with a build script")]
+    MissingOutDir,
+    #[error("the css artifact '{}' can't be embedded with `include_str!` because its path isn't valid UTF-8", .0.display())]
+    NonUtf8ArtifactPath(PathBuf),
+}
+
+/// The css of the style sheets a crate writes to a global css file, the css of style sheets that
+/// compile to the same css is only written once
+///
+/// The entries of every crate writing to the file are stored in the target directory, and the file
+/// is rewritten from the entries of all crates whenever a style sheet is written to it. The entries
+/// of a crate start out empty in every compilation of the crate, so the css of style sheets that
+/// aren't compiled anymore, e.g. of deleted modules, is pruned from the file by a rebuild of the
+/// crate. The entries are ordered by their priority, entries with the same priority keep the order
+/// of the crates and the order the macros are expanded in.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GlobalCssFile {
+    entries: Vec<GlobalCssEntry>,
+}
+
+/// A line of the `file_output.global_css_ranges_path` file
+#[derive(Serialize)]
+struct GlobalCssRange<'a> {
+    style_sheet: &'a str,
+    start: u64,
+    end: u64,
+}
+
+/// A line of the `file_output.ast_json_path` file
+#[derive(Serialize)]
+struct AstJsonLine<'a> {
+    style_sheet: String,
+    rules: &'a [AstRule],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GlobalCssEntry {
+    hash: String,
+    /// The css of the style sheets, wrapped in their cascade layer
+    css: String,
+    layer: Option<String>,
+    priority: i32,
+    /// The sources of the style sheets that compiled to the css
+    style_sheets: BTreeSet<String>,
+}
+
+/// The placement of a style sheet in the global css files
+#[derive(Debug, Default)]
+struct GlobalCssPlacement {
+    priority: i32,
+    /// The cascade layer the css of the style sheet is wrapped in
+    layer: Option<String>,
+}
+
+impl GlobalCssFile {
+    /// Sets the css of a style sheet
+    ///
+    /// The css a style sheet compiled to before, e.g. when rust-analyzer expands its macro again,
+    /// is removed unless another style sheet compiled to the same css. The byte ranges of the other
+    /// style sheets change if the css is placed before them or a cascade layer is added to the
+    /// layer statement, so they are only listed by the ranges file written with the global css
+    /// file.
+    fn set_style_sheet_css(
+        &mut self,
+        style_sheet: &str,
+        css: &str,
+        placement: &GlobalCssPlacement,
+    ) {
+        let css = match &placement.layer {
+            Some(layer) => format!("@layer {layer} {{\n{css}}}\n"),
+            None => css.to_owned(),
+        };
+        let hash = crate::hashing::style_sheet_css_hash(&css);
+        let is_entry_of_css =
+            |entry: &GlobalCssEntry| entry.hash == hash && entry.css.as_str() == css;
+
+        for entry in self.entries.iter_mut() {
+            if !is_entry_of_css(entry) {
+                entry.style_sheets.remove(style_sheet);
             }
+        }
+        self.entries.retain(|entry| !entry.style_sheets.is_empty());
+
+        match self.entries.iter_mut().find(|entry| is_entry_of_css(entry)) {
+            Some(entry) => {
+                entry.priority = entry.priority.min(placement.priority);
+                entry.style_sheets.insert(style_sheet.to_owned());
+            }
+            None => self.entries.push(GlobalCssEntry {
+                hash: hash.clone(),
+                css: css.clone(),
+                layer: placement.layer.clone(),
+                priority: placement.priority,
+                style_sheets: BTreeSet::from([style_sheet.to_owned()]),
+            }),
         };
+        self.entries.sort_by_key(|entry| entry.priority);
+    }
 
-        create_dir_all(path.parent().expect("global css file path has parent dir"))
-            .map_err(|error| CssFileWriteError(path.clone(), error))?;
+    /// Merges the entries of the crates writing to the same file, the css that style sheets of
+    /// different crates compiled to is only written once as well
+    fn merged(crate_files: Vec<GlobalCssFile>) -> GlobalCssFile {
+        let mut merged = GlobalCssFile::default();
+        let mut entry_indices = BTreeMap::<(String, String), usize>::new();
+
+        for entry in crate_files.into_iter().flat_map(|file| file.entries) {
+            match entry_indices.get(&(entry.hash.clone(), entry.css.clone())) {
+                Some(&index) => {
+                    let merged_entry = &mut merged.entries[index];
+                    merged_entry.priority = merged_entry.priority.min(entry.priority);
+                    merged_entry.style_sheets.extend(entry.style_sheets);
+                }
+                None => {
+                    entry_indices.insert(
+                        (entry.hash.clone(), entry.css.clone()),
+                        merged.entries.len(),
+                    );
+                    merged.entries.push(entry);
+                }
+            }
+        }
+        merged.entries.sort_by_key(|entry| entry.priority);
+
+        merged
     }
-    if let Some(path) = &output_paths.separate_css_files_path {
-        if let Err(error) = std::fs::remove_dir_all(path) {
+
+    /// The byte ranges the entries occupy in the file
+    fn entry_ranges(&self) -> impl Iterator<Item = (&GlobalCssEntry, Range<u64>)> {
+        let mut start = self.layer_statement().len() as u64;
+        self.entries.iter().map(move |entry| {
+            let range = start..start + entry.css.len() as u64;
+            start = range.end;
+            (entry, range)
+        })
+    }
+
+    /// Declares the cascade layers ordered by priority and name, so the cascade doesn't depend on
+    /// the order the macros are expanded in
+    fn layer_statement(&self) -> String {
+        let mut layers = self
+            .entries
+            .iter()
+            .filter_map(|entry| Some((entry.priority, entry.layer.as_deref()?)))
+            .collect::<Vec<_>>();
+        layers.sort();
+        let mut layer_names = Vec::new();
+        for (_, layer) in layers {
+            if !layer_names.contains(&layer) {
+                layer_names.push(layer);
+            }
+        }
+
+        match layer_names.is_empty() {
+            true => String::new(),
+            false => format!("@layer {};\n", layer_names.join(", ")),
+        }
+    }
+
+    /// Lists the byte range of every style sheet in the file as JSON Lines
+    fn ranges_json_lines(&self) -> String {
+        let mut lines = String::new();
+        for (entry, range) in self.entry_ranges() {
+            for style_sheet in &entry.style_sheets {
+                let line = serde_json::to_string(&GlobalCssRange {
+                    style_sheet,
+                    start: range.start,
+                    end: range.end,
+                })
+                .expect("ranges can be serialized");
+                lines.push_str(&format!("{line}\n"));
+            }
+        }
+        lines
+    }
+
+    fn css(&self) -> String {
+        let mut css = self.layer_statement();
+        css.extend(self.entries.iter().map(|entry| entry.css.as_str()));
+        css
+    }
+}
+
+/// The directory of the build's artifacts, `CARGO_TARGET_DIR` or the `target` directory of the
+/// workspace
+fn target_dir() -> PathBuf {
+    if let Some(target_dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return PathBuf::from(target_dir);
+    }
+
+    let manifest_dir = path_utils::manifest_dir();
+    let workspace_dir = manifest_dir
+        .ancestors()
+        .find(|directory| directory.join("Cargo.lock").is_file())
+        .unwrap_or(&manifest_dir);
+    workspace_dir.join("target")
+}
+
+/// The directory the entries of every crate writing to the global files are stored in
+fn crate_outputs_dir() -> PathBuf {
+    target_dir().join("turf").join("crate_outputs")
+}
+
+/// Identifies the crate being compiled, the library, binaries, tests and examples of a package are
+/// compiled separately and can write different style sheets
+pub(crate) fn crate_key() -> String {
+    let target_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| crate_name());
+    let kind = match std::env::var_os("CARGO_BIN_NAME") {
+        Some(_) => "bin",
+        None => "lib",
+    };
+    format!("{}.{target_name}.{kind}", crate_name())
+}
+
+/// Resolves the output paths relative to the directory set by `file_output.relative_to`
+pub(crate) fn resolved_output_paths(
+    mut output_paths: FileOutput,
+) -> Result<FileOutput, FileOutputError> {
+    let base_dir = match output_paths.relative_to {
+        OutputPathBase::Working => return Ok(output_paths),
+        OutputPathBase::Target => target_dir(),
+        OutputPathBase::Out => std::env::var_os("OUT_DIR")
+            .map(PathBuf::from)
+            .ok_or(FileOutputError::MissingOutDir)?,
+    };
+
+    for path in [
+        &mut output_paths.global_css_file_path,
+        &mut output_paths.global_dark_css_file_path,
+        &mut output_paths.separate_css_files_path,
+        &mut output_paths.partitioned_css_files_path,
+        &mut output_paths.ast_json_path,
+        &mut output_paths.global_css_ranges_path,
+        &mut output_paths.fragments_path,
+        &mut output_paths.typescript_definitions_path,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *path = base_dir.join(&path);
+    }
+
+    Ok(output_paths)
+}
+
+/// Removes the outputs of the previous compilation of the current crate, once per crate since a
+/// proc macro server, e.g. the one of rust-analyzer, expands the macros of several crates
+fn reset_crate_outputs() -> Result<(), FileOutputError> {
+    let crate_key = crate_key();
+    let mut reset_crates = RESET_CRATES.lock().map_err(|_| FileOutputError::Mutex)?;
+    if reset_crates.contains(&crate_key) {
+        return Ok(());
+    }
+
+    remove_crate_outputs(&crate_outputs_dir(), &crate_key)?;
+    reset_crates.insert(crate_key);
+
+    Ok(())
+}
+
+/// Removes the files a crate wrote to the output directories and its entries of the global files
+///
+/// Only the outputs of the crate are removed, the output directories and global files are shared
+/// with the other crates of the workspace, whose entries are merged into the global files by the
+/// next write.
+fn remove_crate_outputs(
+    crate_outputs_dir: &Path,
+    crate_key: &str,
+) -> Result<(), CssFileWriteError> {
+    let store_dir = crate_outputs_dir.join(crate_key);
+    let owned_files =
+        std::fs::read_to_string(store_dir.join(OWNED_FILES_FILE_NAME)).unwrap_or_default();
+    for path in owned_files.lines().map(PathBuf::from) {
+        let _lock = lock_output_file(&path)?;
+        if let Err(error) = std::fs::remove_file(&path) {
             match error.kind() {
                 std::io::ErrorKind::NotFound => {}
                 _ => Err(CssFileWriteError(path.clone(), error))?,
             }
         };
+    }
+
+    if let Err(error) = std::fs::remove_dir_all(&store_dir) {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => {}
+            _ => Err(CssFileWriteError(store_dir, error))?,
+        }
+    };
+
+    Ok(())
+}
+
+/// Creates the output directories of a style sheet the first time they are used, style sheets with
+/// overrides can write to other directories than the rest of the crate
+fn prepare_output_dirs(output_paths: &FileOutput) -> Result<(), FileOutputError> {
+    let mut prepared_dirs = PREPARED_OUTPUT_DIRS
+        .lock()
+        .map_err(|_| FileOutputError::Mutex)?;
 
-        create_dir_all(path).map_err(|error| CssFileWriteError(path.clone(), error))?;
+    let file_dirs = [
+        &output_paths.global_css_file_path,
+        &output_paths.global_dark_css_file_path,
+        &output_paths.ast_json_path,
+        &output_paths.global_css_ranges_path,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|path| path.parent());
+    let dirs = [
+        &output_paths.separate_css_files_path,
+        &output_paths.partitioned_css_files_path,
+        &output_paths.fragments_path,
+        &output_paths.typescript_definitions_path,
+    ]
+    .into_iter()
+    .flatten()
+    .map(PathBuf::as_path);
+
+    for directory in file_dirs.chain(dirs) {
+        if !prepared_dirs.contains(directory) {
+            create_dir_all(directory)
+                .map_err(|error| CssFileWriteError(directory.to_path_buf(), error))?;
+            prepared_dirs.insert(directory.to_path_buf());
+        }
     }
 
     Ok(())
 }
 
+/// Records a file the current crate writes to a directory shared with the other crates of the
+/// workspace, e.g. a separate css file, so the next compilation of the crate only removes its own
+/// files from the directory
+pub(crate) fn own_output_file(path: &Path) -> Result<(), CssFileWriteError> {
+    record_owned_file(path, &crate_outputs_dir(), &crate_key())
+}
+
+fn record_owned_file(
+    path: &Path,
+    crate_outputs_dir: &Path,
+    crate_key: &str,
+) -> Result<(), CssFileWriteError> {
+    let path =
+        std::path::absolute(path).map_err(|error| CssFileWriteError(path.to_path_buf(), error))?;
+    let path = path.to_string_lossy();
+    let store_dir = crate_outputs_dir.join(crate_key);
+    let owned_files_path = store_dir.join(OWNED_FILES_FILE_NAME);
+    let _lock = lock_output_file(&owned_files_path)?;
+
+    let owned_files = std::fs::read_to_string(&owned_files_path).unwrap_or_default();
+    if owned_files.lines().any(|owned_file| owned_file == path) {
+        return Ok(());
+    }
+
+    create_dir_all(&store_dir).map_err(|error| CssFileWriteError(store_dir, error))?;
+    File::options()
+        .create(true)
+        .append(true)
+        .open(&owned_files_path)
+        .and_then(|mut file| writeln!(file, "{path}"))
+        .map_err(|error| CssFileWriteError(owned_files_path, error))
+}
+
+pub(crate) fn source_path_file_name(path: &Path) -> OsString {
+    let relative_path = path_utils::relative_to_manifest_dir(path);
+
+    let mut file_name = OsString::new();
+    for component in relative_path.components() {
+        if let Component::Normal(component) = component {
+            if !file_name.is_empty() {
+                file_name.push("_");
+            }
+            file_name.push(component);
+        }
+    }
+
+    file_name
+}
+
+/// Names a separate css file with the `file_output.separate_css_file_name_template` template
+fn templated_file_name(template: &str, style: &str, style_sheet: &StyleSheetKind) -> String {
+    let file_stem = match style_sheet {
+        StyleSheetKind::File(path) => path
+            .file_stem()
+            .expect("current css file exists")
+            .to_string_lossy()
+            .into_owned(),
+        StyleSheetKind::Inline(style_sheet) => {
+            format!("{:x?}", xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes()))
+        }
+    };
+    let content_hash = crate::hashing::style_sheet_css_hash(style);
+
+    template
+        .replace("<file_stem>", &file_stem)
+        .replace("<content_hash>", &content_hash)
+        .replace("<content_hash_short>", &content_hash[..8])
+        .replace("<crate>", &crate_name())
+}
+
+/// Names the cascade layer of a style sheet in the global css files with the
+/// `file_output.global_css_layer` template
+fn global_css_layer_name(template: &str, style_sheet: &StyleSheetKind) -> String {
+    let file_stem = match style_sheet {
+        StyleSheetKind::File(path) => path
+            .file_stem()
+            .expect("current css file exists")
+            .to_string_lossy()
+            .chars()
+            .map(|char| match char.is_ascii_alphanumeric() || char == '_' {
+                true => char,
+                false => '-',
+            })
+            .collect(),
+        // Layer names can't start with a digit like the hash
+        StyleSheetKind::Inline(style_sheet) => format!(
+            "inline-{:x?}",
+            xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes())
+        ),
+    };
+
+    template
+        .replace("<file_stem>", &file_stem)
+        .replace("<crate>", &crate_name())
+}
+
+/// Writes the css of a style sheet to its separate css file and returns the name of the file
 fn append_to_separate_file(
     style: &str,
     mut separate_files_dir: PathBuf,
+    naming: SeparateCssFileNaming,
+    name_template: Option<&str>,
     style_sheet: &StyleSheetKind,
-) -> Result<(), CssFileWriteError> {
-    match style_sheet {
-        StyleSheetKind::File(path) => {
-            separate_files_dir.push(path.file_name().expect("current css file exists"));
+) -> Result<String, FileOutputError> {
+    match (style_sheet, name_template) {
+        (_, Some(name_template)) => {
+            separate_files_dir.push(templated_file_name(name_template, style, style_sheet))
+        }
+        (StyleSheetKind::File(path), None) => {
+            match naming {
+                SeparateCssFileNaming::FileName => {
+                    separate_files_dir.push(path.file_name().expect("current css file exists"))
+                }
+                SeparateCssFileNaming::SourcePath => {
+                    separate_files_dir.push(source_path_file_name(path))
+                }
+            }
             separate_files_dir.set_extension("css");
         }
-        StyleSheetKind::Inline(style_sheet) => {
+        (StyleSheetKind::Inline(style_sheet), None) => {
             let hash = xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes());
-            separate_files_dir.push(&format!("{hash:x?}.css"));
+            separate_files_dir.push(format!("{hash:x?}.css"));
         }
     };
 
-    let mut output_file = File::options()
+    registry::register_separate_output_file(&separate_files_dir, style_sheet)?;
+    own_output_file(&separate_files_dir)?;
+    let file_name = separate_files_dir
+        .file_name()
+        .expect("separate css file has a file name")
+        .to_string_lossy()
+        .into_owned();
+
+    append_to_global_file(style, &separate_files_dir)?;
+
+    Ok(file_name)
+}
+
+/// Appends to a file, which is locked while appending, so the content appended by another
+/// compiler process isn't interleaved with it
+fn append_to_global_file(style: &str, global_file_path: &Path) -> Result<(), CssFileWriteError> {
+    let _lock = lock_output_file(global_file_path)?;
+
+    File::options()
         .create(true)
         .append(true)
-        .open(&separate_files_dir)
-        .map_err(|error| CssFileWriteError(separate_files_dir.clone(), error))?;
+        .open(global_file_path)
+        .and_then(|mut file| file.write_all(style.as_bytes()))
+        .map_err(|error| CssFileWriteError(global_file_path.to_path_buf(), error))
+}
+
+/// Writes the css of a style sheet to a global css file
+///
+/// The ranges file lists the byte ranges of all style sheets in the global css file, which is
+/// rewritten with it since the ranges of other style sheets can change.
+fn write_to_global_file(
+    style: &str,
+    global_file_path: &Path,
+    style_sheet: &StyleSheetKind,
+    placement: &GlobalCssPlacement,
+    ranges_path: Option<&Path>,
+) -> Result<(), FileOutputError> {
+    let mut global_css_files = match GLOBAL_CSS_FILES.lock() {
+        Err(_) => return Err(FileOutputError::Mutex),
+        Ok(val) => val,
+    };
+
+    // A proc macro server, e.g. the one of rust-analyzer, expands the macros of several crates
+    let crate_key = crate_key();
+    let global_css_file = global_css_files
+        .entry((crate_key.clone(), global_file_path.to_path_buf()))
+        .or_default();
+    global_css_file.set_style_sheet_css(
+        &registry::style_sheet_source(style_sheet),
+        style,
+        placement,
+    );
+    write_merged_global_file(
+        global_css_file,
+        global_file_path,
+        &crate_outputs_dir(),
+        &crate_key,
+        ranges_path,
+    )?;
+
+    Ok(())
+}
+
+/// Stores the entries of a crate and rewrites a global file with the entries of all crates
+/// writing to it
+///
+/// The file is locked while the entries are stored and merged, so the entries stored by another
+/// compiler process aren't lost. Crates that don't write to the file anymore keep their entries
+/// until their stores are removed by `cargo clean`.
+fn write_merged_global_file(
+    global_css_file: &GlobalCssFile,
+    global_file_path: &Path,
+    crate_outputs_dir: &Path,
+    crate_key: &str,
+    ranges_path: Option<&Path>,
+) -> Result<(), CssFileWriteError> {
+    let _lock = lock_output_file(global_file_path)?;
 
-    output_file
-        .write_all(style.as_bytes())
-        .map_err(|error| CssFileWriteError(separate_files_dir, error))?;
+    let store =
+        serde_json::to_string(global_css_file).expect("global css entries can be serialized");
+    let crate_files = stored_crate_outputs(global_file_path, &store, crate_outputs_dir, crate_key)?
+        .iter()
+        // Stores that can't be read, e.g. ones written by another version of turf, are left out
+        // until their crate is compiled again
+        .filter_map(|store| serde_json::from_str(store).ok())
+        .collect();
+
+    let merged = GlobalCssFile::merged(crate_files);
+    replace_file(global_file_path, &merged.css())?;
+    if let Some(ranges_path) = ranges_path {
+        write_atomically(ranges_path, &merged.ranges_json_lines())?;
+    }
 
     Ok(())
 }
 
-fn append_to_global_file(style: &str, global_file_path: &PathBuf) -> Result<(), CssFileWriteError> {
-    let mut global_css_file = File::options()
+/// Stores the part of an output file written by the current crate and returns the parts stored by
+/// all crates writing to the file, ordered by crate
+///
+/// The output file has to be locked by the caller until it is rewritten with the merged parts.
+pub(crate) fn stored_outputs_of_crates(
+    output_path: &Path,
+    store: &str,
+) -> Result<Vec<String>, CssFileWriteError> {
+    stored_crate_outputs(output_path, store, &crate_outputs_dir(), &crate_key())
+}
+
+fn stored_crate_outputs(
+    output_path: &Path,
+    store: &str,
+    crate_outputs_dir: &Path,
+    crate_key: &str,
+) -> Result<Vec<String>, CssFileWriteError> {
+    let store_file_name = format!("{}.json", output_file_id(output_path)?);
+    let store_dir = crate_outputs_dir.join(crate_key);
+    create_dir_all(&store_dir).map_err(|error| CssFileWriteError(store_dir.clone(), error))?;
+    replace_file(&store_dir.join(&store_file_name), store)?;
+
+    let mut crate_dirs = std::fs::read_dir(crate_outputs_dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|error| CssFileWriteError(crate_outputs_dir.to_path_buf(), error))?;
+    crate_dirs.sort();
+
+    // Crates that don't write to the file have no store
+    Ok(crate_dirs
+        .iter()
+        .filter_map(|crate_dir| std::fs::read_to_string(crate_dir.join(&store_file_name)).ok())
+        .collect())
+}
+
+/// Writes a file to a temporary file next to it and renames it, so the file is never read while
+/// it is partially written, and locks it while doing so
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), CssFileWriteError> {
+    let _lock = lock_output_file(path)?;
+
+    replace_file(path, contents)
+}
+
+/// Locks an output file until the returned lock file is dropped
+///
+/// The crates of a workspace are compiled by parallel compiler processes, which can write to the
+/// same output files. The output file itself can't be locked because it is replaced on every
+/// write, so the lock file is named after its absolute path and kept in the temporary directory
+/// instead of next to the css files.
+pub(crate) fn lock_output_file(path: &Path) -> Result<File, CssFileWriteError> {
+    let lock_path = std::env::temp_dir().join(format!("turf-{}.lock", output_file_id(path)?));
+
+    let lock_file = File::options()
         .create(true)
-        .append(true)
-        .open(global_file_path)
-        .map_err(|error| CssFileWriteError(global_file_path.clone(), error))?;
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|error| CssFileWriteError(lock_path.clone(), error))?;
+    lock_file
+        .lock()
+        .map_err(|error| CssFileWriteError(lock_path, error))?;
+
+    Ok(lock_file)
+}
+
+/// Identifies an output file by the hash of its absolute path
+fn output_file_id(path: &Path) -> Result<String, CssFileWriteError> {
+    let absolute_path =
+        std::path::absolute(path).map_err(|error| CssFileWriteError(path.to_path_buf(), error))?;
 
-    global_css_file
-        .write_all(style.as_bytes())
-        .map_err(|error| CssFileWriteError(global_file_path.clone(), error))?;
+    Ok(format!(
+        "{:016x}",
+        xxhash_rust::xxh3::xxh3_64(absolute_path.as_os_str().as_encoded_bytes())
+    ))
+}
+
+/// Replaces a file by renaming a temporary file next to it, the file has to be locked by the caller
+pub(crate) fn replace_file(path: &Path, contents: &str) -> Result<(), CssFileWriteError> {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(format!(".{}.tmp", std::process::id()));
+    let temporary_path = PathBuf::from(temporary_path);
+
+    std::fs::write(&temporary_path, contents)
+        .map_err(|error| CssFileWriteError(path.to_path_buf(), error))?;
+    std::fs::rename(&temporary_path, path)
+        .map_err(|error| CssFileWriteError(path.to_path_buf(), error))
+}
+
+/// Writes the source map of a separate css file next to it and links it from the css file
+pub fn write_source_map(
+    separate_files_dir: &Path,
+    css_file_name: &str,
+    source_map: &mut parcel_sourcemap::SourceMap,
+) -> Result<(), FileOutputError> {
+    let css_file_path = separate_files_dir.join(css_file_name);
+    let source_map_file_name = format!("{css_file_name}.map");
+    let source_map_path = separate_files_dir.join(&source_map_file_name);
+
+    let source_map_json = source_map
+        .to_json(None)
+        .map_err(|error| FileOutputError::SourceMap(css_file_path.clone(), error))?;
+    own_output_file(&source_map_path)?;
+    write_atomically(&source_map_path, &source_map_json)?;
+    append_to_global_file(
+        &crate::source_maps::source_mapping_url_comment(&source_map_file_name),
+        &css_file_path,
+    )?;
 
     Ok(())
 }
 
-pub fn perform_css_file_output(
-    output_paths: FileOutput,
+fn write_to_partition_files(
     style: &str,
-    style_sheet_kind: &StyleSheetKind,
+    partitioned_files_dir: &Path,
+    partitions: &[String],
+    style_sheet: &StyleSheetKind,
+) -> Result<(), FileOutputError> {
+    for partition in partitions {
+        write_to_global_file(
+            style,
+            &partitioned_files_dir.join(format!("{partition}.css")),
+            style_sheet,
+            &GlobalCssPlacement::default(),
+            None,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn crate_name() -> String {
+    std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("crate"))
+}
+
+/// The fragments file of the crate being compiled, named by its key so the library, binaries and
+/// tests of a package don't replace each other's fragments
+fn fragments_file_path(fragments_dir: &Path) -> PathBuf {
+    fragments_dir.join(format!("{}.{FRAGMENTS_FILE_EXTENSION}", crate_key()))
+}
+
+fn append_to_fragments_file(
+    style: &str,
+    version: &str,
+    fragments_dir: &Path,
+    layer: Option<String>,
+    style_sheet: &StyleSheetKind,
 ) -> Result<(), CssFileWriteError> {
-    if DIRS_RESET.get().is_none() {
-        reset_file_output(&output_paths)?;
+    let fragment = Fragment {
+        style_sheet: registry::style_sheet_source(style_sheet),
+        layer,
+        hash: crate::hashing::style_sheet_css_hash(style),
+        version: version.to_owned(),
+        css: style.to_owned(),
+    };
+    // Every fragment is written as a separate `[[fragments]]` table, so the file can be appended
+    // to by every macro invocation
+    let fragments_file = toml::to_string(&FragmentsFile {
+        fragments: vec![fragment],
+    })
+    .expect("fragments can be serialized");
+
+    let fragments_file_path = fragments_file_path(fragments_dir);
+    own_output_file(&fragments_file_path)?;
+    append_to_global_file(&fragments_file, &fragments_file_path)?;
+
+    Ok(())
+}
+
+fn write_to_ast_json_file(
+    rules: &[AstRule],
+    ast_json_path: &Path,
+    style_sheet: &StyleSheetKind,
+) -> Result<(), FileOutputError> {
+    // Each style sheet is written as a separate line, which is merged into the file like the css
+    // of a global css file
+    let line = serde_json::to_string(&AstJsonLine {
+        style_sheet: registry::style_sheet_source(style_sheet),
+        rules,
+    })
+    .expect("rules can be serialized");
+
+    write_to_global_file(
+        &format!("{line}\n"),
+        ast_json_path,
+        style_sheet,
+        &GlobalCssPlacement::default(),
+        None,
+    )?;
+
+    Ok(())
+}
+
+/// Returns whether a style sheet is excluded from the global css file by a
+/// `/* turf:no-global-file */` annotation
+pub fn is_excluded_from_global_file(css: &str) -> bool {
+    NO_GLOBAL_FILE_ANNOTATION
+        .get_or_init(|| {
+            Regex::new(r"/\*\s*turf:no-global-file\s*\*/")
+                .expect("global file annotation regex is valid")
+        })
+        .is_match(css)
+}
+
+/// Writes a value of a style sheet that changes with its css, e.g. the compiled css, to its
+/// artifact in the directory and returns the absolute path of the artifact
+///
+/// Artifacts are named after the source of their style sheet and the extension, so edits of a style
+/// sheet change the content of the same files. Unchanged artifacts are not written again. The path
+/// is embedded with `include_str!`, so it has to be valid UTF-8.
+pub fn write_css_artifact(
+    directory: &Path,
+    extension: &str,
+    content: &str,
+    style_sheet_kind: &StyleSheetKind,
+) -> Result<String, FileOutputError> {
+    let mut path = path_utils::manifest_dir();
+    path.push(directory);
+    create_dir_all(&path).map_err(|error| CssFileWriteError(path.clone(), error))?;
 
-        DIRS_RESET
-            .set(())
-            .expect("internal turf state has already been set, but should be empty");
+    let source = registry::style_sheet_source(style_sheet_kind);
+    path.push(format!(
+        "{:016x}.{extension}",
+        xxhash_rust::xxh3::xxh3_64(source.as_bytes())
+    ));
+    let Some(path_str) = path.to_str() else {
+        return Err(FileOutputError::NonUtf8ArtifactPath(path));
+    };
+
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(content) {
+        write_atomically(&path, content)?;
     }
 
+    Ok(path_str.to_owned())
+}
+
+/// The locations a style sheet is written to by the configured output files
+#[derive(Debug, Default)]
+pub struct CssFileOutput {
+    /// The name of the separate css file of the style sheet
+    pub css_file_name: Option<String>,
+}
+
+/// The compiled css of a style sheet as it is written to the output files
+#[derive(Debug, Clone, Copy)]
+pub struct StyleSheetCss<'a> {
+    pub css: &'a str,
+    pub dark_css: Option<&'a str>,
+    /// The `STYLE_SHEET_VERSION` of the style sheet, which is written to the fragments
+    pub version: &'a str,
+}
+
+/// Writes the compiled css to the configured output files and returns the locations of the style
+/// sheet in them
+pub fn perform_css_file_output(
+    output_paths: FileOutput,
+    style_sheet_css: StyleSheetCss,
+    style_sheet_kind: &StyleSheetKind,
+    partitions: &[String],
+    ast_json: Option<&[AstRule]>,
+    excluded_from_global_file: bool,
+) -> Result<CssFileOutput, FileOutputError> {
+    let StyleSheetCss {
+        css: style,
+        dark_css: dark_style,
+        version,
+    } = style_sheet_css;
+    reset_crate_outputs()?;
+    prepare_output_dirs(&output_paths)?;
+
+    let mut css_file_name = None;
     if let Some(output_path) = output_paths.separate_css_files_path {
-        append_to_separate_file(style, output_path, style_sheet_kind)?;
+        css_file_name = Some(append_to_separate_file(
+            style,
+            output_path,
+            output_paths.separate_css_file_naming,
+            output_paths.separate_css_file_name_template.as_deref(),
+            style_sheet_kind,
+        )?);
     }
 
+    let placement = GlobalCssPlacement {
+        priority: output_paths.global_css_priority,
+        layer: output_paths
+            .global_css_layer
+            .as_deref()
+            .map(|template| global_css_layer_name(template, style_sheet_kind)),
+    };
+
     if let Some(output_path) = output_paths.global_css_file_path {
-        append_to_global_file(style, &output_path)?;
+        if !excluded_from_global_file {
+            write_to_global_file(
+                style,
+                &output_path,
+                style_sheet_kind,
+                &placement,
+                output_paths.global_css_ranges_path.as_deref(),
+            )?;
+        }
     }
 
-    Ok(())
+    if let (Some(output_path), Some(dark_style)) =
+        (output_paths.global_dark_css_file_path, dark_style)
+    {
+        if !excluded_from_global_file {
+            write_to_global_file(dark_style, &output_path, style_sheet_kind, &placement, None)?;
+        }
+    }
+
+    if let Some(output_path) = output_paths.partitioned_css_files_path {
+        write_to_partition_files(style, &output_path, partitions, style_sheet_kind)?;
+    }
+
+    if let Some(fragments_dir) = output_paths.fragments_path {
+        if !excluded_from_global_file {
+            let style = match dark_style {
+                Some(dark_style) => format!("{style}{dark_style}"),
+                None => style.to_owned(),
+            };
+            append_to_fragments_file(
+                &style,
+                version,
+                &fragments_dir,
+                output_paths.fragments_layer,
+                style_sheet_kind,
+            )?;
+        }
+    }
+
+    if let (Some(output_path), Some(rules)) = (output_paths.ast_json_path, ast_json) {
+        write_to_ast_json_file(rules, &output_path, style_sheet_kind)?;
+    }
+
+    Ok(CssFileOutput { css_file_name })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        settings::{FileOutput, OutputPathBase},
+        StyleSheetKind,
+    };
+
+    use super::{
+        append_to_global_file, global_css_layer_name, is_excluded_from_global_file,
+        prepare_output_dirs, record_owned_file, remove_crate_outputs, resolved_output_paths,
+        target_dir, templated_file_name, write_css_artifact, write_merged_global_file,
+        write_to_global_file, GlobalCssFile, GlobalCssPlacement,
+    };
+
+    #[test]
+    fn global_file_annotation() {
+        assert!(is_excluded_from_global_file(
+            "/* turf:no-global-file */\n.test {\n  color: red;\n}"
+        ));
+        assert!(!is_excluded_from_global_file(
+            "/* turf:partition print */\n.test {\n  color: red;\n}"
+        ));
+    }
+
+    #[test]
+    fn separate_css_file_name_template() {
+        let file_name = templated_file_name(
+            "<crate>-<file_stem>.<content_hash_short>.css",
+            ".a{color:red}",
+            &StyleSheetKind::File("src/button.scss".into()),
+        );
+
+        assert_eq!(
+            file_name,
+            format!(
+                "turf_internals-button.{}.css",
+                &crate::hashing::style_sheet_css_hash(".a{color:red}")[..8]
+            )
+        );
+    }
+
+    #[test]
+    fn deduplicated_global_css() {
+        let mut global_css_file = GlobalCssFile::default();
+        let placement = GlobalCssPlacement::default();
+
+        global_css_file.set_style_sheet_css("a.scss", ".a{}", &placement);
+        global_css_file.set_style_sheet_css("b.scss", ".bb{}", &placement);
+        global_css_file.set_style_sheet_css("c.scss", ".a{}", &placement);
+        assert_eq!(global_css_file.css(), ".a{}.bb{}");
+        assert_eq!(
+            global_css_file.ranges_json_lines(),
+            "{\"style_sheet\":\"a.scss\",\"start\":0,\"end\":4}\n{\"style_sheet\":\"c.scss\",\"start\":0,\"end\":4}\n{\"style_sheet\":\"b.scss\",\"start\":4,\"end\":9}\n"
+        );
+
+        // The css of `a.scss` is kept for `c.scss`, the css `b.scss` compiled to before is removed
+        global_css_file.set_style_sheet_css("a.scss", ".aa{}", &placement);
+        global_css_file.set_style_sheet_css("b.scss", ".b{}", &placement);
+        assert_eq!(global_css_file.css(), ".a{}.aa{}.b{}");
+    }
+
+    #[test]
+    fn ordered_global_css() {
+        let mut global_css_file = GlobalCssFile::default();
+        let layer = |name: &str, priority| GlobalCssPlacement {
+            priority,
+            layer: Some(String::from(name)),
+        };
+
+        global_css_file.set_style_sheet_css("b.scss", ".b{}", &layer("b", 0));
+        global_css_file.set_style_sheet_css("a.scss", ".a{}", &layer("a", 0));
+        global_css_file.set_style_sheet_css("reset.scss", ".r{}", &layer("reset", -1));
+
+        let css = global_css_file.css();
+        assert_eq!(
+            css,
+            "@layer reset, a, b;\n@layer reset {\n.r{}}\n@layer b {\n.b{}}\n@layer a {\n.a{}}\n"
+        );
+        assert_eq!(&css[20..41], "@layer reset {\n.r{}}\n");
+        assert_eq!(
+            global_css_file.ranges_json_lines(),
+            "{\"style_sheet\":\"reset.scss\",\"start\":20,\"end\":41}\n{\"style_sheet\":\"b.scss\",\"start\":41,\"end\":58}\n{\"style_sheet\":\"a.scss\",\"start\":58,\"end\":75}\n"
+        );
+        assert_eq!(
+            global_css_layer_name(
+                "components.<file_stem>",
+                &StyleSheetKind::File("src/button.module.scss".into())
+            ),
+            "components.button-module"
+        );
+    }
+
+    #[test]
+    fn concurrent_appends() {
+        let directory = std::env::temp_dir().join("turf_concurrent_appends");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("style_sheets.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let threads = (0..8)
+            .map(|index| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    append_to_global_file(&format!("{}\n", index.to_string().repeat(1000)), &path)
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        let mut lines = contents.lines().collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(
+            lines,
+            (0..8)
+                .map(|index| index.to_string().repeat(1000))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn output_paths_relative_to_target_dir() {
+        let file_output = FileOutput {
+            global_css_file_path: Some(PathBuf::from("turf/global.css")),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolved_output_paths(file_output.clone())
+                .unwrap()
+                .global_css_file_path,
+            Some(PathBuf::from("turf/global.css"))
+        );
+
+        let file_output = FileOutput {
+            relative_to: OutputPathBase::Target,
+            ..file_output
+        };
+        assert_eq!(
+            resolved_output_paths(file_output)
+                .unwrap()
+                .global_css_file_path,
+            Some(target_dir().join("turf/global.css"))
+        );
+        if std::env::var_os("CARGO_TARGET_DIR").is_none() {
+            assert!(target_dir().ends_with("target"));
+            assert!(target_dir().parent().unwrap().join("Cargo.lock").is_file());
+        }
+    }
+
+    #[test]
+    fn global_css_file_rewrite() {
+        let directory = std::env::temp_dir().join("turf_global_css_file");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("global.css");
+        std::fs::write(&path, ".stale{}").unwrap();
+
+        write_to_global_file(
+            ".a{color:red}",
+            &path,
+            &StyleSheetKind::File("src/a.scss".into()),
+            &GlobalCssPlacement::default(),
+            None,
+        )
+        .unwrap();
+        write_to_global_file(
+            ".a{color:red}",
+            &path,
+            &StyleSheetKind::File("src/b.scss".into()),
+            &GlobalCssPlacement::default(),
+            None,
+        )
+        .unwrap();
+
+        let css = std::fs::read_to_string(&path).unwrap();
+        let files = std::fs::read_dir(&directory).unwrap().count();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(css, ".a{color:red}");
+        assert_eq!(files, 1);
+    }
+
+    #[test]
+    fn global_css_file_of_several_crates() {
+        let directory = std::env::temp_dir().join("turf_global_css_crates");
+        let crate_outputs_dir = directory.join("crate_outputs");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("global.css");
+        let ranges_path = directory.join("global_css_ranges.jsonl");
+        let placement = GlobalCssPlacement::default();
+
+        let mut first_crate = GlobalCssFile::default();
+        first_crate.set_style_sheet_css("src/a.scss", ".a{}", &placement);
+        first_crate.set_style_sheet_css("src/shared.scss", ".s{}", &placement);
+        write_merged_global_file(
+            &first_crate,
+            &path,
+            &crate_outputs_dir,
+            "first.first.lib",
+            Some(&ranges_path),
+        )
+        .unwrap();
+
+        let mut second_crate = GlobalCssFile::default();
+        second_crate.set_style_sheet_css("src/shared.scss", ".s{}", &placement);
+        second_crate.set_style_sheet_css(
+            "src/reset.scss",
+            ".r{}",
+            &GlobalCssPlacement {
+                priority: -1,
+                layer: None,
+            },
+        );
+        write_merged_global_file(
+            &second_crate,
+            &path,
+            &crate_outputs_dir,
+            "second.second.lib",
+            Some(&ranges_path),
+        )
+        .unwrap();
+
+        // Rebuilding the first crate keeps the css of the second crate
+        first_crate.set_style_sheet_css("src/a.scss", ".aa{}", &placement);
+        write_merged_global_file(
+            &first_crate,
+            &path,
+            &crate_outputs_dir,
+            "first.first.lib",
+            Some(&ranges_path),
+        )
+        .unwrap();
+
+        let css = std::fs::read_to_string(&path).unwrap();
+        let ranges = std::fs::read_to_string(&ranges_path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(css, ".r{}.s{}.aa{}");
+        assert_eq!(
+            ranges,
+            "{\"style_sheet\":\"src/reset.scss\",\"start\":0,\"end\":4}\n{\"style_sheet\":\"src/shared.scss\",\"start\":4,\"end\":8}\n{\"style_sheet\":\"src/a.scss\",\"start\":8,\"end\":13}\n"
+        );
+    }
+
+    #[test]
+    fn output_dirs_of_overrides() {
+        let directory = std::env::temp_dir().join("turf_output_dirs_of_overrides");
+        let file_output = |separate_css_files_path: &str| FileOutput {
+            global_css_file_path: Some(directory.join("global").join("styles.css")),
+            separate_css_files_path: Some(directory.join(separate_css_files_path)),
+            ..Default::default()
+        };
+
+        // A style sheet with an override writes to another directory than the first style sheet
+        prepare_output_dirs(&file_output("css")).unwrap();
+        prepare_output_dirs(&file_output("vendor")).unwrap();
+        let created = ["global", "css", "vendor"].map(|name| directory.join(name).is_dir());
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(created, [true; 3]);
+    }
+
+    #[test]
+    fn owned_files_of_several_crates() {
+        let directory = std::env::temp_dir().join("turf_owned_files_crates");
+        let crate_outputs_dir = directory.join("crate_outputs");
+        let separate_files_dir = directory.join("css");
+        std::fs::create_dir_all(&separate_files_dir).unwrap();
+
+        for (crate_key, file_name) in [
+            ("first.first.lib", "a.css"),
+            ("first.first.lib", "a.css.map"),
+            ("second.second.lib", "b.css"),
+        ] {
+            let path = separate_files_dir.join(file_name);
+            std::fs::write(&path, "").unwrap();
+            record_owned_file(&path, &crate_outputs_dir, crate_key).unwrap();
+        }
+
+        // Rebuilding the first crate keeps the files of the second crate
+        remove_crate_outputs(&crate_outputs_dir, "first.first.lib").unwrap();
+        let mut file_names = std::fs::read_dir(&separate_files_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        file_names.sort();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(file_names, ["b.css"]);
+    }
+
+    #[test]
+    fn css_artifacts() {
+        let directory = std::env::temp_dir().join("turf_css_artifacts");
+        let style_sheet = StyleSheetKind::File("src/style.scss".into());
+
+        let path = write_css_artifact(&directory, "css", ".a{color:red}", &style_sheet).unwrap();
+        let edited_path =
+            write_css_artifact(&directory, "css", ".a{color:blue}", &style_sheet).unwrap();
+
+        let css = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(std::path::Path::new(&path).is_absolute());
+        assert_eq!(path, edited_path);
+        assert_eq!(css, ".a{color:blue}");
+    }
 }
@@ -0,0 +1,227 @@
+use std::{collections::HashSet, convert::Infallible};
+
+use lightningcss::{
+    printer::PrinterOptions,
+    properties::Property,
+    rules::CssRule,
+    stylesheet::StyleSheet,
+    traits::ToCss,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+
+/// Selector parts that indicate a rule styles an interaction or widget state
+const STATE_SELECTORS: &[&str] = &[
+    ":hover",
+    ":focus",
+    ":active",
+    ":checked",
+    ":disabled",
+    ":indeterminate",
+    "[aria-",
+];
+
+/// A declaration that breaks or weakens the rendering in forced colors mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForcedColorsIssue {
+    pub selector: String,
+    pub declaration: String,
+    pub reason: &'static str,
+    pub fallback: &'static str,
+}
+
+impl std::fmt::Display for ForcedColorsIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' in '{}' - {}",
+            self.declaration, self.selector, self.reason
+        )
+    }
+}
+
+#[derive(Default)]
+struct ForcedColorsAudit {
+    issues: Vec<ForcedColorsIssue>,
+    covered_selectors: HashSet<String>,
+    in_forced_colors_media: bool,
+}
+
+fn audit_property(property: &Property, selector: &str) -> Option<(&'static str, &'static str)> {
+    let name = property.property_id().name().to_owned();
+    let value = property
+        .value_to_css_string(PrinterOptions::default())
+        .unwrap_or_default();
+    let is_state_selector = STATE_SELECTORS
+        .iter()
+        .any(|state_selector| selector.contains(state_selector));
+
+    match name.as_str() {
+        "outline" | "outline-style" | "outline-width"
+            if matches!(value.as_str(), "none" | "0" | "0px") =>
+        {
+            Some((
+                "removing the outline hides focus indicators in forced colors mode, use a transparent outline instead",
+                "outline: 2px solid CanvasText;",
+            ))
+        }
+        "background-image" if is_state_selector && value != "none" => Some((
+            "background images are removed in forced colors mode and can't indicate a state",
+            "border: 2px solid Highlight;",
+        )),
+        "background"
+            if is_state_selector && (value.contains("url(") || value.contains("gradient(")) =>
+        {
+            Some((
+                "background images are removed in forced colors mode and can't indicate a state",
+                "border: 2px solid Highlight;",
+            ))
+        }
+        "box-shadow" if is_state_selector && value != "none" => Some((
+            "box shadows are removed in forced colors mode and can't indicate a state",
+            "outline: 2px solid Highlight;",
+        )),
+        _ => None,
+    }
+}
+
+impl<'i> Visitor<'i> for ForcedColorsAudit {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        match rule {
+            CssRule::Media(media_rule) => {
+                let query = media_rule
+                    .query
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default();
+
+                let was_in_forced_colors_media = self.in_forced_colors_media;
+                self.in_forced_colors_media |= query.contains("forced-colors");
+                rule.visit_children(self)?;
+                self.in_forced_colors_media = was_in_forced_colors_media;
+
+                return Ok(());
+            }
+            CssRule::Style(style_rule) => {
+                let selector = style_rule
+                    .selectors
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default();
+
+                if self.in_forced_colors_media {
+                    self.covered_selectors.insert(selector);
+                } else {
+                    let declarations = style_rule
+                        .declarations
+                        .declarations
+                        .iter()
+                        .chain(style_rule.declarations.important_declarations.iter());
+
+                    for property in declarations {
+                        if let Some((reason, fallback)) = audit_property(property, &selector) {
+                            self.issues.push(ForcedColorsIssue {
+                                selector: selector.clone(),
+                                declaration: property
+                                    .to_css_string(false, PrinterOptions::default())
+                                    .unwrap_or_default(),
+                                reason,
+                                fallback,
+                            });
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        rule.visit_children(self)
+    }
+}
+
+/// Finds declarations that break forced colors mode, skipping rules whose selector is already
+/// styled in a `@media (forced-colors: active)` rule
+pub fn audit_forced_colors(style_sheet: &mut StyleSheet) -> Vec<ForcedColorsIssue> {
+    let mut audit = ForcedColorsAudit::default();
+    style_sheet
+        .visit(&mut audit)
+        .expect("css visitor never fails");
+
+    audit
+        .issues
+        .into_iter()
+        .filter(|issue| !audit.covered_selectors.contains(&issue.selector))
+        .collect()
+}
+
+/// Creates a `@media (forced-colors: active)` rule with a suggested fallback for every issue
+pub fn forced_colors_fallback(issues: &[ForcedColorsIssue]) -> String {
+    let mut fallback = String::from("@media (forced-colors: active) {\n");
+    for issue in issues {
+        fallback.push_str(&format!(
+            "  {} {{\n    /* {} */\n    {}\n  }}\n",
+            issue.selector, issue.reason, issue.fallback
+        ));
+    }
+    fallback.push_str("}\n");
+
+    fallback
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::{audit_forced_colors, forced_colors_fallback};
+
+    #[test]
+    fn audit() {
+        let css = r#"
+            .button:focus {
+                outline: none;
+                box-shadow: 0 0 0 2px blue;
+            }
+
+            .checkbox:checked {
+                background-image: url("check.svg");
+            }
+
+            .link:focus {
+                outline: 0;
+            }
+
+            .card {
+                background-image: url("card.png");
+            }
+
+            @media (forced-colors: active) {
+                .link:focus {
+                    outline: 2px solid CanvasText;
+                }
+            }
+        "#;
+        let mut style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+
+        let issues = audit_forced_colors(&mut style_sheet);
+        let declarations: Vec<&str> = issues
+            .iter()
+            .map(|issue| issue.declaration.as_str())
+            .collect();
+
+        assert_eq!(
+            declarations,
+            vec![
+                "outline: none",
+                "box-shadow: 0 0 0 2px #00f",
+                "background-image: url(\"check.svg\")"
+            ]
+        );
+        assert!(forced_colors_fallback(&issues).starts_with(
+            "@media (forced-colors: active) {\n  .button:focus {\n    /* removing the outline"
+        ));
+    }
+}
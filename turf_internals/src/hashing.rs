@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::StyleSheetKind;
 
 #[derive(thiserror::Error, Debug)]
@@ -6,13 +8,83 @@ pub enum StyleSheetHashingError {
     FileRead(#[from] std::io::Error),
 }
 
-pub fn hash_style_sheet(style_sheet: &StyleSheetKind) -> Result<String, StyleSheetHashingError> {
-    let hash = match style_sheet {
-        StyleSheetKind::File(ref path) => xxhash_rust::xxh3::xxh3_128(&std::fs::read(path)?),
-        StyleSheetKind::Inline(ref style_sheet) => {
-            xxhash_rust::xxh3::xxh3_128(style_sheet.as_bytes())
-        }
+/// Hashes the style sheet's content, the salt is mixed into the hash so salted builds get distinct hashes
+pub fn hash_style_sheet(
+    style_sheet: &StyleSheetKind,
+    salt: &str,
+) -> Result<String, StyleSheetHashingError> {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    hasher.update(salt.as_bytes());
+    match style_sheet {
+        StyleSheetKind::File(ref path) => hasher.update(&std::fs::read(path)?),
+        StyleSheetKind::Inline(ref style_sheet) => hasher.update(style_sheet.as_bytes()),
     };
+    let hash = hasher.digest128();
 
     Ok(format!("{hash:x}"))
 }
+
+/// Hashes the compiled css only, e.g. for hashed file names of the css
+pub fn style_sheet_css_hash(css: &str) -> String {
+    format!("{:016x}", xxhash_rust::xxh3::xxh3_64(css.as_bytes()))
+}
+
+/// Hashes the compiled css and the generated class names for the `STYLE_SHEET_VERSION`
+///
+/// Every field is prefixed with its length, so moving characters between adjacent fields, e.g.
+/// from a class name to the next original class name, changes the version.
+pub fn style_sheet_version(
+    css: &str,
+    dark_css: Option<&str>,
+    class_names: &BTreeMap<String, String>,
+) -> String {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    let mut update_field = |field: &str| {
+        hasher.update(&(field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    };
+    update_field(css);
+    match dark_css {
+        Some(dark_css) => {
+            update_field("dark");
+            update_field(dark_css);
+        }
+        None => update_field(""),
+    }
+    for (original_class_name, class_name) in class_names {
+        update_field(original_class_name);
+        update_field(class_name);
+    }
+
+    format!("{:016x}", hasher.digest())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::style_sheet_version;
+
+    #[test]
+    fn style_sheet_version_fields() {
+        let class_names = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(original, class_name)| (original.to_string(), class_name.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert_ne!(
+            style_sheet_version("", None, &class_names(&[("a", "bc")])),
+            style_sheet_version("", None, &class_names(&[("ab", "c")]))
+        );
+        assert_ne!(
+            style_sheet_version(".a{}", Some(""), &BTreeMap::new()),
+            style_sheet_version(".a{}", None, &BTreeMap::new())
+        );
+        assert_ne!(
+            style_sheet_version(".a{}", Some(".b{}"), &BTreeMap::new()),
+            style_sheet_version(".a{}.b{}", None, &BTreeMap::new())
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16_LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16_BE_BOM: &[u8] = b"\xFE\xFF";
+
+/// The file system used to read style sheets and the files they load, normalizing their
+/// encoding before they are passed on to grass
+#[derive(Debug)]
+pub struct InputFs;
+
+pub static INPUT_FS: InputFs = InputFs;
+
+impl grass::Fs for InputFs {
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        normalize_input(std::fs::read(path)?).map_err(|encoding| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "'{}' appears to be encoded as {encoding}, but style sheets must be encoded as UTF-8 - save the file as UTF-8 and try again",
+                    path.display()
+                ),
+            )
+        })
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+}
+
+/// Strips a UTF-8 byte order mark and converts CRLF line endings to LF
+///
+/// Returns the detected encoding if the input is not valid UTF-8.
+fn normalize_input(input: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+    if input.starts_with(UTF16_LE_BOM) {
+        return Err("UTF-16 (little endian)");
+    }
+    if input.starts_with(UTF16_BE_BOM) {
+        return Err("UTF-16 (big endian)");
+    }
+
+    let input = input.strip_prefix(UTF8_BOM).unwrap_or(&input);
+    if std::str::from_utf8(input).is_err() {
+        return Err(detect_encoding(input));
+    }
+
+    let mut normalized = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte != b'\r' || bytes.peek() != Some(&&b'\n') {
+            normalized.push(byte);
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Guesses the encoding of input that is not valid UTF-8
+fn detect_encoding(input: &[u8]) -> &'static str {
+    // Style sheets are mostly ASCII, which UTF-16 encodes with a null byte in every other position
+    let null_bytes_at = |offset: usize| {
+        input
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|&&byte| byte == 0)
+            .count()
+    };
+    let half_length = input.len() / 2;
+
+    if half_length > 0 && null_bytes_at(1) > half_length / 2 {
+        "UTF-16 (little endian)"
+    } else if half_length > 0 && null_bytes_at(0) > half_length / 2 {
+        "UTF-16 (big endian)"
+    } else {
+        "a single byte encoding such as Windows-1252 or ISO-8859-1"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_input;
+
+    #[test]
+    fn normalize_utf8_input() {
+        assert_eq!(
+            normalize_input(b"\xEF\xBB\xBF.test {\r\n  color: red;\r\n}\n".to_vec()),
+            Ok(b".test {\n  color: red;\n}\n".to_vec())
+        );
+        assert_eq!(
+            normalize_input(b".test { content: \"\r\"; }".to_vec()),
+            Ok(b".test { content: \"\r\"; }".to_vec())
+        );
+    }
+
+    #[test]
+    fn detect_non_utf8_input() {
+        assert_eq!(
+            normalize_input(b"\xFF\xFE.\0a\0".to_vec()),
+            Err("UTF-16 (little endian)")
+        );
+        assert_eq!(
+            normalize_input(b"\0.\0a\0{\0}\xD8\x3D".to_vec()),
+            Err("UTF-16 (big endian)")
+        );
+        assert_eq!(
+            normalize_input(b".caf\xE9 { color: red; }".to_vec()),
+            Err("a single byte encoding such as Windows-1252 or ISO-8859-1")
+        );
+    }
+}
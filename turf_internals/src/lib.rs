@@ -1,16 +1,63 @@
 //! You're probably looking for `turf` instead.
 
+mod ast_json;
+mod bem;
+mod breakpoints;
+mod class_name_frequency;
+mod class_name_metadata;
+mod class_name_rules;
+mod color_fallbacks;
+mod color_scheme;
+mod composition;
+mod compression;
 mod css_compilation;
+mod css_warnings;
+mod diagnostics;
+mod exports;
 mod file_output;
+mod forced_colors;
 mod hashing;
+mod input_encoding;
+mod link;
+mod lockfile;
 mod manifest;
+mod media_sheets;
+mod output_manifest;
+mod partitions;
 mod path_utils;
+mod raw_css;
+mod registry;
+mod scoping_directives;
+mod selector_prefix;
 mod settings;
+mod settings_builder;
+mod settings_check;
+mod settings_overrides;
+mod source_locations;
+mod source_maps;
+mod theme_variables;
+mod themes;
 mod transformer;
+mod typescript_definitions;
+mod unknown_settings;
 
-use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+use std::{collections::BTreeMap, path::PathBuf, sync::Mutex};
 
-pub use settings::Settings;
+pub use bem::{parse_bem_class_name, BemClassName};
+pub use class_name_metadata::ClassNameMetadata;
+pub use diagnostics::{style_sheet_location, SourceLocation};
+pub use exports::parse_export_number;
+pub use link::{link_fragments, LinkError, LinkedBundle};
+pub use media_sheets::MediaSheets;
+pub use raw_css::RawCss;
+pub use settings::{
+    Codegen, CodegenStyle, Compression, IdentifierCase, IdentifierSanitization, Scoping, Settings,
+    SettingsError, Visibility,
+};
+pub use settings_builder::SettingsBuilder;
+pub use settings_check::check_settings;
+pub use source_locations::class_name_location;
+pub use theme_variables::ThemeValue;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -26,64 +73,460 @@ pub enum Error {
     PathResolution(#[from] path_utils::PathResolutionError),
 
     #[error(transparent)]
-    CssFileWrite(#[from] file_output::CssFileWriteError),
+    FileOutput(#[from] file_output::FileOutputError),
     #[error(transparent)]
     Settings(#[from] settings::SettingsError),
+    #[error("the turf settings are invalid, see the error of `turf::check_settings!()`")]
+    CheckedSettings,
+    #[error("invalid class name or selector exclude pattern - {0}")]
+    ExcludePattern(#[from] regex::Error),
+    #[error(transparent)]
+    Lockfile(#[from] lockfile::LockfileError),
+    #[error(transparent)]
+    Registry(#[from] registry::RegistryError),
+    #[error(transparent)]
+    Partition(#[from] partitions::PartitionError),
+    #[error(transparent)]
+    Salt(#[from] settings::SaltError),
+    #[error(transparent)]
+    RawCss(#[from] raw_css::RawCssError),
+    #[error("error generating a source map - {0}")]
+    SourceMap(#[from] parcel_sourcemap::SourceMapError),
+    #[error("the compiled css of {style_sheet} is {size_kb} KB, more than the limit of {limit_kb} KB set by `max_css_size_kb` - check the style sheet for loops or mixins that generate more rules than intended or split it into multiple style sheets")]
+    CssSizeLimit {
+        style_sheet: String,
+        size_kb: u64,
+        limit_kb: u64,
+    },
 }
 
 fn compile_message(message: &str) {
     println!("🌱 turf [INFO]: {message}");
 }
 
+/// Prints a warning and mirrors it into the SARIF file set by `TURF_DIAGNOSTICS_SARIF`
+pub fn compile_warning(message: &str, location: Option<&SourceLocation>) {
+    println!("🌱 turf [WARN]: {message}");
+    diagnostics::record_diagnostic(diagnostics::DiagnosticLevel::Warning, message, location);
+}
+
+/// Mirrors an error of the macros themselves into the SARIF file set by `TURF_DIAGNOSTICS_SARIF`
+pub fn record_compile_error(message: &str) {
+    diagnostics::record_diagnostic(diagnostics::DiagnosticLevel::Error, message, None);
+}
+
 #[derive(Debug)]
 pub enum StyleSheetKind {
     File(PathBuf),
     Inline(String),
 }
 
+impl std::fmt::Display for StyleSheetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", registry::style_sheet_source(self))
+    }
+}
+
 #[derive(Debug)]
 pub struct CompiledStyleSheet {
     pub css: String,
-    pub class_names: HashMap<String, String>,
+    pub class_names: BTreeMap<String, String>,
+    pub container_names: BTreeMap<String, String>,
+    pub counter_styles: BTreeMap<String, String>,
+    pub view_transition_names: BTreeMap<String, String>,
+    pub keyframes: BTreeMap<String, String>,
+    pub parts: BTreeMap<String, String>,
+    pub css_variables: BTreeMap<String, String>,
+    pub font_families: BTreeMap<String, String>,
+    pub grid_areas: BTreeMap<String, String>,
+    pub exports: BTreeMap<String, String>,
+    pub breakpoints: BTreeMap<String, String>,
+    pub theme_variables: BTreeMap<String, String>,
+    pub scope_id: String,
+    pub scoping: Scoping,
+    pub bem: bool,
+    pub partitions: Vec<String>,
+    pub partition_urls: Vec<String>,
+    pub theme_bootstrap: Option<String>,
+    pub forced_colors_fallback: Option<String>,
+    pub class_name_sources: BTreeMap<String, String>,
+    pub class_name_rules: BTreeMap<String, Vec<String>>,
+    pub class_name_metadata: BTreeMap<String, ClassNameMetadata>,
+    pub media_sheets: Option<MediaSheets>,
+    pub dark_css: Option<String>,
+    pub css_artifacts: Option<CssArtifacts>,
+    pub compressed_css: Option<Vec<u8>>,
+    pub version: String,
+    pub css_hash: String,
+    pub css_file_name: Option<String>,
     pub original_style_sheet: StyleSheetKind,
+    pub codegen: Codegen,
+}
+
+/// The paths of the artifacts the values of a style sheet that change with its css are embedded
+/// from with `codegen.css_artifacts_path`
+#[derive(Debug, Clone)]
+pub struct CssArtifacts {
+    pub css: String,
+    pub version: String,
+    pub css_hash: String,
+    pub scope_id: String,
+    pub dark_css: Option<String>,
+    pub forced_colors_fallback: Option<String>,
+    pub css_file_name: Option<String>,
 }
 
 fn style_sheet_with_compile_options(
     style_sheet_input: StyleSheetKind,
     settings: Settings,
 ) -> Result<CompiledStyleSheet, crate::Error> {
-    let hash = hashing::hash_style_sheet(&style_sheet_input)?;
+    settings.codegen.check_css_artifacts()?;
+    settings.codegen.check_compression()?;
+    settings.codegen.check_class_name_lookup()?;
+    let salt = match &settings.class_names.salt {
+        Some(salt) => salt.value()?,
+        None => String::new(),
+    };
+    let hash = hashing::hash_style_sheet(&style_sheet_input, &salt)?;
     let css = css_compilation::compile_style_sheet(&style_sheet_input, &settings)?;
 
-    let (style_sheet_css, class_names) =
-        transformer::transform_stylesheet(&css, &hash, settings.clone())?;
+    // The scss and the files it loads are checked before grass compiles them, the compiled css is
+    // checked as well since loops and mixins can generate far more css than their scss, and the
+    // memory used by the transform step grows with the size of the compiled css
+    if let Some(limit_kb) = settings.max_css_size_kb {
+        let size = css.len() as u64;
+        if size > limit_kb.saturating_mul(1024) {
+            return Err(crate::Error::CssSizeLimit {
+                style_sheet: style_sheet_input.to_string(),
+                size_kb: size.div_ceil(1024),
+                limit_kb,
+            });
+        }
+    }
+    let (css, mut exports) = exports::extract_exports(&css);
+    let breakpoints = breakpoints::take_breakpoints(&mut exports);
+    let theme_variables = match &style_sheet_input {
+        StyleSheetKind::File(path) if theme_variables::is_theme_style_sheet(&css) => {
+            let theme_css = css_compilation::compile_theme_export(path, &settings)?;
+            theme_variables::take_theme_variables(&theme_css)
+        }
+        _ => BTreeMap::new(),
+    };
+    let partitions = partitions::style_sheet_partitions(&css)?;
+    let excluded_from_global_file = file_output::is_excluded_from_global_file(&css);
+    let partition_urls = match settings
+        .file_output
+        .as_ref()
+        .and_then(|file_output| file_output.partitioned_css_files_url.as_ref())
+    {
+        Some(base_url) => partitions::partition_urls(base_url, &partitions),
+        None => Vec::new(),
+    };
+
+    let locked_class_names = match &settings.class_names.lockfile {
+        Some(lockfile_path) => lockfile::locked_class_names(lockfile_path, &style_sheet_input)?,
+        None => BTreeMap::new(),
+    };
 
-    if let Some(file_output) = settings.file_output {
-        file_output::perform_css_file_output(file_output, &style_sheet_css, &style_sheet_input)?;
+    let transformer::TransformedStyleSheet {
+        css: mut style_sheet_css,
+        mut class_names,
+        container_names,
+        counter_styles,
+        view_transition_names,
+        keyframes,
+        parts,
+        css_variables,
+        font_families,
+        grid_areas,
+        scope_id,
+        forced_colors_issues,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        ast_json,
+        randomized_css_size,
+        compositions,
+        source_map,
+    } = transformer::transform_stylesheet(&css, &hash, locked_class_names, settings.clone())?;
+
+    // Colliding class names would apply the rules of one style sheet to the elements of another,
+    // so they fail the build unless they are explicitly allowed
+    let style_sheet_location = diagnostics::style_sheet_location(&style_sheet_input);
+    let mut collisions = registry::register_class_names(&class_names, &style_sheet_input)?;
+    if settings.class_names.deny_collisions && !collisions.is_empty() {
+        return Err(collisions.swap_remove(0).into());
+    }
+    for collision in &collisions {
+        compile_warning(&collision.to_string(), style_sheet_location.as_ref());
     }
 
+    if let Some(randomized_css_size) = randomized_css_size {
+        compile_message(&format!(
+            "frequency based class names - {style_sheet_input} is {} bytes instead of {randomized_css_size} bytes",
+            style_sheet_css.len()
+        ));
+    }
+
+    if let Some(lockfile_path) = &settings.class_names.lockfile {
+        lockfile::lock_class_names(lockfile_path, &style_sheet_input, &class_names)?;
+    }
+
+    // Composed class names are only part of the generated constants, the registry, lockfile and
+    // css keep the class names of the rules themselves
+    class_names.extend(compositions);
+
+    let mut source_map = source_map
+        .map(|source_map| source_maps::named_source_map(&source_map, &style_sheet_input))
+        .transpose()?;
+
+    // The hashes identify the css itself, so they are computed before an inline source map is
+    // appended to it
+    let version = hashing::style_sheet_version(&style_sheet_css, dark_css.as_deref(), &class_names);
+    let css_hash = hashing::style_sheet_css_hash(&style_sheet_css);
+
+    let mut css_file_output = file_output::CssFileOutput::default();
+    if let Some(file_output) = &settings.file_output {
+        css_file_output = file_output::perform_css_file_output(
+            file_output.clone(),
+            file_output::StyleSheetCss {
+                css: &style_sheet_css,
+                dark_css: dark_css.as_deref(),
+                version: &version,
+            },
+            &style_sheet_input,
+            &partitions,
+            ast_json.as_deref(),
+            excluded_from_global_file,
+        )?;
+
+        if let (true, Some(source_map), Some(directory), Some(css_file_name)) = (
+            file_output.source_maps,
+            source_map.as_mut(),
+            &file_output.separate_css_files_path,
+            &css_file_output.css_file_name,
+        ) {
+            file_output::write_source_map(directory, css_file_name, source_map)?;
+        }
+
+        output_manifest::write_to_output_manifest(
+            file_output,
+            &style_sheet_input,
+            &class_names,
+            &css_hash,
+            &version,
+            css_file_output.css_file_name.as_deref(),
+        )?;
+
+        if let Some(directory) = &file_output.typescript_definitions_path {
+            typescript_definitions::write_typescript_definitions(
+                directory,
+                file_output.typescript_const_objects,
+                file_output.separate_css_file_naming,
+                &style_sheet_input,
+                &class_names,
+            )?;
+        }
+    }
+
+    // Only the embedded css links to an inline source map, the files written above are left
+    // without it
+    if let (true, Some(source_map)) = (
+        settings
+            .file_output
+            .as_ref()
+            .is_some_and(|file_output| file_output.inline_source_maps),
+        source_map.as_mut(),
+    ) {
+        style_sheet_css.push_str(&source_maps::source_mapping_url_comment(
+            &source_map.to_data_url(None)?,
+        ));
+    }
+
+    let theme_bootstrap = settings.themes.as_ref().map(themes::theme_bootstrap);
+    let class_name_sources = match settings.codegen.class_name_sources {
+        true => source_locations::class_name_sources(&style_sheet_input, &class_names),
+        false => BTreeMap::new(),
+    };
+
+    for issue in &forced_colors_issues {
+        compile_warning(
+            &format!("forced colors mode - {issue} ({style_sheet_input})"),
+            style_sheet_location.as_ref(),
+        );
+    }
+    let forced_colors_fallback = (settings.audits.forced_colors_fallback
+        && !forced_colors_issues.is_empty())
+    .then(|| forced_colors::forced_colors_fallback(&forced_colors_issues));
+
+    let css_artifacts = match &settings.codegen.css_artifacts_path {
+        Some(directory) => {
+            let write = |extension: &str, content: &str| {
+                file_output::write_css_artifact(directory, extension, content, &style_sheet_input)
+            };
+            Some(CssArtifacts {
+                css: write("css", &style_sheet_css)?,
+                version: write("version", &version)?,
+                css_hash: write("hash", &css_hash)?,
+                scope_id: write("scope-id", &scope_id)?,
+                dark_css: dark_css
+                    .as_deref()
+                    .map(|dark_css| write("dark.css", dark_css))
+                    .transpose()?,
+                forced_colors_fallback: forced_colors_fallback
+                    .as_deref()
+                    .map(|fallback| write("forced-colors.css", fallback))
+                    .transpose()?,
+                css_file_name: css_file_output
+                    .css_file_name
+                    .as_deref()
+                    .map(|css_file_name| write("file-name", css_file_name))
+                    .transpose()?,
+            })
+        }
+        None => None,
+    };
+
     Ok(CompiledStyleSheet {
+        compressed_css: compression::compress_css(&style_sheet_css, settings.codegen.compress),
         css: style_sheet_css,
         class_names,
+        container_names,
+        counter_styles,
+        view_transition_names,
+        keyframes,
+        parts,
+        css_variables,
+        font_families,
+        grid_areas,
+        exports,
+        breakpoints,
+        theme_variables,
+        scope_id,
+        scoping: settings.scoping,
+        bem: settings.class_names.bem,
+        partitions,
+        partition_urls,
+        theme_bootstrap,
+        forced_colors_fallback,
+        class_name_sources,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        css_artifacts,
+        version,
+        css_hash,
+        css_file_name: css_file_output.css_file_name,
         original_style_sheet: style_sheet_input,
+        codegen: settings.codegen,
     })
 }
 
 pub fn style_sheet(style_sheet: StyleSheetKind) -> Result<CompiledStyleSheet, crate::Error> {
-    let settings = Settings::get()?;
+    let style_sheet_location = diagnostics::style_sheet_location(&style_sheet);
+
+    Settings::get()
+        .map_err(settings_check::settings_error)
+        .and_then(|settings| resolved_style_sheet(style_sheet, settings))
+        .inspect_err(|error| {
+            diagnostics::record_error(error, style_sheet_location.as_ref());
+        })
+}
 
-    let style_sheet = match style_sheet {
+/// Compiles a style sheet with explicit settings instead of the settings of the Cargo manifest
+///
+/// Relative paths are resolved against `CARGO_MANIFEST_DIR` if it is set, e.g. in build scripts,
+/// and against the working directory otherwise.
+pub fn style_sheet_with_settings(
+    style_sheet: StyleSheetKind,
+    settings: Settings,
+) -> Result<CompiledStyleSheet, crate::Error> {
+    let style_sheet_location = diagnostics::style_sheet_location(&style_sheet);
+
+    resolved_style_sheet(style_sheet, settings).inspect_err(|error| {
+        diagnostics::record_error(error, style_sheet_location.as_ref());
+    })
+}
+
+fn resolved_style_sheet(
+    style_sheet: StyleSheetKind,
+    settings: Settings,
+) -> Result<CompiledStyleSheet, crate::Error> {
+    let style_sheet = canonicalized_style_sheet(style_sheet)?;
+    let settings = style_sheet_settings(&style_sheet, settings)?;
+
+    style_sheet_with_compile_options(style_sheet, settings)
+}
+
+/// Reads css that is embedded verbatim, without compiling, transforming or scoping it
+///
+/// The css is only minified if `minify_raw_css` is set, but it is still written to the configured
+/// output files.
+pub fn raw_css(style_sheet: StyleSheetKind) -> Result<RawCss, crate::Error> {
+    let style_sheet_location = diagnostics::style_sheet_location(&style_sheet);
+
+    Settings::get()
+        .map_err(settings_check::settings_error)
+        .and_then(|settings| {
+            let style_sheet = canonicalized_style_sheet(style_sheet)?;
+            let settings = style_sheet_settings(&style_sheet, settings)?;
+            raw_css::raw_css_with_settings(style_sheet, settings)
+        })
+        .inspect_err(|error| {
+            diagnostics::record_error(error, style_sheet_location.as_ref());
+        })
+}
+
+/// Reads raw css with explicit settings instead of the settings of the Cargo manifest
+pub fn raw_css_with_settings(
+    style_sheet: StyleSheetKind,
+    settings: Settings,
+) -> Result<RawCss, crate::Error> {
+    let style_sheet_location = diagnostics::style_sheet_location(&style_sheet);
+
+    canonicalized_style_sheet(style_sheet)
+        .and_then(|style_sheet| {
+            let settings = style_sheet_settings(&style_sheet, settings)?;
+            raw_css::raw_css_with_settings(style_sheet, settings)
+        })
+        .inspect_err(|error| {
+            diagnostics::record_error(error, style_sheet_location.as_ref());
+        })
+}
+
+/// Applies the settings overrides whose pattern matches the path of a style sheet file and resolves
+/// the output paths
+fn style_sheet_settings(
+    style_sheet: &StyleSheetKind,
+    settings: Settings,
+) -> Result<Settings, crate::Error> {
+    let mut settings = match style_sheet {
+        StyleSheetKind::File(path) => settings.for_style_sheet(path),
+        StyleSheetKind::Inline(_) => settings,
+    };
+    settings.file_output = settings
+        .file_output
+        .map(file_output::resolved_output_paths)
+        .transpose()?;
+
+    Ok(settings)
+}
+
+fn canonicalized_style_sheet(style_sheet: StyleSheetKind) -> Result<StyleSheetKind, crate::Error> {
+    match style_sheet {
         StyleSheetKind::File(path) => {
-            if path == PathBuf::from("") {
+            if path.as_os_str().is_empty() {
                 return Err(crate::Error::NoInputFile);
             };
             let canonicalized_path = path_utils::canonicalize(path)?;
-            StyleSheetKind::File(canonicalized_path)
+            Ok(StyleSheetKind::File(canonicalized_path))
         }
-        StyleSheetKind::Inline(inline_style_sheet) => StyleSheetKind::Inline(inline_style_sheet),
-    };
-
-    style_sheet_with_compile_options(style_sheet, settings)
+        StyleSheetKind::Inline(inline_style_sheet) => {
+            Ok(StyleSheetKind::Inline(inline_style_sheet))
+        }
+    }
 }
 
 static LOAD_PATHS_TRACKED: Mutex<bool> = Mutex::new(false);
@@ -115,7 +558,124 @@ pub fn get_untracked_load_paths() -> Result<Vec<PathBuf>, LoadPathTrackingError>
         for path in settings.load_paths {
             result.extend(path_utils::get_file_paths_recusively(path)?);
         }
+        // Changes to the configuration file rebuild the crate like changes to the load paths do
+        result.extend(manifest::config_file_path());
 
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::{ClassNameGeneration, FileOutput};
+
+    use super::{
+        style_sheet_with_compile_options, style_sheet_with_settings, Settings, StyleSheetKind,
+    };
+
+    #[test]
+    fn css_size_limit() {
+        let style_sheet = StyleSheetKind::Inline(String::from(
+            "@for $i from 1 through 1000 { .size-limit-#{$i} { width: $i * 1px; } }",
+        ));
+        let settings = Settings {
+            max_css_size_kb: Some(1),
+            ..Default::default()
+        };
+
+        let result = style_sheet_with_compile_options(style_sheet, settings.clone());
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::CssSizeLimit { limit_kb: 1, .. })
+        ));
+
+        // Css that is only a fraction of a kilobyte larger than the limit exceeds it as well
+        let style_sheet = StyleSheetKind::Inline(String::from(
+            "$content: \"\"; @for $i from 1 through 1024 { $content: $content + \"a\"; } .a { content: $content; }",
+        ));
+        let result = style_sheet_with_compile_options(style_sheet, settings);
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::CssSizeLimit {
+                size_kb: 2,
+                limit_kb: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn class_name_collisions() {
+        let settings = |deny_collisions| Settings {
+            class_names: ClassNameGeneration {
+                template: String::from("collision-test-<original_name>"),
+                deny_collisions,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let style_sheet = |color| StyleSheetKind::Inline(format!(".button {{ color: {color}; }}"));
+
+        assert!(style_sheet_with_compile_options(style_sheet("red"), settings(false)).is_ok());
+        // Collisions are only reported as warnings if they are explicitly allowed
+        assert!(style_sheet_with_compile_options(style_sheet("blue"), settings(false)).is_ok());
+        assert!(matches!(
+            style_sheet_with_compile_options(style_sheet("green"), settings(true)),
+            Err(crate::Error::Registry(
+                crate::registry::RegistryError::ClassNameCollision { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn inline_source_map() {
+        let settings = Settings {
+            file_output: Some(FileOutput {
+                inline_source_maps: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let compiled_style_sheet = style_sheet_with_compile_options(
+            StyleSheetKind::Inline(String::from(".button { color: red; }")),
+            settings,
+        )
+        .unwrap();
+
+        let (css, source_mapping_url) = compiled_style_sheet
+            .css
+            .split_once("\n/*# sourceMappingURL=")
+            .unwrap();
+        assert!(source_mapping_url.starts_with("data:application/json;charset=utf-8;base64,"));
+        assert_eq!(
+            compiled_style_sheet.css_hash,
+            crate::hashing::style_sheet_css_hash(css)
+        );
+    }
+
+    #[test]
+    fn explicit_settings() {
+        let settings = Settings::builder()
+            .class_name_template("builder-<original_name>")
+            .build()
+            .unwrap();
+
+        let compiled_style_sheet = style_sheet_with_settings(
+            StyleSheetKind::Inline(String::from(".button { color: red; }")),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            compiled_style_sheet
+                .class_names
+                .get("button")
+                .map(String::as_str),
+            Some("builder-button")
+        );
+        assert_eq!(compiled_style_sheet.css, ".builder-button{color:red}");
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use lightningcss::{
+    printer::PrinterOptions,
+    rules::{CssRule, CssRuleList},
+    stylesheet::{ParserOptions, StyleSheet},
+    traits::ToCss,
+};
+use serde::{Deserialize, Serialize};
+
+/// The extension of the fragment files crates write to the `file_output.fragments_path`
+pub(crate) const FRAGMENTS_FILE_EXTENSION: &str = "toml";
+
+/// The compiled css of a style sheet as it is written to the fragment file of its crate
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Fragment {
+    pub(crate) style_sheet: String,
+    pub(crate) layer: Option<String>,
+    pub(crate) hash: String,
+    /// The `STYLE_SHEET_VERSION` of the style sheet, missing in fragments of older versions of
+    /// turf
+    #[serde(default)]
+    pub(crate) version: String,
+    pub(crate) css: String,
+}
+
+/// The content of a fragment file, every style sheet appends a `[[fragments]]` table to it
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct FragmentsFile {
+    #[serde(default)]
+    pub(crate) fragments: Vec<Fragment>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LinkError {
+    #[error("error reading the css fragments in '{0}' - {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("error parsing the css fragments file '{0}' - {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("error parsing the css fragment of {0} - {1}")]
+    Css(String, String),
+    #[error("the css fragment of {0} contains `@import` rules, which can't be wrapped in its `@layer` - import the css in a style sheet without a `fragments_layer`")]
+    LayeredImport(String),
+}
+
+/// The css of all crates of a workspace, linked into one bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedBundle {
+    pub css: String,
+    /// The hash of the bundle's css, e.g. for a hashed file name
+    pub hash: String,
+}
+
+/// Links the css fragments written by the crates of a workspace into one bundle
+///
+/// The fragments of the packages in `crate_order` come first, in the given order, followed by the
+/// fragments of all other packages in alphabetical order. The fragments of a package are ordered by
+/// the crate that wrote them and their style sheet. Fragments with the same css, e.g. of a style
+/// sheet that is included by several crates, are only added once. The css of a fragment with a
+/// layer is wrapped in an `@layer` rule, and the bundle starts with an `@layer` statement declaring
+/// all layers in the order they first appear, so the cascade doesn't depend on the order of the
+/// rules. `@import` rules, which are only valid at the start of a style sheet, are moved behind
+/// that statement, and `@charset` rules are left out since the bundle is UTF-8.
+pub fn link_fragments(
+    fragments_dir: &Path,
+    crate_order: &[&str],
+) -> Result<LinkedBundle, LinkError> {
+    let mut crate_keys = Vec::new();
+    for entry in
+        std::fs::read_dir(fragments_dir).map_err(|e| LinkError::Read(fragments_dir.into(), e))?
+    {
+        let path = entry
+            .map_err(|e| LinkError::Read(fragments_dir.into(), e))?
+            .path();
+        if path.extension().and_then(|extension| extension.to_str())
+            != Some(FRAGMENTS_FILE_EXTENSION)
+        {
+            continue;
+        }
+        if let Some(crate_key) = path.file_stem().and_then(|stem| stem.to_str()) {
+            crate_keys.push(crate_key.to_owned());
+        }
+    }
+    // The files are named by the key of the crate that wrote them, which starts with the name of
+    // its package
+    crate_keys.sort_by_key(|crate_key| {
+        let package_name = crate_key.split('.').next().unwrap_or_default();
+        let position = crate_order.iter().position(|name| *name == package_name);
+        (position.unwrap_or(crate_order.len()), crate_key.clone())
+    });
+
+    let mut fragments = Vec::new();
+    for crate_key in crate_keys {
+        let path = fragments_dir.join(format!("{crate_key}.{FRAGMENTS_FILE_EXTENSION}"));
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| LinkError::Read(path.clone(), e))?;
+        let mut fragments_file: FragmentsFile =
+            toml::from_str(&content).map_err(|e| LinkError::Parse(path, e))?;
+        fragments_file
+            .fragments
+            .sort_by(|a, b| a.style_sheet.cmp(&b.style_sheet));
+        fragments.extend(fragments_file.fragments);
+    }
+
+    link(fragments)
+}
+
+fn link(fragments: Vec<Fragment>) -> Result<LinkedBundle, LinkError> {
+    let mut hashes = BTreeSet::new();
+    let mut layers: Vec<String> = Vec::new();
+    let mut imports: Vec<String> = Vec::new();
+    let mut css = String::new();
+
+    for fragment in fragments {
+        if !hashes.insert(fragment.hash.clone()) {
+            continue;
+        }
+        let (fragment_imports, fragment_css) = split_imports(&fragment)?;
+        for import in fragment_imports {
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
+        match fragment.layer {
+            Some(layer) => {
+                if !layers.contains(&layer) {
+                    layers.push(layer.clone());
+                }
+                css.push_str(&format!("@layer {layer}{{{fragment_css}}}"));
+            }
+            None => css.push_str(&fragment_css),
+        }
+    }
+
+    css.insert_str(0, &imports.concat());
+    if !layers.is_empty() {
+        css.insert_str(0, &format!("@layer {};", layers.join(",")));
+    }
+    let hash = crate::hashing::style_sheet_css_hash(&css);
+
+    Ok(LinkedBundle { css, hash })
+}
+
+/// Splits the `@import` rules from the css of a fragment and leaves out its `@charset` rule, which
+/// lightningcss doesn't keep
+///
+/// Only fragments that may contain these rules are parsed, the css of all others is linked as it
+/// was written.
+fn split_imports(fragment: &Fragment) -> Result<(Vec<String>, String), LinkError> {
+    if !fragment.css.contains("@import") && !fragment.css.contains("@charset") {
+        return Ok((Vec::new(), fragment.css.clone()));
+    }
+    let css_error = |error: String| LinkError::Css(fragment.style_sheet.clone(), error);
+
+    let style_sheet = StyleSheet::parse(&fragment.css, ParserOptions::default())
+        .map_err(|error| css_error(error.to_string()))?;
+    let options = || PrinterOptions {
+        minify: true,
+        ..Default::default()
+    };
+    let mut imports = Vec::new();
+    let mut rules = Vec::new();
+    for rule in style_sheet.rules.0 {
+        match rule {
+            CssRule::Import(import) => {
+                if fragment.layer.is_some() {
+                    return Err(LinkError::LayeredImport(fragment.style_sheet.clone()));
+                }
+                imports.push(
+                    import
+                        .to_css_string(options())
+                        .map_err(|error| css_error(error.to_string()))?,
+                );
+            }
+            rule => rules.push(rule),
+        }
+    }
+    let css = StyleSheet::new(Vec::new(), CssRuleList(rules), ParserOptions::default())
+        .to_css(options())
+        .map_err(|error| css_error(error.to_string()))?
+        .code;
+
+    Ok((imports, css))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{link, link_fragments, Fragment, FragmentsFile, LinkError};
+
+    fn fragment(style_sheet: &str, layer: Option<&str>, css: &str) -> Fragment {
+        Fragment {
+            style_sheet: String::from(style_sheet),
+            layer: layer.map(String::from),
+            hash: crate::hashing::style_sheet_css_hash(css),
+            version: crate::hashing::style_sheet_version(css, None, &Default::default()),
+            css: String::from(css),
+        }
+    }
+
+    #[test]
+    fn link_crate_fragments() {
+        let fragments_dir = std::env::temp_dir().join("turf_link_crate_fragments");
+        let _ = std::fs::remove_dir_all(&fragments_dir);
+        std::fs::create_dir_all(&fragments_dir).unwrap();
+        let crates = [
+            (
+                "app.app.lib",
+                vec![
+                    fragment("src/page.scss", Some("app"), ".page{color:red}"),
+                    fragment("src/button.scss", None, ".button{color:blue}"),
+                ],
+            ),
+            (
+                "components.components.lib",
+                vec![fragment(
+                    "src/card.scss",
+                    Some("components"),
+                    ".card{color:green}",
+                )],
+            ),
+            (
+                "widgets.widgets.lib",
+                vec![fragment("src/button.scss", None, ".button{color:blue}")],
+            ),
+            (
+                "components.gallery.bin",
+                vec![fragment("src/gallery.scss", None, ".gallery{color:red}")],
+            ),
+        ];
+        for (crate_key, fragments) in crates {
+            let fragments_file = toml::to_string(&FragmentsFile { fragments }).unwrap();
+            std::fs::write(
+                fragments_dir.join(format!("{crate_key}.toml")),
+                fragments_file,
+            )
+            .unwrap();
+        }
+
+        let bundle = link_fragments(&fragments_dir, &["components"]).unwrap();
+
+        std::fs::remove_dir_all(&fragments_dir).unwrap();
+        assert_eq!(
+            bundle.css,
+            "@layer components,app;@layer components{.card{color:green}}.gallery{color:red}.button{color:blue}@layer app{.page{color:red}}"
+        );
+        assert_eq!(
+            bundle.hash,
+            crate::hashing::style_sheet_css_hash(&bundle.css)
+        );
+    }
+
+    #[test]
+    fn hoisted_imports() {
+        let bundle = link(vec![
+            fragment("src/card.scss", Some("components"), ".card{color:green}"),
+            fragment(
+                "src/page.scss",
+                None,
+                "@charset \"UTF-8\";@import url(\"fonts.css\");.page{content:\"é\"}",
+            ),
+        ])
+        .unwrap();
+        assert_eq!(
+            bundle.css,
+            "@layer components;@import \"fonts.css\";@layer components{.card{color:green}}.page{content:\"é\"}"
+        );
+
+        let result = link(vec![fragment(
+            "src/page.scss",
+            Some("app"),
+            "@import url(\"fonts.css\");.page{color:red}",
+        )]);
+        assert!(matches!(result, Err(LinkError::LayeredImport(_))));
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{path_utils, StyleSheetKind};
+
+type LockedClassNames = BTreeMap<String, BTreeMap<String, String>>;
+
+static LOCKFILE: Mutex<Option<LockedClassNames>> = Mutex::new(None);
+
+static LOCKFILE_HEADER: &str =
+    "# This file is generated by turf to keep generated class names stable across builds.\n\n";
+
+#[derive(Debug, thiserror::Error)]
+pub enum LockfileError {
+    #[error("Could not read internal state")]
+    Mutex,
+    #[error("error reading class name lockfile '{0}' - {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("error parsing class name lockfile '{0}' - {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("error serializing class name lockfile - {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("error writing class name lockfile '{0}' - {1}")]
+    Write(PathBuf, std::io::Error),
+}
+
+fn lockfile_path(path: &Path) -> PathBuf {
+    let mut lockfile_path = path_utils::manifest_dir();
+    lockfile_path.push(path);
+    lockfile_path
+}
+
+pub(crate) fn style_sheet_key(style_sheet: &StyleSheetKind) -> String {
+    match style_sheet {
+        StyleSheetKind::File(path) => path_utils::relative_to_manifest_dir(path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        StyleSheetKind::Inline(style_sheet) => format!(
+            "inline:{:x}",
+            xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes())
+        ),
+    }
+}
+
+fn read_lockfile(path: &Path) -> Result<LockedClassNames, LockfileError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            toml::de::from_str(&content).map_err(|e| LockfileError::Parse(path.to_path_buf(), e))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(error) => Err(LockfileError::Read(path.to_path_buf(), error)),
+    }
+}
+
+pub fn locked_class_names(
+    path: &Path,
+    style_sheet: &StyleSheetKind,
+) -> Result<BTreeMap<String, String>, LockfileError> {
+    let mut lockfile = LOCKFILE.lock().map_err(|_| LockfileError::Mutex)?;
+
+    if lockfile.is_none() {
+        *lockfile = Some(read_lockfile(&lockfile_path(path))?);
+    }
+
+    Ok(lockfile
+        .as_ref()
+        .and_then(|lockfile| lockfile.get(&style_sheet_key(style_sheet)))
+        .cloned()
+        .unwrap_or_default())
+}
+
+pub fn lock_class_names(
+    path: &Path,
+    style_sheet: &StyleSheetKind,
+    class_names: &BTreeMap<String, String>,
+) -> Result<(), LockfileError> {
+    let path = lockfile_path(path);
+    let mut lockfile = LOCKFILE.lock().map_err(|_| LockfileError::Mutex)?;
+    let lockfile = match lockfile.as_mut() {
+        Some(lockfile) => lockfile,
+        None => lockfile.insert(read_lockfile(&path)?),
+    };
+
+    let key = style_sheet_key(style_sheet);
+
+    if lockfile.get(&key) == Some(class_names) {
+        return Ok(());
+    }
+
+    lockfile.insert(key, class_names.clone());
+
+    let content = format!("{LOCKFILE_HEADER}{}", toml::to_string(lockfile)?);
+    std::fs::write(&path, content).map_err(|error| LockfileError::Write(path, error))
+}
@@ -1,20 +1,79 @@
-use std::fs::read_to_string;
+use std::{fs::read_to_string, path::PathBuf};
 
 use serde::Deserialize;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ManifestError {
-    #[error("Could not find the Cargo manifest")]
-    Path(#[from] std::env::VarError),
     #[error("Could not read the Cargo manifest file")]
     ReadFile(#[from] std::io::Error),
     #[error("Could not read the Cargo manifest's toml")]
     ReadToml(#[from] toml::de::Error),
+    #[error("Could not read the turf configuration file '{0}'")]
+    ReadConfigFile(PathBuf, #[source] std::io::Error),
+    #[error("Could not read the turf configuration file's toml '{0}'")]
+    ReadConfigToml(PathBuf, #[source] Box<toml::de::Error>),
+    #[error("Could not parse the turf configuration file's json '{0}'")]
+    ParseConfigJson(PathBuf, #[source] serde_json::Error),
 }
 
-pub fn cargo_manifest() -> Result<ManifestWithPackage, ManifestError> {
-    let manifest_path = format!("{}/Cargo.toml", std::env::var("CARGO_MANIFEST_DIR")?);
-    Ok(toml::de::from_str(&read_to_string(manifest_path)?)?)
+/// The standalone configuration files, looked up in the manifest directory and its ancestors
+const CONFIG_FILE_PATHS: [&str; 2] = ["turf.toml", ".config/turf.toml"];
+
+/// The environment variable with the path of a configuration file that is used instead of looking
+/// one up, e.g. for build systems without a Cargo manifest
+pub(crate) const SETTINGS_FILE_ENV: &str = "TURF_SETTINGS_FILE";
+
+/// Returns the path of the configuration file set by `TURF_SETTINGS_FILE`, or the one closest to
+/// the manifest directory
+pub fn config_file_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(SETTINGS_FILE_ENV) {
+        return Some(crate::path_utils::manifest_dir().join(path));
+    }
+
+    crate::path_utils::manifest_dir()
+        .ancestors()
+        .flat_map(|dir| CONFIG_FILE_PATHS.map(|path| dir.join(path)))
+        .find(|path| path.is_file())
+}
+
+/// Reads the turf settings from the configuration file if there is one, otherwise from the
+/// metadata of the Cargo manifest
+///
+/// Configuration files with a `.json` extension are read as JSON, all others as TOML. Without a
+/// configuration file or Cargo manifest, e.g. in builds that aren't run by cargo, there are no
+/// settings.
+pub fn turf_metadata() -> Result<Option<MetadataWithTurfSettings>, ManifestError> {
+    match config_file_path() {
+        Some(path) => {
+            let content = read_to_string(&path)
+                .map_err(|error| ManifestError::ReadConfigFile(path.clone(), error))?;
+            let metadata = match path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                true => serde_json::from_str(&content)
+                    .map_err(|error| ManifestError::ParseConfigJson(path, error))?,
+                false => toml::de::from_str(&content)
+                    .map_err(|error| ManifestError::ReadConfigToml(path, Box::new(error)))?,
+            };
+
+            Ok(Some(metadata))
+        }
+        None => Ok(cargo_manifest()?
+            .and_then(|manifest| manifest.package)
+            .and_then(|package| package.metadata)),
+    }
+}
+
+/// Reads the Cargo manifest in the manifest directory, returns `None` if there is none
+pub fn cargo_manifest() -> Result<Option<ManifestWithPackage>, ManifestError> {
+    let manifest_path = crate::path_utils::manifest_dir().join("Cargo.toml");
+
+    match read_to_string(manifest_path) {
+        Ok(content) => Ok(Some(toml::de::from_str(&content)?)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -29,7 +88,7 @@ pub struct PackageWithMetadata {
 
 #[derive(Deserialize, Debug)]
 pub struct MetadataWithTurfSettings {
-    pub turf: Option<crate::settings::Settings>,
+    pub turf: Option<toml::Table>,
     #[serde(rename = "turf-dev")]
-    pub turf_dev: Option<crate::settings::Settings>,
+    pub turf_dev: Option<toml::Table>,
 }
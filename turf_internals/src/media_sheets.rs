@@ -0,0 +1,134 @@
+use lightningcss::{
+    printer::PrinterOptions,
+    rules::{CssRule, CssRuleList},
+    stylesheet::{ParserOptions, StyleSheet},
+    traits::ToCss,
+};
+
+/// The css of a style sheet split into the rules that apply regardless of the viewport and the
+/// rules of each top level media query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaSheets {
+    pub base: String,
+    /// The css of the media rules keyed by their media query, in the order they appear in the
+    /// style sheet
+    pub media: Vec<(String, String)>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MediaSheetsError {
+    #[error("error splitting the css by its media queries - {0}")]
+    Printer(String),
+    #[error("the `@media {0}` rules can't be split into `MEDIA_SHEETS` without changing the order of the rules - `codegen.media_sheets` requires the rules that aren't nested in an `@media` rule to come first and the rules of each media query to be adjacent")]
+    ReorderedRules(String),
+}
+
+/// Splits a transformed style sheet by its top level media rules
+///
+/// Media rules with the same query are collected into the same sheet. The sheets keep their
+/// `@media` rules, so each of them can be injected on its own. Injecting the base sheet followed by
+/// the media sheets only keeps the cascade order of the style sheet if no rule is moved past
+/// another one, so the split fails if a rule follows an `@media` rule with a different query and
+/// isn't part of a later media sheet.
+pub fn media_sheets(
+    style_sheet: &StyleSheet,
+    settings: &crate::Settings,
+) -> Result<MediaSheets, MediaSheetsError> {
+    let mut base_rules = Vec::new();
+    let mut media_rules: Vec<(String, Vec<CssRule>)> = Vec::new();
+
+    for rule in &style_sheet.rules.0 {
+        let CssRule::Media(media_rule) = rule else {
+            if let Some((query, _)) = media_rules.last() {
+                return Err(MediaSheetsError::ReorderedRules(query.clone()));
+            }
+            base_rules.push(rule.clone());
+            continue;
+        };
+        let query = media_rule
+            .query
+            .to_css_string(PrinterOptions::default())
+            .map_err(|e| MediaSheetsError::Printer(e.to_string()))?;
+
+        match media_rules.last_mut() {
+            Some((last_query, rules)) if *last_query == query => rules.push(rule.clone()),
+            _ => {
+                if let Some((previous_query, _)) =
+                    media_rules.iter().find(|(other, _)| *other == query)
+                {
+                    return Err(MediaSheetsError::ReorderedRules(previous_query.clone()));
+                }
+                media_rules.push((query, vec![rule.clone()]));
+            }
+        }
+    }
+
+    Ok(MediaSheets {
+        base: rules_css(base_rules, settings).map_err(MediaSheetsError::Printer)?,
+        media: media_rules
+            .into_iter()
+            .map(|(query, rules)| Ok((query, rules_css(rules, settings)?)))
+            .collect::<Result<_, String>>()
+            .map_err(MediaSheetsError::Printer)?,
+    })
+}
+
+pub(crate) fn rules_css(rules: Vec<CssRule>, settings: &crate::Settings) -> Result<String, String> {
+    let style_sheet = StyleSheet::new(Vec::new(), CssRuleList(rules), ParserOptions::default());
+
+    style_sheet
+        .to_css(settings.clone().into())
+        .map(|result| result.code)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use crate::Settings;
+
+    use super::{media_sheets, MediaSheetsError};
+
+    #[test]
+    fn split_media_sheets() {
+        let css = ".a{color:red}.b{color:green}@media (min-width: 768px){.a{color:blue}}@media (min-width: 768px){.b{color:red}}@media print{.b{display:none}}";
+        let style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+
+        let sheets = media_sheets(&style_sheet, &Settings::default()).unwrap();
+
+        assert_eq!(sheets.base, ".a{color:red}.b{color:green}");
+        assert_eq!(
+            sheets.media,
+            vec![
+                (
+                    String::from("(width >= 768px)"),
+                    String::from(
+                        "@media (width>=768px){.a{color:#00f}}@media (width>=768px){.b{color:red}}"
+                    )
+                ),
+                (
+                    String::from("print"),
+                    String::from("@media print{.b{display:none}}")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reordered_media_sheets() {
+        for css in [
+            ".a{color:red}@media print{.a{color:blue}}.b{color:green}",
+            "@media print{.a{color:blue}}@media (min-width: 768px){.a{color:red}}@media print{.b{color:green}}",
+        ] {
+            let style_sheet = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+
+            let result = media_sheets(&style_sheet, &Settings::default());
+
+            assert!(
+                matches!(result, Err(MediaSheetsError::ReorderedRules(ref query)) if query == "print"),
+                "{css} - {result:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    file_output::{self, FileOutputError},
+    lockfile,
+    settings::FileOutput,
+    StyleSheetKind,
+};
+
+/// The name of the manifest file written to the output directory
+pub(crate) const OUTPUT_MANIFEST_FILE_NAME: &str = "turf-manifest.json";
+
+static OUTPUT_MANIFESTS: Mutex<BTreeMap<(String, PathBuf), OutputManifest>> =
+    Mutex::new(BTreeMap::new());
+
+/// The style sheets of a compilation, keyed by their source path relative to the manifest
+/// directory
+///
+/// Like the global css files, the style sheets of every crate writing to the manifest are stored
+/// in the target directory and start out empty in every compilation of the crate. The manifest is
+/// rewritten with the style sheets of all crates whenever a style sheet is added to it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OutputManifest {
+    style_sheets: BTreeMap<String, OutputManifestEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct OutputManifestEntry {
+    class_names: BTreeMap<String, String>,
+    content_hash: String,
+    /// The `STYLE_SHEET_VERSION` of the style sheet
+    version: String,
+    /// The name of the separate css file of the style sheet, or of the global css file if the
+    /// style sheet isn't written to a separate file
+    css_file: Option<String>,
+}
+
+/// The path of the manifest in the separate css files directory, or next to the global css file
+///
+/// The directories already exist once the css of the style sheet has been written to them.
+pub(crate) fn output_manifest_path(file_output: &FileOutput) -> Option<PathBuf> {
+    if !file_output.manifest {
+        return None;
+    }
+    if let Some(directory) = &file_output.separate_css_files_path {
+        return Some(directory.join(OUTPUT_MANIFEST_FILE_NAME));
+    }
+
+    file_output
+        .global_css_file_path
+        .as_deref()
+        .map(|path| match path.parent() {
+            Some(directory) => directory.join(OUTPUT_MANIFEST_FILE_NAME),
+            None => PathBuf::from(OUTPUT_MANIFEST_FILE_NAME),
+        })
+}
+
+/// Adds a style sheet to the manifest in the output directory and rewrites the manifest
+pub(crate) fn write_to_output_manifest(
+    file_output: &FileOutput,
+    style_sheet: &StyleSheetKind,
+    class_names: &BTreeMap<String, String>,
+    content_hash: &str,
+    version: &str,
+    css_file_name: Option<&str>,
+) -> Result<(), FileOutputError> {
+    let Some(path) = output_manifest_path(file_output) else {
+        return Ok(());
+    };
+
+    let css_file = css_file_name.map(str::to_owned).or_else(|| {
+        file_output
+            .global_css_file_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+    });
+    let entry = OutputManifestEntry {
+        class_names: class_names.clone(),
+        content_hash: content_hash.to_owned(),
+        version: version.to_owned(),
+        css_file,
+    };
+
+    let mut output_manifests = OUTPUT_MANIFESTS
+        .lock()
+        .map_err(|_| FileOutputError::Mutex)?;
+    // A proc macro server, e.g. the one of rust-analyzer, expands the macros of several crates
+    let output_manifest = output_manifests
+        .entry((file_output::crate_key(), path.clone()))
+        .or_default();
+    let key = lockfile::style_sheet_key(style_sheet);
+
+    if output_manifest.style_sheets.get(&key) == Some(&entry) && path.exists() {
+        return Ok(());
+    }
+    output_manifest.style_sheets.insert(key, entry);
+
+    write_merged_output_manifest(output_manifest, &path)
+}
+
+/// Stores the style sheets of the current crate and rewrites the manifest with the style sheets of
+/// all crates writing to it, locking the manifest like a global css file
+fn write_merged_output_manifest(
+    output_manifest: &OutputManifest,
+    path: &Path,
+) -> Result<(), FileOutputError> {
+    let _lock = file_output::lock_output_file(path)?;
+
+    let store = serde_json::to_string(output_manifest)
+        .map_err(|error| FileOutputError::OutputManifest(path.to_path_buf(), error))?;
+    let mut merged = OutputManifest::default();
+    for store in file_output::stored_outputs_of_crates(path, &store)? {
+        // Stores that can't be read, e.g. ones written by another version of turf, are left out
+        // until their crate is compiled again
+        if let Ok(crate_manifest) = serde_json::from_str::<OutputManifest>(&store) {
+            merged.style_sheets.extend(crate_manifest.style_sheets);
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&merged)
+        .map_err(|error| FileOutputError::OutputManifest(path.to_path_buf(), error))?;
+    file_output::replace_file(path, &json)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{settings::FileOutput, StyleSheetKind};
+
+    use super::{output_manifest_path, write_to_output_manifest};
+
+    #[test]
+    fn output_manifest() {
+        let directory =
+            std::env::temp_dir().join(format!("turf_output_manifest_test_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file_output = FileOutput {
+            manifest: true,
+            global_css_file_path: Some(directory.join("global.css")),
+            ..Default::default()
+        };
+        assert_eq!(output_manifest_path(&FileOutput::default()), None);
+        let path = output_manifest_path(&file_output).unwrap();
+        assert_eq!(path, directory.join("turf-manifest.json"));
+
+        let class_names = BTreeMap::from([(String::from("button"), String::from("class-abc"))]);
+        write_to_output_manifest(
+            &file_output,
+            &StyleSheetKind::Inline(String::from(".button {}")),
+            &class_names,
+            "1f2e3d4c5b6a7988",
+            "8a7b6c5d4e3f2a1b",
+            None,
+        )
+        .unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let style_sheets = manifest["style_sheets"].as_object().unwrap();
+        assert_eq!(style_sheets.len(), 1);
+        let (source, entry) = style_sheets.iter().next().unwrap();
+        assert!(source.starts_with("inline:"));
+        assert_eq!(entry["class_names"]["button"], "class-abc");
+        assert_eq!(entry["content_hash"], "1f2e3d4c5b6a7988");
+        assert_eq!(entry["version"], "8a7b6c5d4e3f2a1b");
+        assert_eq!(entry["css_file"], "global.css");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
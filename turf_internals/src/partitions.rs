@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+static PARTITION_ANNOTATION: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid partition name '{0}' - partition names may only contain alphanumeric characters, `_` and `-`")]
+pub struct PartitionError(String);
+
+fn is_valid_partition_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Collects the partitions a style sheet belongs to from `/* turf:partition <names> */` annotations
+pub fn style_sheet_partitions(css: &str) -> Result<Vec<String>, PartitionError> {
+    let partition_annotation = PARTITION_ANNOTATION.get_or_init(|| {
+        Regex::new(r"/\*\s*turf:partition\s+([^*]*?)\s*\*/")
+            .expect("partition annotation regex is valid")
+    });
+
+    let mut partitions = BTreeSet::new();
+    for captures in partition_annotation.captures_iter(css) {
+        for name in captures[1].split(|c: char| c == ',' || c.is_whitespace()) {
+            if name.is_empty() {
+                continue;
+            }
+            if !is_valid_partition_name(name) {
+                return Err(PartitionError(name.to_owned()));
+            }
+            partitions.insert(name.to_owned());
+        }
+    }
+
+    Ok(partitions.into_iter().collect())
+}
+
+/// Returns the URLs the partitioned CSS files of a style sheet are served from
+pub fn partition_urls(base_url: &str, partitions: &[String]) -> Vec<String> {
+    let base_url = base_url.trim_end_matches('/');
+
+    partitions
+        .iter()
+        .map(|partition| format!("{base_url}/{partition}.css"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{partition_urls, style_sheet_partitions};
+
+    #[test]
+    fn collect_partitions() {
+        let css = r#"
+            /* turf:partition settings */
+            .test {
+                color: red;
+            }
+            /* turf:partition profile, admin */
+            /* some other comment */
+        "#;
+
+        assert_eq!(
+            style_sheet_partitions(css).unwrap(),
+            vec!["admin", "profile", "settings"]
+        );
+        assert!(style_sheet_partitions("/* turf:partition ../settings */").is_err());
+    }
+
+    #[test]
+    fn create_partition_urls() {
+        let partitions = vec![String::from("profile"), String::from("settings")];
+
+        assert_eq!(
+            partition_urls("/assets/css/", &partitions),
+            vec!["/assets/css/profile.css", "/assets/css/settings.css"]
+        );
+        assert_eq!(
+            partition_urls("https://example.com/css", &partitions),
+            vec![
+                "https://example.com/css/profile.css",
+                "https://example.com/css/settings.css"
+            ]
+        );
+    }
+}
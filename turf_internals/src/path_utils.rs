@@ -16,18 +16,41 @@ impl From<(PathBuf, std::io::Error)> for PathResolutionError {
     }
 }
 
+/// The environment variable with the root directory of the project, for build systems that don't
+/// set `CARGO_MANIFEST_DIR`
+pub(crate) const PROJECT_ROOT_ENV: &str = "TURF_PROJECT_ROOT";
+
+/// The directory of the crate's manifest, read without requiring the path to be valid UTF-8
+///
+/// `TURF_PROJECT_ROOT` takes precedence over `CARGO_MANIFEST_DIR`. Outside of cargo builds, e.g.
+/// when style sheets are compiled by a build tool, paths are resolved relative to the working
+/// directory instead.
+pub fn manifest_dir() -> PathBuf {
+    std::env::var_os(PROJECT_ROOT_ENV)
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR"))
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
 pub fn canonicalize<P>(path: P) -> Result<PathBuf, PathResolutionError>
 where
     P: AsRef<Path>,
 {
-    let mut canonicalized_path = PathBuf::from(
-        std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR environment variable"),
-    );
+    let mut canonicalized_path = manifest_dir();
     canonicalized_path.push(path.as_ref());
 
     std::fs::canonicalize(canonicalized_path.clone()).map_err(|e| (canonicalized_path, e).into())
 }
 
+pub fn relative_to_manifest_dir(path: &Path) -> PathBuf {
+    let manifest_dir = manifest_dir();
+    let manifest_dir = std::fs::canonicalize(&manifest_dir).unwrap_or(manifest_dir);
+
+    path.strip_prefix(&manifest_dir)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
 pub fn get_file_paths_recusively(path: PathBuf) -> Result<Vec<PathBuf>, PathResolutionError> {
     use std::fs::read_dir;
 
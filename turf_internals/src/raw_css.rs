@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use lightningcss::{
+    printer::PrinterOptions,
+    stylesheet::{ParserOptions, StyleSheet},
+};
+
+use crate::{Codegen, Settings, StyleSheetKind};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RawCssError {
+    #[error("error reading the raw css file '{0}' - {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("error minifying the raw css of {0} - {1}")]
+    Minification(String, String),
+}
+
+/// Css that is embedded verbatim by the `raw_css` and `raw_css_file` macros
+#[derive(Debug)]
+pub struct RawCss {
+    pub css: String,
+    pub version: String,
+    pub css_hash: String,
+    pub compressed_css: Option<Vec<u8>>,
+    pub css_file_name: Option<String>,
+    pub original_style_sheet: StyleSheetKind,
+    pub codegen: Codegen,
+}
+
+pub(crate) fn raw_css_with_settings(
+    style_sheet_input: StyleSheetKind,
+    settings: Settings,
+) -> Result<RawCss, crate::Error> {
+    settings.codegen.check_compression()?;
+    let css = match &style_sheet_input {
+        StyleSheetKind::File(path) => {
+            std::fs::read_to_string(path).map_err(|error| RawCssError::Read(path.clone(), error))?
+        }
+        StyleSheetKind::Inline(css) => css.clone(),
+    };
+    let css = match settings.minify_raw_css {
+        true => minified_css(&css)
+            .map_err(|error| RawCssError::Minification(style_sheet_input.to_string(), error))?,
+        false => css,
+    };
+
+    let version = crate::hashing::style_sheet_version(&css, None, &BTreeMap::new());
+    let mut css_file_output = crate::file_output::CssFileOutput::default();
+    if let Some(file_output) = settings.file_output {
+        css_file_output = crate::file_output::perform_css_file_output(
+            file_output,
+            crate::file_output::StyleSheetCss {
+                css: &css,
+                dark_css: None,
+                version: &version,
+            },
+            &style_sheet_input,
+            &[],
+            None,
+            crate::file_output::is_excluded_from_global_file(&css),
+        )?;
+    }
+
+    Ok(RawCss {
+        version,
+        css_hash: crate::hashing::style_sheet_css_hash(&css),
+        compressed_css: crate::compression::compress_css(&css, settings.codegen.compress),
+        css,
+        css_file_name: css_file_output.css_file_name,
+        original_style_sheet: style_sheet_input,
+        codegen: settings.codegen,
+    })
+}
+
+/// Removes whitespace and comments without transforming the rules for any browser targets
+fn minified_css(css: &str) -> Result<String, String> {
+    let style_sheet =
+        StyleSheet::parse(css, ParserOptions::default()).map_err(|e| e.to_string())?;
+
+    style_sheet
+        .to_css(PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+        })
+        .map(|result| result.code)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Settings, StyleSheetKind};
+
+    use super::raw_css_with_settings;
+
+    #[test]
+    fn keep_css_verbatim() {
+        let css = "/* vendor v1.2.3 */\n.btn  {\n  color: RED;\n  -webkit-box-flex: 1;\n}\n";
+
+        let raw_css =
+            raw_css_with_settings(StyleSheetKind::Inline(css.into()), Settings::default()).unwrap();
+
+        assert_eq!(raw_css.css, css);
+        assert_eq!(raw_css.css_hash, crate::hashing::style_sheet_css_hash(css));
+    }
+
+    #[test]
+    fn minify_raw_css() {
+        let settings = Settings {
+            minify_raw_css: true,
+            ..Settings::default()
+        };
+
+        let raw_css = raw_css_with_settings(
+            StyleSheetKind::Inline(String::from("/* vendor */\n.btn {\n  color: red;\n}\n")),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(raw_css.css, ".btn{color:red}");
+    }
+}
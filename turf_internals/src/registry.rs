@@ -0,0 +1,223 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::StyleSheetKind;
+
+static SEPARATE_OUTPUT_FILES: Mutex<BTreeMap<PathBuf, OsString>> = Mutex::new(BTreeMap::new());
+static GENERATED_CLASS_NAMES: Mutex<BTreeMap<String, OsString>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Could not read internal state")]
+    Mutex,
+    #[error("the separate output file '{output_path}' would be written by both '{first_source}' and '{second_source}' - set `file_output.separate_css_file_naming = \"source_path\"` to derive unique file names from the style sheet paths")]
+    SeparateOutputFileCollision {
+        output_path: PathBuf,
+        first_source: String,
+        second_source: String,
+    },
+    #[error("the generated class name '{class_name}' is used by both '{first_source}' and '{second_source}' - add `<id>` or `<style_sheet_hash>` to `class_names.template` to generate unique class names")]
+    ClassNameCollision {
+        class_name: String,
+        first_source: String,
+        second_source: String,
+    },
+}
+
+pub(crate) fn style_sheet_source(style_sheet: &StyleSheetKind) -> String {
+    match style_sheet {
+        StyleSheetKind::File(path) => format!("{}", path.display()),
+        StyleSheetKind::Inline(style_sheet) => format!(
+            "inline style sheet {:x}",
+            xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes())
+        ),
+    }
+}
+
+/// Identifies a style sheet without losing information, so non-UTF-8 paths that display the same
+/// are still told apart
+fn style_sheet_key(style_sheet: &StyleSheetKind) -> OsString {
+    match style_sheet {
+        StyleSheetKind::File(path) => path.as_os_str().to_owned(),
+        StyleSheetKind::Inline(_) => style_sheet_source(style_sheet).into(),
+    }
+}
+
+fn display_style_sheet_key(key: &OsString) -> String {
+    format!("{}", Path::new(key).display())
+}
+
+pub fn register_separate_output_file(
+    output_path: &PathBuf,
+    style_sheet: &StyleSheetKind,
+) -> Result<(), RegistryError> {
+    let mut separate_output_files = match SEPARATE_OUTPUT_FILES.lock() {
+        Err(_) => return Err(RegistryError::Mutex),
+        Ok(val) => val,
+    };
+
+    let source = style_sheet_key(style_sheet);
+
+    match separate_output_files.get(output_path) {
+        Some(first_source) if *first_source != source => {
+            Err(RegistryError::SeparateOutputFileCollision {
+                output_path: output_path.clone(),
+                first_source: display_style_sheet_key(first_source),
+                second_source: display_style_sheet_key(&source),
+            })
+        }
+        Some(_) => Ok(()),
+        None => {
+            separate_output_files.insert(output_path.clone(), source);
+            Ok(())
+        }
+    }
+}
+
+pub fn is_class_name_registered(class_name: &str) -> bool {
+    GENERATED_CLASS_NAMES
+        .lock()
+        .map(|generated_class_names| generated_class_names.contains_key(class_name))
+        .unwrap_or(false)
+}
+
+/// Registers the generated class names of a style sheet and returns the class names that are
+/// already used by other style sheets
+pub fn register_class_names(
+    class_names: &BTreeMap<String, String>,
+    style_sheet: &StyleSheetKind,
+) -> Result<Vec<RegistryError>, RegistryError> {
+    let mut generated_class_names = match GENERATED_CLASS_NAMES.lock() {
+        Err(_) => return Err(RegistryError::Mutex),
+        Ok(val) => val,
+    };
+
+    let source = style_sheet_key(style_sheet);
+    let mut collisions = Vec::new();
+
+    for (original_class_name, class_name) in class_names {
+        if original_class_name == class_name {
+            continue;
+        }
+
+        match generated_class_names.get(class_name) {
+            Some(first_source) if *first_source != source => {
+                collisions.push(RegistryError::ClassNameCollision {
+                    class_name: class_name.clone(),
+                    first_source: display_style_sheet_key(first_source),
+                    second_source: display_style_sheet_key(&source),
+                });
+            }
+            Some(_) => {}
+            None => {
+                generated_class_names.insert(class_name.clone(), source.clone());
+            }
+        }
+    }
+
+    Ok(collisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use super::{
+        is_class_name_registered, register_class_names, register_separate_output_file,
+        RegistryError,
+    };
+    use crate::StyleSheetKind;
+
+    #[test]
+    fn detect_separate_output_file_collision() {
+        let output_path = PathBuf::from("/registry-test/out/button.css");
+
+        register_separate_output_file(
+            &output_path,
+            &StyleSheetKind::File(PathBuf::from("/registry-test/a/button.scss")),
+        )
+        .unwrap();
+        register_separate_output_file(
+            &output_path,
+            &StyleSheetKind::File(PathBuf::from("/registry-test/a/button.scss")),
+        )
+        .unwrap();
+
+        let result = register_separate_output_file(
+            &output_path,
+            &StyleSheetKind::File(PathBuf::from("/registry-test/b/button.scss")),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RegistryError::SeparateOutputFileCollision { .. })
+        ));
+    }
+
+    #[test]
+    fn detect_class_name_collision() {
+        let mut class_names = BTreeMap::new();
+        class_names.insert(
+            String::from("registry-test"),
+            String::from("registry-test-generated"),
+        );
+        class_names.insert(
+            String::from("registry-test-excluded"),
+            String::from("registry-test-excluded"),
+        );
+
+        register_class_names(
+            &class_names,
+            &StyleSheetKind::File(PathBuf::from("/registry-test/a/style.scss")),
+        )
+        .unwrap();
+
+        assert!(is_class_name_registered("registry-test-generated"));
+        assert!(!is_class_name_registered("registry-test-excluded"));
+
+        let collisions = register_class_names(
+            &class_names,
+            &StyleSheetKind::File(PathBuf::from("/registry-test/b/style.scss")),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            collisions.as_slice(),
+            [RegistryError::ClassNameCollision { class_name, .. }]
+                if class_name == "registry-test-generated"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn distinguish_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let output_path = PathBuf::from("/registry-test/out/non-utf8.css");
+
+        register_separate_output_file(
+            &output_path,
+            &StyleSheetKind::File(PathBuf::from(OsStr::from_bytes(
+                b"/registry-test/\xff.scss",
+            ))),
+        )
+        .unwrap();
+
+        // Both paths are displayed as '/registry-test/\u{fffd}.scss'
+        let result = register_separate_output_file(
+            &output_path,
+            &StyleSheetKind::File(PathBuf::from(OsStr::from_bytes(
+                b"/registry-test/\xfe.scss",
+            ))),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RegistryError::SeparateOutputFileCollision { .. })
+        ));
+    }
+}
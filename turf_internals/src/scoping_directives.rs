@@ -0,0 +1,61 @@
+use regex::Regex;
+
+static SCOPING_DIRECTIVE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+
+/// A `/* turf:global */` or `/* turf:scope */` comment that toggles the scoping of all following rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopingDirective {
+    /// The line of the comment, starting at 0
+    line: u32,
+    /// The column of the comment in UTF-16 code units, starting at 1
+    column: u32,
+    global: bool,
+}
+
+pub fn scoping_directives(css: &str) -> Vec<ScopingDirective> {
+    let scoping_directive = SCOPING_DIRECTIVE.get_or_init(|| {
+        Regex::new(r"/\*\s*turf:(global|scope)\s*\*/").expect("scoping directive regex is valid")
+    });
+
+    scoping_directive
+        .captures_iter(css)
+        .map(|captures| {
+            let start = captures.get(0).expect("match exists").start();
+            let preceding = &css[..start];
+            let line_start = preceding.rfind('\n').map_or(0, |index| index + 1);
+
+            ScopingDirective {
+                line: preceding.matches('\n').count() as u32,
+                column: preceding[line_start..].encode_utf16().count() as u32 + 1,
+                global: &captures[1] == "global",
+            }
+        })
+        .collect()
+}
+
+/// Returns whether the rule at the given location follows a `/* turf:global */` directive that
+/// has not been reverted by a `/* turf:scope */` directive
+pub fn is_global_at(directives: &[ScopingDirective], line: u32, column: u32) -> bool {
+    directives
+        .iter()
+        .take_while(|directive| (directive.line, directive.column) < (line, column))
+        .last()
+        .is_some_and(|directive| directive.global)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_global_at, scoping_directives};
+
+    #[test]
+    fn global_regions() {
+        let css = ".a{}\n/* turf:global */\n.b{} .c{}\n/* turf:scope */ .d{}";
+        let directives = scoping_directives(css);
+
+        assert_eq!(directives.len(), 2);
+        assert!(!is_global_at(&directives, 0, 1));
+        assert!(is_global_at(&directives, 2, 1));
+        assert!(is_global_at(&directives, 2, 6));
+        assert!(!is_global_at(&directives, 3, 18));
+    }
+}
@@ -0,0 +1,127 @@
+use std::convert::Infallible;
+
+use lightningcss::{
+    rules::CssRule,
+    selector::{Component, Selector},
+    stylesheet::{ParserOptions, StyleSheet},
+    traits::ParseWithOptions,
+    visit_types,
+    visitor::{Visit, VisitTypes, Visitor},
+};
+use parcel_selectors::parser::Combinator;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SelectorPrefixError {
+    #[error("selector prefix {0:?} is invalid, it must be a single compound selector such as \"#my-extension-root\"")]
+    Invalid(String),
+}
+
+pub(crate) struct SelectorPrefixer<'i> {
+    pub(crate) prefix: Vec<Component<'i>>,
+}
+
+impl<'i> Visitor<'i> for SelectorPrefixer<'i> {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        if let CssRule::Style(style_rule) = rule {
+            for selector in style_rule.selectors.0.iter_mut() {
+                self.prefix_selector(selector);
+            }
+        }
+
+        rule.visit_children(self)
+    }
+}
+
+impl<'i> SelectorPrefixer<'i> {
+    pub(crate) fn prefix_selector(&self, selector: &mut Selector<'i>) {
+        let is_nested = selector
+            .iter_raw_match_order()
+            .any(|component| matches!(component, Component::Nesting));
+        if is_nested || crate::transformer::is_view_transition_selector(selector) {
+            return;
+        }
+
+        // Compound selectors are iterated from right to left, their components from left to right
+        let mut compound_selectors = Vec::new();
+        let mut iter = selector.iter();
+        loop {
+            let compound_selector: Vec<Component<'i>> = iter.by_ref().cloned().collect();
+            let combinator = iter.next_sequence();
+            compound_selectors.push((combinator, compound_selector));
+            if combinator.is_none() {
+                break;
+            }
+        }
+
+        let mut components: Vec<Component<'i>> = Vec::new();
+        let mut is_leading = true;
+        for (combinator, compound_selector) in compound_selectors.into_iter().rev() {
+            // Leading `html`, `body` and `:root` selectors are merged into the prefix, since the
+            // injected root node takes the place of the document
+            if is_leading && compound_selector.iter().any(is_document_root) {
+                if components.is_empty() {
+                    components.extend(self.prefix.iter().cloned());
+                }
+                components.extend(
+                    compound_selector
+                        .into_iter()
+                        .filter(|component| !is_document_root(component)),
+                );
+                continue;
+            }
+
+            match (is_leading && components.is_empty(), combinator) {
+                (true, _) => {
+                    components.extend(self.prefix.iter().cloned());
+                    components.push(Component::Combinator(Combinator::Descendant));
+                }
+                (false, Some(combinator)) => components.push(Component::Combinator(combinator)),
+                (false, None) => (),
+            }
+            is_leading = false;
+            components.extend(compound_selector);
+        }
+        *selector = Selector::from(components);
+    }
+}
+
+fn is_document_root(component: &Component) -> bool {
+    match component {
+        Component::Root => true,
+        Component::LocalName(local_name) => {
+            matches!(local_name.lower_name.0.as_ref(), "html" | "body")
+        }
+        _ => false,
+    }
+}
+
+/// Nests every selector of a style sheet under the prefix, e.g. for the injected root node of a
+/// browser extension's content script
+pub fn prefix_selectors<'i>(
+    style_sheet: &mut StyleSheet<'i, '_>,
+    prefix: &'i str,
+) -> Result<(), SelectorPrefixError> {
+    let invalid_prefix = || SelectorPrefixError::Invalid(prefix.to_owned());
+    let prefix_selector = Selector::parse_string_with_options(prefix, ParserOptions::default())
+        .map_err(|_| invalid_prefix())?;
+
+    let mut iter = prefix_selector.iter();
+    let prefix_components: Vec<Component<'i>> = iter.by_ref().cloned().collect();
+    if prefix_components.is_empty() || iter.next_sequence().is_some() {
+        return Err(invalid_prefix());
+    }
+
+    style_sheet
+        .visit(&mut SelectorPrefixer {
+            prefix: prefix_components,
+        })
+        .expect("css visitor never fails");
+
+    Ok(())
+}
@@ -5,21 +5,116 @@ use serde::Deserialize;
 use crate::{
     manifest::ManifestError,
     path_utils::{canonicalize, PathResolutionError},
+    settings_overrides::{settings_with_overrides, SettingsOverride},
 };
 
 #[derive(Deserialize, Debug, Default, Clone)]
 pub struct FileOutput {
     pub(crate) global_css_file_path: Option<PathBuf>,
+    pub(crate) global_dark_css_file_path: Option<PathBuf>,
     pub(crate) separate_css_files_path: Option<PathBuf>,
+    pub(crate) partitioned_css_files_path: Option<PathBuf>,
+    pub(crate) partitioned_css_files_url: Option<String>,
+    pub(crate) ast_json_path: Option<PathBuf>,
+    pub(crate) global_css_ranges_path: Option<PathBuf>,
+    pub(crate) fragments_path: Option<PathBuf>,
+    pub(crate) fragments_layer: Option<String>,
+    #[serde(default)]
+    pub(crate) separate_css_file_naming: SeparateCssFileNaming,
+    pub(crate) separate_css_file_name_template: Option<String>,
+    #[serde(default)]
+    pub(crate) source_maps: bool,
+    #[serde(default)]
+    pub(crate) inline_source_maps: bool,
+    #[serde(default)]
+    pub(crate) manifest: bool,
+    pub(crate) typescript_definitions_path: Option<PathBuf>,
+    #[serde(default)]
+    pub(crate) typescript_const_objects: bool,
+    #[serde(default)]
+    pub(crate) relative_to: OutputPathBase,
+    #[serde(default)]
+    pub(crate) global_css_priority: i32,
+    pub(crate) global_css_layer: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SeparateCssFileNaming {
+    #[default]
+    FileName,
+    SourcePath,
+}
+
+/// The directory the output paths are relative to
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputPathBase {
+    #[default]
+    #[serde(rename = "working_dir")]
+    Working,
+    #[serde(rename = "target_dir")]
+    Target,
+    #[serde(rename = "out_dir")]
+    Out,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassNameStrategy {
+    #[default]
+    Template,
+    None,
+    Frequency,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Salt {
+    Value(String),
+    Env { env: String },
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("the environment variable '{0}' referenced by `class_names.salt` is not set")]
+pub struct SaltError(String);
+
+impl Salt {
+    pub(crate) fn value(&self) -> Result<String, SaltError> {
+        match self {
+            Salt::Value(value) => Ok(value.clone()),
+            Salt::Env { env } => std::env::var(env).map_err(|_| SaltError(env.clone())),
+        }
+    }
 }
 
 pub(crate) static DEFAULT_CLASS_NAME_TEMPLATE: &str = "class-<id>";
 
+fn default_class_name_template() -> String {
+    DEFAULT_CLASS_NAME_TEMPLATE.to_owned()
+}
+
+fn default_deny_collisions() -> bool {
+    true
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ClassNameGeneration {
+    #[serde(default = "default_class_name_template")]
     pub(crate) template: String,
     #[serde(default)]
     pub(crate) excludes: Vec<String>,
+    #[serde(default)]
+    pub(crate) excludes_selectors: Vec<String>,
+    pub(crate) lockfile: Option<PathBuf>,
+    #[serde(default)]
+    pub(crate) crate_prefix: bool,
+    #[serde(default)]
+    pub(crate) bem: bool,
+    #[serde(default)]
+    pub(crate) strategy: ClassNameStrategy,
+    pub(crate) salt: Option<Salt>,
+    #[serde(default = "default_deny_collisions")]
+    pub(crate) deny_collisions: bool,
 }
 
 impl Default for ClassNameGeneration {
@@ -27,10 +122,293 @@ impl Default for ClassNameGeneration {
         Self {
             template: DEFAULT_CLASS_NAME_TEMPLATE.to_owned(),
             excludes: vec![],
+            excludes_selectors: vec![],
+            lockfile: None,
+            crate_prefix: false,
+            bem: false,
+            strategy: ClassNameStrategy::Template,
+            salt: None,
+            deny_collisions: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scoping {
+    #[default]
+    Classes,
+    DataAttribute,
+    Wrap,
+    Prose,
+    Shadow,
+}
+
+pub(crate) static DEFAULT_THEME_ATTRIBUTE: &str = "data-theme";
+pub(crate) static DEFAULT_THEME_STORAGE_KEY: &str = "theme";
+
+fn default_theme_attribute() -> String {
+    DEFAULT_THEME_ATTRIBUTE.to_owned()
+}
+
+fn default_theme_storage_key() -> String {
+    DEFAULT_THEME_STORAGE_KEY.to_owned()
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Themes {
+    #[serde(default = "default_theme_attribute")]
+    pub(crate) attribute: String,
+    #[serde(default = "default_theme_storage_key")]
+    pub(crate) storage_key: String,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Self {
+            attribute: DEFAULT_THEME_ATTRIBUTE.to_owned(),
+            storage_key: DEFAULT_THEME_STORAGE_KEY.to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Audits {
+    #[serde(default)]
+    pub(crate) forced_colors: bool,
+    #[serde(default)]
+    pub(crate) forced_colors_fallback: bool,
+}
+
+/// Draft and non-standard syntax the css parser accepts
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CssParser {
+    #[serde(default)]
+    pub(crate) nesting: bool,
+    #[serde(default)]
+    pub(crate) custom_media: bool,
+    #[serde(default)]
+    pub(crate) deep_selector_combinator: bool,
+}
+
+impl From<&CssParser> for lightningcss::stylesheet::ParserFlags {
+    fn from(val: &CssParser) -> Self {
+        let mut flags = lightningcss::stylesheet::ParserFlags::empty();
+        flags.set(Self::NESTING, val.nesting);
+        flags.set(Self::CUSTOM_MEDIA, val.custom_media);
+        flags.set(Self::DEEP_SELECTOR_COMBINATOR, val.deep_selector_combinator);
+        flags
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CodegenStyle {
+    #[default]
+    Struct,
+    Module,
+    Enum,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    #[default]
+    #[serde(rename = "pub")]
+    Pub,
+    #[serde(rename = "pub(crate)")]
+    PubCrate,
+    #[serde(rename = "private")]
+    Private,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierSanitization {
+    #[default]
+    Replace,
+    Strip,
+}
+
+/// The case of the identifiers generated for the class names
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierCase {
+    ScreamingSnake,
+    Snake,
+    Pascal,
+    Camel,
+}
+
+/// The codec the css embedded by the macros is compressed with
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    Brotli,
+    Gzip,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Codegen {
+    #[serde(default)]
+    pub debug_stats: bool,
+    #[serde(default)]
+    pub class_name_sources: bool,
+    #[serde(default)]
+    pub class_name_map: bool,
+    #[serde(default)]
+    pub class_name_lookup: bool,
+    #[serde(default)]
+    pub class_name_metadata: bool,
+    #[serde(default)]
+    pub media_sheets: bool,
+    pub css_artifacts_path: Option<PathBuf>,
+    #[serde(default)]
+    pub values_function: bool,
+    #[serde(default)]
+    pub style: CodegenStyle,
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    #[serde(default)]
+    pub derives: Vec<String>,
+    pub class_name_doc: Option<String>,
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub identifier_sanitization: IdentifierSanitization,
+    pub const_case: Option<IdentifierCase>,
+    pub field_case: Option<IdentifierCase>,
+    pub compress: Option<Compression>,
+}
+
+impl Codegen {
+    /// Rejects the items whose values change with edits of the css, which `css_artifacts_path`
+    /// keeps out of the generated tokens
+    pub(crate) fn check_css_artifacts(&self) -> Result<(), SettingsError> {
+        if self.css_artifacts_path.is_none() {
+            return Ok(());
+        }
+
+        for (enabled, setting) in [
+            (self.media_sheets, "codegen.media_sheets"),
+            (self.class_name_sources, "codegen.class_name_sources"),
+            (self.class_name_metadata, "codegen.class_name_metadata"),
+            (self.compress.is_some(), "codegen.compress"),
+        ] {
+            if enabled {
+                return Err(SettingsError::IncompatibleSettings {
+                    setting,
+                    other_setting: "codegen.css_artifacts_path",
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rejects `compress` if the macros are built without the codecs
+    pub(crate) fn check_compression(&self) -> Result<(), SettingsError> {
+        match self.compress.is_some() && !cfg!(feature = "compression") {
+            true => Err(SettingsError::MissingFeature {
+                setting: "codegen.compress",
+                feature: "compression",
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Rejects `class_name_lookup` if turf is built without the perfect hash map it is generated
+    /// with
+    pub(crate) fn check_class_name_lookup(&self) -> Result<(), SettingsError> {
+        match self.class_name_lookup && !cfg!(feature = "class_name_lookup") {
+            true => Err(SettingsError::MissingFeature {
+                setting: "codegen.class_name_lookup",
+                feature: "class_name_lookup",
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+/// The syntax of the compiled style sheets
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SassInputSyntax {
+    Scss,
+    Indented,
+}
+
+impl From<SassInputSyntax> for grass::InputSyntax {
+    fn from(value: SassInputSyntax) -> Self {
+        match value {
+            SassInputSyntax::Scss => grass::InputSyntax::Scss,
+            SassInputSyntax::Indented => grass::InputSyntax::Sass,
         }
     }
 }
 
+/// The extension of a file loaded by `@use`, `@forward` or `@import`
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SassExtension {
+    Scss,
+    Sass,
+    Css,
+}
+
+impl SassExtension {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SassExtension::Scss => "scss",
+            SassExtension::Sass => "sass",
+            SassExtension::Css => "css",
+        }
+    }
+}
+
+static DEFAULT_SASS_EXTENSIONS: [SassExtension; 3] =
+    [SassExtension::Scss, SassExtension::Sass, SassExtension::Css];
+
+fn default_sass_extensions() -> Vec<SassExtension> {
+    DEFAULT_SASS_EXTENSIONS.to_vec()
+}
+
+fn default_charset() -> bool {
+    true
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Sass {
+    #[serde(default)]
+    pub(crate) quiet: bool,
+    #[serde(default = "default_charset")]
+    pub(crate) charset: bool,
+    pub(crate) input_syntax: Option<SassInputSyntax>,
+    #[serde(default = "default_sass_extensions")]
+    pub(crate) extensions: Vec<SassExtension>,
+}
+
+impl Default for Sass {
+    fn default() -> Self {
+        Self {
+            quiet: false,
+            charset: true,
+            input_syntax: None,
+            extensions: default_sass_extensions(),
+        }
+    }
+}
+
+impl Sass {
+    /// Returns whether files are resolved with every extension the SCSS compiler supports
+    pub(crate) fn has_all_extensions(&self) -> bool {
+        DEFAULT_SASS_EXTENSIONS
+            .iter()
+            .all(|extension| self.extensions.contains(extension))
+    }
+}
+
 pub(crate) static DEFAULT_MINIFY: bool = true;
 
 fn default_minify() -> bool {
@@ -44,11 +422,38 @@ pub struct Settings {
     #[serde(default = "default_minify")]
     pub(crate) minify: bool,
     #[serde(default)]
+    pub(crate) minify_raw_css: bool,
+    #[serde(default)]
     pub(crate) load_paths: Vec<PathBuf>,
-    pub(crate) browser_targets: Option<BrowserVersions>,
+    #[serde(default)]
+    pub(crate) sass: Sass,
+    pub(crate) browser_targets: Option<BrowserTargets>,
     #[serde(default)]
     pub(crate) class_names: ClassNameGeneration,
+    #[serde(default)]
+    pub(crate) scoping: Scoping,
+    #[serde(default)]
+    pub(crate) scope_element_selectors: bool,
+    #[serde(default)]
+    pub(crate) scope_grid_areas: bool,
+    #[serde(default)]
+    pub(crate) split_dark_color_scheme: bool,
+    pub(crate) selector_prefix: Option<String>,
     pub(crate) file_output: Option<FileOutput>,
+    pub(crate) themes: Option<Themes>,
+    #[serde(default)]
+    pub(crate) audits: Audits,
+    #[serde(default)]
+    pub(crate) css_parser: CssParser,
+    #[serde(default)]
+    pub(crate) codegen: Codegen,
+    pub(crate) max_css_size_kb: Option<u64>,
+    #[serde(default)]
+    pub(crate) untrusted_input: bool,
+    #[serde(default)]
+    pub(crate) strict: bool,
+    #[serde(skip)]
+    pub(crate) overrides: Vec<SettingsOverride>,
 }
 
 impl Default for Settings {
@@ -56,10 +461,25 @@ impl Default for Settings {
         Self {
             debug: false,
             minify: DEFAULT_MINIFY,
+            minify_raw_css: false,
             load_paths: Vec::new(),
+            sass: Sass::default(),
             browser_targets: None,
             class_names: ClassNameGeneration::default(),
+            scoping: Scoping::default(),
+            scope_element_selectors: false,
+            scope_grid_areas: false,
+            split_dark_color_scheme: false,
+            selector_prefix: None,
             file_output: None,
+            themes: None,
+            audits: Audits::default(),
+            css_parser: CssParser::default(),
+            codegen: Codegen::default(),
+            max_css_size_kb: None,
+            untrusted_input: false,
+            strict: false,
+            overrides: Vec::new(),
         }
     }
 }
@@ -78,9 +498,17 @@ impl<'a> TryFrom<Settings> for grass::Options<'a> {
     type Error = PathResolutionError;
 
     fn try_from(val: Settings) -> Result<Self, PathResolutionError> {
-        Ok(grass::Options::default()
+        let options = grass::Options::default()
             .style(grass::OutputStyle::Expanded)
-            .load_paths(&val.canonicalized_load_paths()?))
+            .fs(&crate::input_encoding::INPUT_FS)
+            .load_paths(&val.canonicalized_load_paths()?)
+            .quiet(val.sass.quiet)
+            .allows_charset(val.sass.charset);
+
+        Ok(match val.sass.input_syntax {
+            Some(input_syntax) => options.input_syntax(input_syntax.into()),
+            None => options,
+        })
     }
 }
 
@@ -91,14 +519,88 @@ impl<'a> From<Settings> for lightningcss::printer::PrinterOptions<'a> {
             project_root: None,
             targets: val
                 .browser_targets
-                .map(From::<BrowserVersions>::from)
+                .map(|browser_targets| browser_targets.0)
                 .into(),
             analyze_dependencies: None,
             pseudo_classes: None,
+            source_map: None,
+        }
+    }
+}
+
+/// The `browser_targets` setting, either a browserslist query, the browserslist configuration of
+/// the project or the versions of each browser
+///
+/// `Browserslist` comes before `Versions`, which matches every table.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BrowserTargetsConfig {
+    Query(String),
+    Browserslist { from_browserslist: bool },
+    Versions(BrowserVersions),
+}
+
+/// The browser targets with the browserslist query resolved
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "BrowserTargetsConfig")]
+pub struct BrowserTargets(pub(crate) lightningcss::targets::Browsers);
+
+impl TryFrom<BrowserTargetsConfig> for BrowserTargets {
+    type Error = String;
+
+    fn try_from(value: BrowserTargetsConfig) -> Result<Self, Self::Error> {
+        match value {
+            BrowserTargetsConfig::Versions(versions) => Ok(Self(versions.into())),
+            BrowserTargetsConfig::Browserslist {
+                from_browserslist: false,
+            } => Ok(Self(lightningcss::targets::Browsers::default())),
+            BrowserTargetsConfig::Browserslist {
+                from_browserslist: true,
+            } => project_browserslist().map(Self),
+            BrowserTargetsConfig::Query(query) => {
+                match lightningcss::targets::Browsers::from_browserslist([&query]) {
+                    Ok(Some(browsers)) => Ok(Self(browsers)),
+                    Ok(None) => Err(format!(
+                        "the browserslist query '{query}' of `browser_targets` matches no browsers"
+                    )),
+                    Err(error) => Err(format!(
+                        "invalid browserslist query '{query}' in `browser_targets` - {error}"
+                    )),
+                }
+            }
         }
     }
 }
 
+/// Resolves the browserslist configuration of the project, i.e. the `browserslist` key of a
+/// `package.json` or a `.browserslistrc` or `browserslist` file in the manifest directory or one of
+/// its ancestors
+fn project_browserslist() -> Result<lightningcss::targets::Browsers, String> {
+    let options = browserslist::Opts {
+        path: Some(
+            crate::path_utils::manifest_dir()
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        ..browserslist::Opts::default()
+    };
+    let queries: Vec<String> = browserslist::execute(&options)
+        .map_err(|error| format!("could not resolve the browserslist configuration - {error}"))?
+        .iter()
+        .map(|distrib| format!("{} {}", distrib.name(), distrib.version()))
+        .collect();
+
+    match lightningcss::targets::Browsers::from_browserslist(&queries) {
+        Ok(Some(browsers)) => Ok(browsers),
+        Ok(None) => Err(String::from(
+            "the browserslist configuration of the project matches no browsers",
+        )),
+        Err(error) => Err(format!(
+            "could not resolve the browserslist configuration - {error}"
+        )),
+    }
+}
+
 #[derive(Deserialize, Debug, Default, Clone)]
 pub struct BrowserVersions {
     pub android: Option<BrowserVersion>,
@@ -151,13 +653,380 @@ impl From<BrowserVersion> for u32 {
 
 static TURF_SETTINGS: std::sync::OnceLock<Settings> = std::sync::OnceLock::new();
 static TURF_DEV_SETTINGS: std::sync::OnceLock<Settings> = std::sync::OnceLock::new();
+static TURF_NAMED_PROFILE_SETTINGS: std::sync::OnceLock<Option<Settings>> =
+    std::sync::OnceLock::new();
+
+/// The prefix of the environment variables that override settings of the Cargo manifest
+const SETTINGS_ENV_PREFIX: &str = "TURF_";
+
+/// Separates the keys of nested settings in the names of environment variables
+const SETTINGS_ENV_SEPARATOR: &str = "__";
+
+/// Short names of environment variables for settings that are commonly overridden, mapped to the
+/// names following the keys of the settings
+const SETTINGS_ENV_ALIASES: [(&str, &str); 1] = [("CLASS_TEMPLATE", "CLASS_NAMES__TEMPLATE")];
+
+/// Whether the environment variables that match no setting have been reported, the settings of
+/// every profile are read with the same variables
+static UNKNOWN_ENV_OVERRIDES_REPORTED: std::sync::Once = std::sync::Once::new();
+
+/// The environment variable selecting a named settings profile
+const PROFILE_ENV: &str = "TURF_PROFILE";
+
+/// The key of the named profiles in the settings table
+pub(crate) const PROFILES_KEY: &str = "profile";
+
+/// The key naming the profile a named profile falls back to
+pub(crate) const INHERITS_KEY: &str = "inherits";
+
+/// The key of the per target settings in the settings table
+pub(crate) const TARGETS_KEY: &str = "target";
+
+/// The environment variable with the target triple the crate is compiled for
+///
+/// Cargo only sets it for build scripts, which pass it on to the macros with
+/// `cargo:rustc-env=TARGET=...`.
+const TARGET_ENV: &str = "TARGET";
 
 #[derive(Debug, thiserror::Error)]
-#[error("Could not obtain turf settings from the Cargo manifest")]
-pub struct SettingsError(#[from] ManifestError);
+pub enum SettingsError {
+    #[error(
+        "Could not obtain turf settings from the Cargo manifest or the turf configuration file"
+    )]
+    Manifest(#[from] ManifestError),
+    #[error("The turf settings profile '{0}' does not exist")]
+    UnknownProfile(String),
+    #[error("The turf settings profile '{0}' inherits from itself")]
+    ProfileCycle(String),
+    #[error(
+        "The turf setting `{key}` {} is unknown{}",
+        table_description(table),
+        suggestion_description(suggestion)
+    )]
+    UnknownKey {
+        key: String,
+        table: String,
+        suggestion: Option<String>,
+    },
+    #[error(
+        "The environment variable `{variable}` of the turf setting `{key}` is not set or not valid unicode"
+    )]
+    UnsetVariable { variable: String, key: String },
+    #[error("Invalid turf settings override - {0}")]
+    InvalidOverride(String),
+    #[error("Could not resolve the turf browser targets - {0}")]
+    BrowserTargets(String),
+    #[error("The turf setting `{setting}` can't be combined with `{other_setting}`, since its generated items change with the css")]
+    IncompatibleSettings {
+        setting: &'static str,
+        other_setting: &'static str,
+    },
+    #[error("The turf setting `{setting}` requires the `{feature}` feature of turf")]
+    MissingFeature {
+        setting: &'static str,
+        feature: &'static str,
+    },
+    #[error("Could not apply the turf settings of the environment variables {variables}")]
+    EnvOverrides {
+        variables: String,
+        source: Box<toml::de::Error>,
+    },
+}
+
+fn table_description(table: &str) -> String {
+    match table.is_empty() {
+        true => String::from("of the settings table"),
+        false => format!("in the `{table}` table"),
+    }
+}
+
+fn suggestion_description(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean `{suggestion}`?"),
+        None => String::new(),
+    }
+}
+
+/// Sets the settings of `TURF_` environment variables in the settings table of a profile
+///
+/// The rest of a variable's name is the lowercase key of the setting, with `__` separating the
+/// keys of nested settings, e.g. `TURF_CLASS_NAMES__TEMPLATE` sets `class_names.template`, or one
+/// of the short names of `SETTINGS_ENV_ALIASES`, e.g. `TURF_CLASS_TEMPLATE`. Values are parsed as
+/// TOML values and fall back to strings, so `TURF_MINIFY=false` sets a boolean and
+/// `TURF_CLASS_NAMES__TEMPLATE=app-<id>` a string. Returns the names of the applied variables and
+/// of the variables that match no setting, which are left out.
+fn apply_env_overrides(
+    table: &mut toml::Table,
+    variables: impl IntoIterator<Item = (String, String)>,
+) -> (Vec<String>, Vec<String>) {
+    let mut variables: Vec<(String, String)> = variables
+        .into_iter()
+        .filter(|(name, _)| {
+            name.starts_with(SETTINGS_ENV_PREFIX)
+                && name != crate::diagnostics::DIAGNOSTICS_SARIF_ENV
+                && name != PROFILE_ENV
+                && name != crate::manifest::SETTINGS_FILE_ENV
+                && name != crate::path_utils::PROJECT_ROOT_ENV
+        })
+        .collect();
+    variables.sort();
+
+    let mut applied = Vec::new();
+    let mut unknown = Vec::new();
+    for (name, value) in variables {
+        let setting_name = &name[SETTINGS_ENV_PREFIX.len()..];
+        let setting_name = SETTINGS_ENV_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == setting_name)
+            .map_or(setting_name, |(_, setting_name)| setting_name);
+        let keys: Vec<String> = setting_name
+            .split(SETTINGS_ENV_SEPARATOR)
+            .map(str::to_lowercase)
+            .collect();
+        if keys.iter().any(String::is_empty) || !is_setting(&keys) {
+            unknown.push(name);
+            continue;
+        }
+
+        let (key, parents) = keys.split_last().expect("split returns at least one key");
+        let mut current_table = &mut *table;
+        for parent in parents {
+            let entry = current_table
+                .entry(parent.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(toml::Table::new());
+            }
+            current_table = entry.as_table_mut().expect("entry is a table");
+        }
+        current_table.insert(key.clone(), env_value(&value));
+        applied.push(name);
+    }
+
+    (applied, unknown)
+}
+
+/// Returns whether the keys of an environment variable name a setting, e.g. `class_names` and
+/// `template`
+fn is_setting(keys: &[String]) -> bool {
+    let mut value = toml::Value::Boolean(true);
+    for key in keys.iter().rev() {
+        value = toml::Value::Table(toml::Table::from_iter([(key.clone(), value)]));
+    }
+    match value {
+        toml::Value::Table(table) => crate::unknown_settings::unknown_key(&table).is_none(),
+        _ => false,
+    }
+}
+
+/// Replaces `${NAME}` in the string values of a settings table with the value of the environment
+/// variable `NAME`, `$${` is kept as a literal `${`
+///
+/// The variables are looked up with `lookup`, a variable that isn't set is an error naming the
+/// key of the value.
+pub(crate) fn interpolate_variables(
+    table: &mut toml::Table,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<(), SettingsError> {
+    for (key, value) in table.iter_mut() {
+        interpolate_value_variables(value, key, lookup)?;
+    }
+
+    Ok(())
+}
+
+fn interpolate_value_variables(
+    value: &mut toml::Value,
+    key: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<(), SettingsError> {
+    match value {
+        toml::Value::String(string) => *string = interpolated_string(string, key, lookup)?,
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate_value_variables(value, key, lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (nested_key, value) in table.iter_mut() {
+                interpolate_value_variables(value, &format!("{key}.{nested_key}"), lookup)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn interpolated_string(
+    string: &str,
+    key: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<String, SettingsError> {
+    let mut interpolated = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            interpolated.push_str(&rest[..start - 1]);
+            interpolated.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(length) = rest[start + 2..].find('}') else {
+            break;
+        };
+
+        let variable = &rest[start + 2..start + 2 + length];
+        let value = lookup(variable).ok_or_else(|| SettingsError::UnsetVariable {
+            variable: variable.to_owned(),
+            key: key.to_owned(),
+        })?;
+        interpolated.push_str(&rest[..start]);
+        interpolated.push_str(&value);
+        rest = &rest[start + 3 + length..];
+    }
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+fn env_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_owned()))
+}
+
+/// Returns the settings table of a profile
+///
+/// `release` is the `turf` table and `dev` the `turf-dev` table, falling back to the `turf` table.
+/// Named profiles are looked up in the `profile` table of the `turf` table. Settings they don't
+/// set are taken from the profile named by their `inherits` key, which defaults to `release`.
+/// Fails if there is no named profile with the name.
+fn profile_table(
+    turf: Option<&toml::Table>,
+    turf_dev: Option<&toml::Table>,
+    name: &str,
+    inheriting_profiles: &mut Vec<String>,
+) -> Result<toml::Table, SettingsError> {
+    let mut release = turf.cloned().unwrap_or_default();
+    let profiles = match release.remove(PROFILES_KEY) {
+        Some(toml::Value::Table(profiles)) => profiles,
+        _ => toml::Table::new(),
+    };
+
+    match name {
+        "release" => return Ok(release),
+        "dev" => return Ok(dev_table(release, turf_dev)),
+        _ => (),
+    }
+    let Some(toml::Value::Table(mut profile)) = profiles.get(name).cloned() else {
+        return Err(SettingsError::UnknownProfile(name.to_owned()));
+    };
+
+    if inheriting_profiles
+        .iter()
+        .any(|inheriting| inheriting == name)
+    {
+        return Err(SettingsError::ProfileCycle(name.to_owned()));
+    }
+    inheriting_profiles.push(name.to_owned());
+
+    let inherits = match profile.remove(INHERITS_KEY) {
+        Some(toml::Value::String(inherits)) => inherits,
+        _ => String::from("release"),
+    };
+    let mut table = profile_table(turf, turf_dev, &inherits, inheriting_profiles)?;
+    merge_tables(&mut table, profile);
+
+    Ok(table)
+}
+
+/// Returns the settings table of the dev profile
+///
+/// The settings of the `turf-dev` table are merged into the `turf` table, which has to be passed
+/// without its named profiles, so the dev profile only needs to contain the settings that differ.
+fn dev_table(mut release: toml::Table, turf_dev: Option<&toml::Table>) -> toml::Table {
+    if let Some(turf_dev) = turf_dev {
+        merge_tables(&mut release, turf_dev.clone());
+    }
+
+    release
+}
+
+/// Sets the settings of a table in another table, nested tables are merged key by key
+pub(crate) fn merge_tables(table: &mut toml::Table, settings: toml::Table) {
+    for (key, value) in settings {
+        match (table.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(settings)) => {
+                merge_tables(table, settings)
+            }
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Merges the settings of the target in the `target` table of a profile into the profile
+///
+/// The settings of other targets are removed.
+fn apply_target_overrides(table: &mut toml::Table, target: Option<&str>) {
+    let Some(toml::Value::Table(mut targets)) = table.remove(TARGETS_KEY) else {
+        return;
+    };
+
+    if let Some(toml::Value::Table(target_settings)) =
+        target.and_then(|target| targets.remove(target))
+    {
+        merge_tables(table, target_settings);
+    }
+}
+
+/// Deserializes the settings table of a profile after applying the target and environment
+/// overrides to it and interpolating the environment variables of its values, the per style sheet
+/// overrides are deserialized with the same overrides
+///
+/// Unknown keys of the table, including those of the named profiles and targets it contains, are
+/// an error, while environment variables with unknown keys are only reported as a warning, since
+/// other tools might use the `TURF_` prefix as well.
+fn profile_settings(mut table: toml::Table) -> Result<Settings, SettingsError> {
+    if let Some(unknown_key) = crate::unknown_settings::unknown_key(&table) {
+        return Err(unknown_key.into());
+    }
+    table.remove(PROFILES_KEY);
+    apply_target_overrides(&mut table, std::env::var(TARGET_ENV).ok().as_deref());
+    let (applied, unknown) = apply_env_overrides(&mut table, std::env::vars());
+    UNKNOWN_ENV_OVERRIDES_REPORTED.call_once(|| {
+        for name in unknown {
+            crate::compile_warning(
+                &format!(
+                    "the environment variable `{name}` matches no turf setting and is ignored"
+                ),
+                None,
+            );
+        }
+    });
+    interpolate_variables(&mut table, &|name| std::env::var(name).ok())?;
+
+    settings_with_overrides(table, |table| {
+        table.try_into().map_err(|error| match applied.is_empty() {
+            true => SettingsError::Manifest(ManifestError::ReadToml(error)),
+            false => SettingsError::EnvOverrides {
+                variables: applied.join(", "),
+                source: Box::new(error),
+            },
+        })
+    })
+}
 
 impl Settings {
     pub fn get() -> Result<Self, SettingsError> {
+        if let Some(named_profile_settings) = Self::named_profile_settings()? {
+            return Ok(named_profile_settings);
+        }
+
         let dev_settings = Self::dev_profile_settings()?;
         let prod_settings = Self::prod_profile_settings()?;
 
@@ -182,15 +1051,55 @@ impl Settings {
         }
     }
 
+    /// Returns the settings of the profile selected by the `TURF_PROFILE` environment variable
+    ///
+    /// Returns `None` if the variable isn't set, in which case the dev or release settings are
+    /// chosen as usual, and fails if the profile doesn't exist, so a misspelled profile isn't
+    /// silently replaced by the dev or release settings.
+    fn named_profile_settings() -> Result<Option<Self>, SettingsError> {
+        if let Some(named_profile_settings) = TURF_NAMED_PROFILE_SETTINGS.get() {
+            return Ok(named_profile_settings.clone());
+        }
+
+        let named_profile_settings = match std::env::var(PROFILE_ENV) {
+            Ok(name) => {
+                let metadata = crate::manifest::turf_metadata()?;
+                let (turf, turf_dev) = match &metadata {
+                    Some(metadata) => (metadata.turf.as_ref(), metadata.turf_dev.as_ref()),
+                    None => (None, None),
+                };
+
+                Some(profile_settings(profile_table(
+                    turf,
+                    turf_dev,
+                    &name,
+                    &mut Vec::new(),
+                )?)?)
+            }
+            Err(_) => None,
+        };
+
+        TURF_NAMED_PROFILE_SETTINGS
+            .set(named_profile_settings.clone())
+            .expect("internal turf profile settings have already been set, but should be empty");
+
+        Ok(named_profile_settings)
+    }
+
     fn dev_profile_settings() -> Result<Option<Self>, SettingsError> {
         if let Some(turf_dev_settings) = TURF_DEV_SETTINGS.get() {
             return Ok(Some(turf_dev_settings.clone()));
         }
 
-        let dev_settings_maybe = crate::manifest::cargo_manifest()?
-            .package
-            .and_then(|package| package.metadata)
-            .and_then(|metadata| metadata.turf_dev);
+        let dev_settings_maybe = crate::manifest::turf_metadata()?
+            .and_then(|metadata| {
+                let turf_dev = metadata.turf_dev?;
+                let mut release = metadata.turf.unwrap_or_default();
+                release.remove(PROFILES_KEY);
+                Some(dev_table(release, Some(&turf_dev)))
+            })
+            .map(profile_settings)
+            .transpose()?;
 
         if let Some(turf_dev_settings) = dev_settings_maybe.clone() {
             TURF_DEV_SETTINGS
@@ -206,18 +1115,17 @@ impl Settings {
             return Ok(Some(turf_prod_settings.clone()));
         }
 
-        let prod_settings_maybe = crate::manifest::cargo_manifest()?
-            .package
-            .and_then(|package| package.metadata)
-            .and_then(|metadata| metadata.turf);
+        // Environment overrides also apply to crates without turf settings in their manifest
+        let prod_settings_table = crate::manifest::turf_metadata()?
+            .and_then(|metadata| metadata.turf)
+            .unwrap_or_default();
+        let turf_prod_settings = profile_settings(prod_settings_table)?;
 
-        if let Some(turf_prod_settings) = prod_settings_maybe.clone() {
-            TURF_SETTINGS
-                .set(turf_prod_settings)
-                .expect("internal turf settings have already been set, but should be empty");
-        }
+        TURF_SETTINGS
+            .set(turf_prod_settings.clone())
+            .expect("internal turf settings have already been set, but should be empty");
 
-        Ok(prod_settings_maybe)
+        Ok(Some(turf_prod_settings))
     }
 }
 
@@ -299,3 +1207,218 @@ mod debug_tests {
         assert_ne!(selected_settings.class_names, dev_settings.class_names);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        apply_env_overrides, apply_target_overrides, dev_table, interpolate_variables,
+        profile_table, CodegenStyle, Settings, SettingsError,
+    };
+
+    #[test]
+    fn named_profiles() {
+        let turf: toml::Table = toml::from_str(
+            r#"
+            minify = true
+            [class_names]
+            template = "release-<id>"
+            bem = true
+            [profile.bench.class_names]
+            template = "bench-<id>"
+            [profile.release-lto]
+            inherits = "bench"
+            minify = false
+            [profile.profiling]
+            inherits = "dev"
+            [profile.first]
+            inherits = "second"
+            [profile.second]
+            inherits = "first"
+            [profile.broken]
+            inherits = "missing"
+            "#,
+        )
+        .unwrap();
+        let turf_dev: toml::Table = toml::from_str("minify = false").unwrap();
+        let profile =
+            |name: &str| profile_table(Some(&turf), Some(&turf_dev), name, &mut Vec::new());
+
+        let settings: Settings = profile("release-lto").unwrap().try_into().unwrap();
+        assert!(!settings.minify);
+        assert_eq!(settings.class_names.template, "bench-<id>");
+        assert!(settings.class_names.bem);
+
+        let settings: Settings = profile("profiling").unwrap().try_into().unwrap();
+        assert!(!settings.minify);
+        assert_eq!(settings.class_names.template, "release-<id>");
+
+        assert!(matches!(
+            profile("missing"),
+            Err(SettingsError::UnknownProfile(name)) if name == "missing"
+        ));
+        let settings: Settings = profile("dev").unwrap().try_into().unwrap();
+        assert!(!settings.minify);
+        assert!(matches!(
+            profile("first"),
+            Err(SettingsError::ProfileCycle(name)) if name == "first"
+        ));
+        assert!(matches!(
+            profile("broken"),
+            Err(SettingsError::UnknownProfile(name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn dev_settings_merge() {
+        let turf: toml::Table = toml::from_str(
+            "minify = true\nload_paths = [\"styles\"]\n[class_names]\ntemplate = \"app-<id>\"\nbem = true",
+        )
+        .unwrap();
+        let turf_dev: toml::Table =
+            toml::from_str("debug = true\n[class_names]\ntemplate = \"dev-<id>\"").unwrap();
+
+        let settings: Settings = dev_table(turf.clone(), Some(&turf_dev)).try_into().unwrap();
+        assert!(settings.debug);
+        assert!(settings.minify);
+        assert_eq!(settings.load_paths, vec![PathBuf::from("styles")]);
+        assert_eq!(settings.class_names.template, "dev-<id>");
+        assert!(settings.class_names.bem);
+
+        let settings: Settings = dev_table(turf, None).try_into().unwrap();
+        assert!(!settings.debug);
+        assert_eq!(settings.class_names.template, "app-<id>");
+    }
+
+    #[test]
+    fn env_overrides() {
+        let mut table: toml::Table = toml::from_str(
+            "minify = true\nload_paths = [\"styles\"]\n[class_names]\ntemplate = \"class-<id>\"\nbem = true",
+        )
+        .unwrap();
+        let variables = [
+            ("TURF_MINIFY", "false"),
+            ("TURF_LOAD_PATHS", "[\"a\", \"b\"]"),
+            ("TURF_CLASS_TEMPLATE", "app-<id>"),
+            ("TURF_CLASS_TEMPLAT", "typo-<id>"),
+            ("TURF_CODEGEN__STYLE", "\"enum\""),
+            ("TURF_UNRELATED_TOOL", "1"),
+            ("TURF_DIAGNOSTICS_SARIF", "turf.sarif"),
+            ("TURF_SETTINGS_FILE", "turf.json"),
+            ("TURF_PROJECT_ROOT", "/project"),
+            ("CARGO_PKG_NAME", "turf"),
+        ]
+        .map(|(name, value)| (String::from(name), String::from(value)));
+
+        let (applied, unknown) = apply_env_overrides(&mut table, variables);
+
+        assert_eq!(
+            applied,
+            vec![
+                "TURF_CLASS_TEMPLATE",
+                "TURF_CODEGEN__STYLE",
+                "TURF_LOAD_PATHS",
+                "TURF_MINIFY",
+            ]
+        );
+        assert_eq!(unknown, vec!["TURF_CLASS_TEMPLAT", "TURF_UNRELATED_TOOL"]);
+        let settings: Settings = table.try_into().unwrap();
+        assert!(!settings.minify);
+        assert_eq!(
+            settings.load_paths,
+            vec![PathBuf::from("a"), PathBuf::from("b")]
+        );
+        assert_eq!(settings.class_names.template, "app-<id>");
+        assert!(settings.class_names.bem);
+        assert_eq!(settings.codegen.style, CodegenStyle::Enum);
+    }
+
+    #[test]
+    fn variable_interpolation() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            load_paths = ["${DESIGN_SYSTEM_DIR}/scss", "styles"]
+            minify = true
+
+            [class_names]
+            template = "${PREFIX}-<id>-$${literal}"
+            "#,
+        )
+        .unwrap();
+        let lookup = |name: &str| match name {
+            "DESIGN_SYSTEM_DIR" => Some(String::from("/opt/design")),
+            "PREFIX" => Some(String::from("app")),
+            _ => None,
+        };
+
+        interpolate_variables(&mut table, &lookup).unwrap();
+        let settings: Settings = table.try_into().unwrap();
+        assert_eq!(
+            settings.load_paths,
+            vec![PathBuf::from("/opt/design/scss"), PathBuf::from("styles")]
+        );
+        assert_eq!(settings.class_names.template, "app-<id>-${literal}");
+
+        let mut table: toml::Table =
+            toml::from_str("[file_output]\nglobal_css_file_path = \"${OUT_DIR}/global.css\"")
+                .unwrap();
+        assert!(matches!(
+            interpolate_variables(&mut table, &lookup),
+            Err(SettingsError::UnsetVariable { variable, key })
+                if variable == "OUT_DIR" && key == "file_output.global_css_file_path"
+        ));
+    }
+
+    #[test]
+    fn target_overrides() {
+        let turf: toml::Table = toml::from_str(
+            r#"
+            minify = false
+            [class_names]
+            template = "class-<id>"
+            [target.wasm32-unknown-unknown]
+            minify = true
+            [target.wasm32-unknown-unknown.class_names]
+            bem = true
+            [target.x86_64-unknown-linux-gnu]
+            debug = true
+            "#,
+        )
+        .unwrap();
+
+        let mut table = turf.clone();
+        apply_target_overrides(&mut table, Some("wasm32-unknown-unknown"));
+        let settings: Settings = table.try_into().unwrap();
+        assert!(settings.minify);
+        assert!(!settings.debug);
+        assert_eq!(settings.class_names.template, "class-<id>");
+        assert!(settings.class_names.bem);
+        // Collisions are denied unless the class names table allows them
+        assert!(settings.class_names.deny_collisions);
+
+        let mut table = turf;
+        apply_target_overrides(&mut table, None);
+        assert!(!table.contains_key("target"));
+        let settings: Settings = table.try_into().unwrap();
+        assert!(!settings.minify);
+    }
+
+    #[test]
+    fn browserslist_browser_targets() {
+        let settings: Settings =
+            toml::from_str("browser_targets = \"chrome 100, firefox 100\"").unwrap();
+        let browsers = settings.browser_targets.unwrap().0;
+        assert_eq!(browsers.chrome, Some(100 << 16));
+        assert_eq!(browsers.firefox, Some(100 << 16));
+        assert_eq!(browsers.safari, None);
+
+        let settings: Settings = toml::from_str("[browser_targets]\nsafari = [15, 4]").unwrap();
+        assert_eq!(
+            settings.browser_targets.unwrap().0.safari,
+            Some(15 << 16 | 4 << 8)
+        );
+
+        assert!(toml::from_str::<Settings>("browser_targets = \"not a query\"").is_err());
+    }
+}
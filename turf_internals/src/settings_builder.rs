@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use crate::settings::{
+    BrowserTargets, BrowserTargetsConfig, Codegen, FileOutput, Scoping, Settings, SettingsError,
+};
+
+/// Builds [`Settings`] without a Cargo manifest, e.g. in build scripts and tools
+///
+/// Settings that aren't set keep their defaults, the same defaults as for a crate without turf
+/// settings in its manifest.
+///
+/// ```
+/// let settings = turf_internals::Settings::builder()
+///     .minify(false)
+///     .load_path("styles")
+///     .class_name_template("app-<id>")
+///     .browser_targets("defaults")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SettingsBuilder {
+    settings: Settings,
+    browser_targets: Option<String>,
+}
+
+impl Settings {
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+
+    /// Reads the settings from a table with the same keys as the `[package.metadata.turf]` table
+    ///
+    /// Unknown keys are an error, just like in the manifest, and environment variables in values
+    /// are interpolated. Named profiles, per target settings and environment overrides are not
+    /// applied.
+    pub fn from_table(mut table: toml::Table) -> Result<Self, SettingsError> {
+        if let Some(unknown_key) = crate::unknown_settings::unknown_key(&table) {
+            return Err(unknown_key.into());
+        }
+        table.remove(crate::settings::PROFILES_KEY);
+        table.remove(crate::settings::TARGETS_KEY);
+        crate::settings::interpolate_variables(&mut table, &|name| std::env::var(name).ok())?;
+
+        crate::settings_overrides::settings_with_overrides(table, |table| {
+            table.try_into().map_err(|error| {
+                SettingsError::Manifest(crate::manifest::ManifestError::ReadToml(error))
+            })
+        })
+    }
+}
+
+impl SettingsBuilder {
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.settings.debug = debug;
+        self
+    }
+
+    pub fn minify(mut self, minify: bool) -> Self {
+        self.settings.minify = minify;
+        self
+    }
+
+    pub fn minify_raw_css(mut self, minify_raw_css: bool) -> Self {
+        self.settings.minify_raw_css = minify_raw_css;
+        self
+    }
+
+    /// Adds a path to the load paths of the SCSS compilation
+    pub fn load_path(mut self, load_path: impl Into<PathBuf>) -> Self {
+        self.settings.load_paths.push(load_path.into());
+        self
+    }
+
+    /// Sets the browser targets to the browsers matching a browserslist query, e.g. `"defaults"`
+    ///
+    /// The query is resolved by [`SettingsBuilder::build`].
+    pub fn browser_targets(mut self, query: impl Into<String>) -> Self {
+        self.browser_targets = Some(query.into());
+        self
+    }
+
+    pub fn class_name_template(mut self, template: impl Into<String>) -> Self {
+        self.settings.class_names.template = template.into();
+        self
+    }
+
+    /// Adds a regular expression matching class names that are not renamed
+    pub fn class_name_exclude(mut self, exclude: impl Into<String>) -> Self {
+        self.settings.class_names.excludes.push(exclude.into());
+        self
+    }
+
+    pub fn class_name_lockfile(mut self, lockfile: impl Into<PathBuf>) -> Self {
+        self.settings.class_names.lockfile = Some(lockfile.into());
+        self
+    }
+
+    pub fn scoping(mut self, scoping: Scoping) -> Self {
+        self.settings.scoping = scoping;
+        self
+    }
+
+    pub fn selector_prefix(mut self, selector_prefix: impl Into<String>) -> Self {
+        self.settings.selector_prefix = Some(selector_prefix.into());
+        self
+    }
+
+    /// Writes the css of all style sheets to a single file
+    pub fn global_css_file_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings
+            .file_output
+            .get_or_insert_with(FileOutput::default)
+            .global_css_file_path = Some(path.into());
+        self
+    }
+
+    /// Writes the css of each style sheet to a file in a directory
+    pub fn separate_css_files_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings
+            .file_output
+            .get_or_insert_with(FileOutput::default)
+            .separate_css_files_path = Some(path.into());
+        self
+    }
+
+    pub fn codegen(mut self, codegen: Codegen) -> Self {
+        self.settings.codegen = codegen;
+        self
+    }
+
+    pub fn max_css_size_kb(mut self, max_css_size_kb: u64) -> Self {
+        self.settings.max_css_size_kb = Some(max_css_size_kb);
+        self
+    }
+
+    pub fn untrusted_input(mut self, untrusted_input: bool) -> Self {
+        self.settings.untrusted_input = untrusted_input;
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.settings.strict = strict;
+        self
+    }
+
+    pub fn build(self) -> Result<Settings, SettingsError> {
+        let mut settings = self.settings;
+
+        if let Some(query) = self.browser_targets {
+            settings.browser_targets = Some(
+                BrowserTargets::try_from(BrowserTargetsConfig::Query(query))
+                    .map_err(SettingsError::BrowserTargets)?,
+            );
+        }
+
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::settings::{Scoping, Settings, SettingsError};
+
+    #[test]
+    fn build_settings() {
+        let settings = Settings::builder()
+            .minify(false)
+            .load_path("styles")
+            .load_path("vendor")
+            .class_name_template("app-<id>")
+            .scoping(Scoping::DataAttribute)
+            .global_css_file_path("target/global.css")
+            .browser_targets("safari 12")
+            .build()
+            .unwrap();
+
+        assert!(!settings.minify);
+        assert_eq!(
+            settings.load_paths,
+            vec![PathBuf::from("styles"), PathBuf::from("vendor")]
+        );
+        assert_eq!(settings.class_names.template, "app-<id>");
+        assert_eq!(settings.scoping, Scoping::DataAttribute);
+        assert_eq!(
+            settings.file_output.unwrap().global_css_file_path,
+            Some(PathBuf::from("target/global.css"))
+        );
+        assert_eq!(settings.browser_targets.unwrap().0.safari, Some(12 << 16));
+
+        assert!(matches!(
+            Settings::builder().browser_targets("not a query").build(),
+            Err(SettingsError::BrowserTargets(_))
+        ));
+    }
+
+    #[test]
+    fn settings_from_table() {
+        let settings = Settings::from_table(
+            toml::from_str("minify = false\n[class_names]\nbem = true").unwrap(),
+        )
+        .unwrap();
+        assert!(!settings.minify);
+        assert!(settings.class_names.bem);
+
+        assert!(matches!(
+            Settings::from_table(toml::from_str("minifiy = false").unwrap()),
+            Err(SettingsError::UnknownKey { .. })
+        ));
+    }
+}
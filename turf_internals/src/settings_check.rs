@@ -0,0 +1,206 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use regex::RegexSet;
+
+use crate::Settings;
+
+/// Whether `check_settings` reported invalid settings, which the style sheet macros refer to
+/// instead of repeating the error
+static SETTINGS_CHECK_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// The placeholders of the class name template that differ between class names
+const DISTINCT_CLASS_NAME_PLACEHOLDERS: [&str; 4] = [
+    "<original_name>",
+    "<id>",
+    "<name_hash>",
+    "<name_hash_short>",
+];
+
+/// Loads and validates the settings without compiling a style sheet
+///
+/// Settings that have no effect are reported as warnings, settings that fail the compilation of
+/// every style sheet are returned as an error.
+pub fn check_settings() -> Result<(), crate::Error> {
+    let result = Settings::get()
+        .map_err(crate::Error::from)
+        .and_then(|settings| {
+            validate_settings(&settings)?;
+            Ok(settings)
+        });
+
+    match result {
+        Ok(settings) => {
+            for warning in settings_warnings(&settings) {
+                crate::compile_warning(&warning, None);
+            }
+            Ok(())
+        }
+        Err(error) => {
+            SETTINGS_CHECK_FAILED.store(true, Ordering::Relaxed);
+            crate::diagnostics::record_error(&error, None);
+            Err(error)
+        }
+    }
+}
+
+/// Returns the error of invalid settings, which only refers to the error of `check_settings` if it
+/// has already been reported
+pub(crate) fn settings_error(error: crate::settings::SettingsError) -> crate::Error {
+    match SETTINGS_CHECK_FAILED.load(Ordering::Relaxed) {
+        true => crate::Error::CheckedSettings,
+        false => crate::Error::Settings(error),
+    }
+}
+
+/// Resolves the settings that are only resolved while compiling a style sheet otherwise
+fn validate_settings(settings: &Settings) -> Result<(), crate::Error> {
+    if let Some(salt) = &settings.class_names.salt {
+        salt.value()?;
+    }
+    settings.canonicalized_load_paths()?;
+    settings.codegen.check_css_artifacts()?;
+    settings.codegen.check_compression()?;
+    settings.codegen.check_class_name_lookup()?;
+    RegexSet::new(&settings.class_names.excludes)?;
+    RegexSet::new(&settings.class_names.excludes_selectors)?;
+    if let Some(file_output) = &settings.file_output {
+        crate::file_output::resolved_output_paths(file_output.clone())?;
+    }
+
+    Ok(())
+}
+
+fn settings_warnings(settings: &Settings) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if !DISTINCT_CLASS_NAME_PLACEHOLDERS
+        .iter()
+        .any(|placeholder| settings.class_names.template.contains(placeholder))
+    {
+        warnings.push(format!(
+            "the class name template {:?} contains none of the placeholders {}, so every class name of a style sheet is the same",
+            settings.class_names.template,
+            DISTINCT_CLASS_NAME_PLACEHOLDERS.join(", ")
+        ));
+    }
+
+    if let Some(file_output) = &settings.file_output {
+        if file_output.global_dark_css_file_path.is_some() && !settings.split_dark_color_scheme {
+            warnings.push(String::from(
+                "`file_output.global_dark_css_file_path` has no effect without `split_dark_color_scheme`",
+            ));
+        }
+        if file_output.global_css_ranges_path.is_some()
+            && file_output.global_css_file_path.is_none()
+        {
+            warnings.push(String::from(
+                "`file_output.global_css_ranges_path` has no effect without `file_output.global_css_file_path`",
+            ));
+        }
+        if file_output.source_maps && file_output.separate_css_files_path.is_none() {
+            warnings.push(String::from(
+                "`file_output.source_maps` has no effect without `file_output.separate_css_files_path`",
+            ));
+        }
+        if file_output.manifest
+            && file_output.separate_css_files_path.is_none()
+            && file_output.global_css_file_path.is_none()
+        {
+            warnings.push(String::from(
+                "`file_output.manifest` has no effect without `file_output.separate_css_files_path` or `file_output.global_css_file_path`",
+            ));
+        }
+        if file_output.typescript_const_objects && file_output.typescript_definitions_path.is_none()
+        {
+            warnings.push(String::from(
+                "`file_output.typescript_const_objects` has no effect without `file_output.typescript_definitions_path`",
+            ));
+        }
+        if file_output.global_css_layer.is_some()
+            && file_output.global_css_file_path.is_none()
+            && file_output.global_dark_css_file_path.is_none()
+        {
+            warnings.push(String::from(
+                "`file_output.global_css_layer` has no effect without `file_output.global_css_file_path`",
+            ));
+        }
+        if file_output.fragments_layer.is_some() && file_output.fragments_path.is_none() {
+            warnings.push(String::from(
+                "`file_output.fragments_layer` has no effect without `file_output.fragments_path`",
+            ));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::{ClassNameGeneration, FileOutput, Salt, Settings};
+
+    use super::{settings_warnings, validate_settings};
+
+    #[test]
+    fn ineffective_settings() {
+        assert!(settings_warnings(&Settings::default()).is_empty());
+
+        let settings = Settings {
+            class_names: ClassNameGeneration {
+                template: String::from("static-class"),
+                ..Default::default()
+            },
+            file_output: Some(FileOutput {
+                global_dark_css_file_path: Some("dark.css".into()),
+                fragments_layer: Some(String::from("components")),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let warnings = settings_warnings(&settings);
+
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("\"static-class\""));
+        assert!(warnings[1].contains("`file_output.global_dark_css_file_path`"));
+        assert!(warnings[2].contains("`file_output.fragments_layer`"));
+    }
+
+    #[test]
+    fn invalid_settings() {
+        assert!(validate_settings(&Settings::default()).is_ok());
+
+        let settings = Settings {
+            class_names: ClassNameGeneration {
+                excludes: vec![String::from("(unclosed")],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_settings(&settings),
+            Err(crate::Error::ExcludePattern(_))
+        ));
+
+        let settings = Settings {
+            class_names: ClassNameGeneration {
+                salt: Some(Salt::Env {
+                    env: String::from("TURF_CHECK_SETTINGS_UNSET_SALT"),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_settings(&settings),
+            Err(crate::Error::Salt(_))
+        ));
+
+        let settings = Settings {
+            load_paths: vec!["does/not/exist".into()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_settings(&settings),
+            Err(crate::Error::PathResolution(_))
+        ));
+    }
+}
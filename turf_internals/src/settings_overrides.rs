@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::settings::{Settings, SettingsError};
+
+/// The key of the per style sheet settings in the settings table
+pub(crate) const OVERRIDES_KEY: &str = "overrides";
+
+/// The key of the path pattern of an override
+pub(crate) const PATTERN_KEY: &str = "pattern";
+
+/// The settings of the style sheets whose path matches a pattern
+#[derive(Debug, Clone)]
+pub(crate) struct SettingsOverride {
+    pattern: Regex,
+    settings: Box<Settings>,
+}
+
+/// Deserializes a settings table together with the settings of each of its overrides
+///
+/// The `overrides` key holds an array of tables with a `pattern` and the settings that differ for
+/// the style sheets matching it. The settings of an override are merged into the settings table,
+/// so they are deserialized with `deserialize`, just like the table itself.
+pub(crate) fn settings_with_overrides(
+    mut table: toml::Table,
+    deserialize: impl Fn(toml::Table) -> Result<Settings, SettingsError>,
+) -> Result<Settings, SettingsError> {
+    let overrides = match table.remove(OVERRIDES_KEY) {
+        Some(toml::Value::Array(overrides)) => overrides,
+        Some(_) => {
+            return Err(SettingsError::InvalidOverride(format!(
+                "`{OVERRIDES_KEY}` is not an array of tables"
+            )))
+        }
+        None => Vec::new(),
+    };
+
+    let mut settings = deserialize(table.clone())?;
+    for settings_override in overrides {
+        let toml::Value::Table(mut override_table) = settings_override else {
+            return Err(SettingsError::InvalidOverride(format!(
+                "`{OVERRIDES_KEY}` is not an array of tables"
+            )));
+        };
+        let Some(toml::Value::String(pattern)) = override_table.remove(PATTERN_KEY) else {
+            return Err(SettingsError::InvalidOverride(format!(
+                "an override has no `{PATTERN_KEY}` string"
+            )));
+        };
+
+        let mut override_settings = table.clone();
+        crate::settings::merge_tables(&mut override_settings, override_table);
+        settings.overrides.push(SettingsOverride {
+            pattern: path_pattern(&pattern),
+            settings: Box::new(deserialize(override_settings)?),
+        });
+    }
+
+    Ok(settings)
+}
+
+impl Settings {
+    /// Returns the settings of a style sheet, which are those of the last override with a pattern
+    /// matching its path relative to the manifest directory
+    pub(crate) fn for_style_sheet(self, path: &Path) -> Settings {
+        let relative_path = crate::path_utils::relative_to_manifest_dir(path);
+        let relative_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        match self
+            .overrides
+            .iter()
+            .rev()
+            .find(|settings_override| settings_override.pattern.is_match(&relative_path))
+        {
+            Some(settings_override) => *settings_override.settings.clone(),
+            None => self,
+        }
+    }
+}
+
+/// Converts a glob pattern to a regular expression matching the whole path
+///
+/// `*` and `?` match any characters and a single character of a path segment, `**` matches any
+/// number of segments, e.g. `styles/vendor/**` matches every file in `styles/vendor`.
+fn path_pattern(pattern: &str) -> Regex {
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                match chars.peek() {
+                    Some('/') => {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    }
+                    _ => regex.push_str(".*"),
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            char => regex.push_str(&regex::escape(&char.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).expect("escaped glob patterns are valid regular expressions")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{path_pattern, settings_with_overrides};
+    use crate::settings::{Settings, SettingsError};
+
+    #[test]
+    fn path_patterns() {
+        let vendor = path_pattern("styles/vendor/**");
+        assert!(vendor.is_match("styles/vendor/normalize.css"));
+        assert!(vendor.is_match("styles/vendor/swiper/swiper.scss"));
+        assert!(!vendor.is_match("styles/app.scss"));
+
+        let partials = path_pattern("./src/**/_*.scss");
+        assert!(partials.is_match("src/_colors.scss"));
+        assert!(partials.is_match("src/components/_button.scss"));
+        assert!(!partials.is_match("src/components/button.scss"));
+
+        let single_char = path_pattern("src/?.css");
+        assert!(single_char.is_match("src/a.css"));
+        assert!(!single_char.is_match("src/ab.css"));
+        assert!(!path_pattern("src/*.css").is_match("src/nested/a.css"));
+    }
+
+    #[test]
+    fn style_sheet_overrides() {
+        let table: toml::Table = toml::from_str(
+            r#"
+            minify = false
+
+            [class_names]
+            template = "app-<id>"
+
+            [[overrides]]
+            pattern = "styles/vendor/**"
+            class_names = { excludes = [".*"] }
+
+            [[overrides]]
+            pattern = "styles/vendor/minified/*.css"
+            minify = true
+            "#,
+        )
+        .unwrap();
+        let settings = settings_with_overrides(table, |table| {
+            table.try_into().map_err(|error| {
+                SettingsError::Manifest(crate::manifest::ManifestError::ReadToml(error))
+            })
+        })
+        .unwrap();
+
+        let app = settings
+            .clone()
+            .for_style_sheet(Path::new("styles/app.scss"));
+        assert!(!app.minify);
+        assert!(app.class_names.excludes.is_empty());
+
+        let vendor = settings
+            .clone()
+            .for_style_sheet(Path::new("styles/vendor/swiper.css"));
+        assert!(!vendor.minify);
+        assert_eq!(vendor.class_names.template, "app-<id>");
+        assert_eq!(vendor.class_names.excludes, vec![String::from(".*")]);
+
+        let minified = settings.for_style_sheet(Path::new("styles/vendor/minified/reset.css"));
+        assert!(minified.minify);
+        assert!(minified.class_names.excludes.is_empty());
+
+        assert!(matches!(
+            Settings::from_table(toml::from_str("[[overrides]]\nminify = true").unwrap()),
+            Err(SettingsError::InvalidOverride(_))
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::{SourceLocation, StyleSheetKind};
+
+/// Finds the first definition of every class name in the source of a style sheet file
+///
+/// Locations are formatted as `path:line:column` with lines and columns starting at 1. Class
+/// names that can't be found in the file itself, e.g. names built from SCSS parent selectors or
+/// defined in imported files, are located by the file path only. Inline style sheets have no
+/// source file and are skipped.
+pub fn class_name_sources(
+    style_sheet: &StyleSheetKind,
+    class_names: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let StyleSheetKind::File(path) = style_sheet else {
+        return BTreeMap::new();
+    };
+    let source = std::fs::read_to_string(path).unwrap_or_default();
+    let path = path.display();
+
+    class_names
+        .keys()
+        .map(|class_name| {
+            let location = match find_class_name(&source, class_name) {
+                Some((line, column)) => format!("{path}:{line}:{column}"),
+                None => format!("{path}"),
+            };
+
+            (class_name.clone(), location)
+        })
+        .collect()
+}
+
+/// Narrows the location of a style sheet file down to the first definition of a class name
+pub fn class_name_location(
+    style_sheet_location: &SourceLocation,
+    class_name: &str,
+) -> SourceLocation {
+    let source = std::fs::read_to_string(&style_sheet_location.path).unwrap_or_default();
+
+    SourceLocation {
+        path: style_sheet_location.path.clone(),
+        position: find_class_name(&source, class_name),
+    }
+}
+
+fn find_class_name(source: &str, class_name: &str) -> Option<(usize, usize)> {
+    let class_selector = Regex::new(&format!(r"\.{}(?:[^\w-]|$)", regex::escape(class_name)))
+        .expect("escaped class name regex is valid");
+    let start = class_selector.find(source)?.start();
+    let preceding = &source[..start];
+    let line_start = preceding.rfind('\n').map_or(0, |index| index + 1);
+
+    Some((
+        preceding.matches('\n').count() + 1,
+        preceding[line_start..].chars().count() + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_class_name;
+
+    #[test]
+    fn find_class_names() {
+        let source = ".card-title {\n  color: red;\n}\n\n.card {\n  &:hover .card-title {}\n}\n";
+
+        assert_eq!(find_class_name(source, "card"), Some((5, 1)));
+        assert_eq!(find_class_name(source, "card-title"), Some((1, 1)));
+        assert_eq!(find_class_name(source, "card__title"), None);
+    }
+}
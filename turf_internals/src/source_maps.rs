@@ -0,0 +1,82 @@
+use parcel_sourcemap::{SourceMap, SourceMapError};
+
+use crate::{path_utils, StyleSheetKind};
+
+/// Creates the source map the transformed css is mapped to while printing it
+///
+/// The transformer doesn't know the style sheet, so its source is named by [`named_source_map`]
+/// afterwards.
+pub(crate) fn unnamed_source_map(css: &str) -> Result<SourceMap, SourceMapError> {
+    let mut source_map = SourceMap::new("/");
+    source_map.add_source("");
+    source_map.set_source_content(0, css)?;
+
+    Ok(source_map)
+}
+
+/// Copies a source map of the transformer with its source named after the style sheet
+///
+/// The source is the css the SCSS compiled to, since grass doesn't generate source maps, so it is
+/// named after the SCSS file with a `.css` extension appended, e.g. `src/button.scss.css`.
+pub(crate) fn named_source_map(
+    source_map: &SourceMap,
+    style_sheet: &StyleSheetKind,
+) -> Result<SourceMap, SourceMapError> {
+    let source = match style_sheet {
+        StyleSheetKind::File(path) => {
+            format!(
+                "{}.css",
+                path_utils::relative_to_manifest_dir(path).display()
+            )
+        }
+        StyleSheetKind::Inline(style_sheet) => {
+            format!(
+                "{:x?}.css",
+                xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes())
+            )
+        }
+    };
+
+    let mut named_source_map = SourceMap::new("/");
+    named_source_map.add_source(&source);
+    named_source_map.set_source_content(0, source_map.get_source_content(0)?)?;
+    for mapping in source_map.get_mappings() {
+        named_source_map.add_mapping(
+            mapping.generated_line,
+            mapping.generated_column,
+            mapping.original,
+        );
+    }
+
+    Ok(named_source_map)
+}
+
+/// The comment linking css to its source map
+pub(crate) fn source_mapping_url_comment(url: &str) -> String {
+    format!("\n/*# sourceMappingURL={url} */")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StyleSheetKind;
+
+    use super::{named_source_map, unnamed_source_map};
+
+    #[test]
+    fn named_source() {
+        let mut source_map = unnamed_source_map(".a {\n  color: red;\n}\n").unwrap();
+        source_map.add_mapping(
+            0,
+            0,
+            Some(parcel_sourcemap::OriginalLocation::new(0, 0, 0, None)),
+        );
+
+        let mut named_source_map =
+            named_source_map(&source_map, &StyleSheetKind::File("src/button.scss".into())).unwrap();
+
+        assert_eq!(
+            named_source_map.to_json(None).unwrap(),
+            r#"{"version":3,"sourceRoot":null,"mappings":"AAAA","sources":["src/button.scss.css"],"sourcesContent":[".a {\n  color: red;\n}\n"],"names":[]}"#
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::{collections::BTreeMap, path::Path};
+
+use regex::Regex;
+
+use crate::exports::extract_exports;
+
+const THEME_EXPORT_PREFIX: &str = "turf-theme-";
+
+static THEME_ANNOTATION: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+static THEME_LENGTH: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+
+/// The value of a top level SCSS variable, typed by the way it was compiled
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeValue {
+    /// A number without a unit, e.g. `1.5`
+    Number(f64),
+    /// A number with a unit, e.g. `8px` or `50%`
+    Length(f64, String),
+    /// Any other value, e.g. `#123456`, `bold` or `1px solid red`
+    Text(String),
+}
+
+impl ThemeValue {
+    pub fn parse(value: &str) -> Self {
+        let theme_length = THEME_LENGTH.get_or_init(|| {
+            Regex::new(r"^([+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?)([a-zA-Z]+|%)?$")
+                .expect("theme length regex is valid")
+        });
+
+        let number = theme_length.captures(value).and_then(|captures| {
+            let number = captures[1].parse().ok()?;
+            Some((number, captures.get(2).map(|unit| unit.as_str().to_owned())))
+        });
+
+        match number {
+            Some((number, None)) => ThemeValue::Number(number),
+            Some((number, Some(unit))) => ThemeValue::Length(number, unit),
+            None => ThemeValue::Text(unquote(value).to_owned()),
+        }
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|value| value.strip_suffix(quote))
+        })
+        .unwrap_or(value)
+}
+
+/// Returns whether the top level variables of a style sheet are reflected into a `Theme` struct
+/// by a `/* turf:theme */` annotation
+pub fn is_theme_style_sheet(css: &str) -> bool {
+    THEME_ANNOTATION
+        .get_or_init(|| {
+            Regex::new(r"/\*\s*turf:theme\s*\*/").expect("theme annotation regex is valid")
+        })
+        .is_match(css)
+}
+
+/// Creates a style sheet that loads the style sheet at the path as a module and exports each of
+/// its top level variables, which are then collected by [`take_theme_variables`]
+///
+/// Variables of turf itself, like `$turf-breakpoints`, are left out.
+pub fn theme_export(path: &Path) -> String {
+    let url = path.to_string_lossy().replace('\\', "/");
+
+    format!(
+        r#"@use "sass:meta";
+@use "{url}" as turf-theme;
+:export {{
+  @each $name, $value in meta.module-variables("turf-theme") {{
+    @if str-index($name, "turf-") != 1 {{
+      turf-theme-#{{$name}}: meta.inspect($value);
+    }}
+  }}
+}}
+"#
+    )
+}
+
+/// Collects the variables exported by [`theme_export`] from the compiled css
+pub fn take_theme_variables(css: &str) -> BTreeMap<String, String> {
+    let (_, exports) = extract_exports(css);
+
+    exports
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let variable = name.strip_prefix(THEME_EXPORT_PREFIX)?.to_owned();
+            Some((variable, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{is_theme_style_sheet, take_theme_variables, ThemeValue};
+
+    #[test]
+    fn theme_annotation() {
+        assert!(is_theme_style_sheet(
+            "/* turf:theme */\n.test {\n  color: red;\n}"
+        ));
+        assert!(!is_theme_style_sheet(".test {\n  color: red;\n}"));
+    }
+
+    #[test]
+    fn take_theme_exports() {
+        let css = ".test {\n  color: red;\n}\n\n:export {\n  turf-theme-primary: #123456;\n  turf-theme-gap: 8px;\n}\n";
+
+        assert_eq!(
+            take_theme_variables(css),
+            BTreeMap::from([
+                (String::from("gap"), String::from("8px")),
+                (String::from("primary"), String::from("#123456")),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_theme_values() {
+        assert_eq!(ThemeValue::parse("1.5"), ThemeValue::Number(1.5));
+        assert_eq!(
+            ThemeValue::parse("8px"),
+            ThemeValue::Length(8.0, String::from("px"))
+        );
+        assert_eq!(
+            ThemeValue::parse("50%"),
+            ThemeValue::Length(50.0, String::from("%"))
+        );
+        assert_eq!(
+            ThemeValue::parse("#123456"),
+            ThemeValue::Text(String::from("#123456"))
+        );
+        assert_eq!(
+            ThemeValue::parse("\"Helvetica\""),
+            ThemeValue::Text(String::from("Helvetica"))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::settings::Themes;
+
+fn js_string_literal(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            // Prevents the snippet from closing the surrounding script element early
+            '<' => literal.push_str("\\u003c"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Creates a minimal script that applies the stored theme to the root element before the first paint
+///
+/// The theme is read from `localStorage` and falls back to `dark` or `light` depending on the
+/// `prefers-color-scheme` media query.
+pub fn theme_bootstrap(themes: &Themes) -> String {
+    format!(
+        "(function(){{var t;try{{t=localStorage.getItem({storage_key})}}catch(e){{}}if(!t)t=matchMedia(\"(prefers-color-scheme: dark)\").matches?\"dark\":\"light\";document.documentElement.setAttribute({attribute},t)}})()",
+        storage_key = js_string_literal(&themes.storage_key),
+        attribute = js_string_literal(&themes.attribute),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{js_string_literal, theme_bootstrap};
+    use crate::settings::Themes;
+
+    #[test]
+    fn default_theme_bootstrap() {
+        let bootstrap = theme_bootstrap(&Themes::default());
+
+        assert!(bootstrap.contains("localStorage.getItem(\"theme\")"));
+        assert!(bootstrap.contains("setAttribute(\"data-theme\",t)"));
+    }
+
+    #[test]
+    fn escape_js_string_literal() {
+        assert_eq!(
+            js_string_literal("a\"b\\c</script>"),
+            "\"a\\\"b\\\\c\\u003c/script>\""
+        );
+    }
+}
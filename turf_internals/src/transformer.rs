@@ -1,28 +1,153 @@
 use lightningcss::{
-    selector::{Component, Selector},
-    stylesheet::{ParserOptions, StyleSheet},
+    printer::PrinterOptions,
+    properties::{
+        animation::AnimationName,
+        contain::ContainerNameList,
+        custom::{CustomProperty, CustomPropertyName},
+        font::FontFamily,
+        grid::{GridLine, GridTemplateAreas},
+        list::{CounterStyle, ListStyleType},
+        Property,
+    },
+    rules::{font_face::FontFaceProperty, keyframes::KeyframesName, CssRule},
+    selector::{Component, PseudoElement, Selector, ViewTransitionPartName},
+    stylesheet::{MinifyOptions, ParserOptions, StyleSheet},
+    targets::{Features, Targets},
+    traits::ToCss,
+    values::ident::CustomIdent,
     visit_types,
     visitor::{Visit, VisitTypes, Visitor},
 };
+use parcel_selectors::{
+    attr::{AttrSelectorOperator, ParsedCaseSensitivity},
+    parser::Combinator,
+};
 use regex::RegexSet;
-use std::{collections::HashMap, convert::Infallible};
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::Infallible,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    bem::parse_bem_class_name,
+    composition::{ComposedClassName, CompositionError},
+    forced_colors::ForcedColorsIssue,
+    scoping_directives::{is_global_at, ScopingDirective},
+    settings::{ClassNameStrategy, SaltError, Scoping},
+};
 
+const SCOPE_ATTRIBUTE: &str = "data-turf-scope";
+const MAX_CLASS_NAME_GENERATION_ATTEMPTS: usize = 16;
 const CHARSET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_-";
 
 #[derive(thiserror::Error, Debug)]
 pub enum TransformationVisitorInitializationError {
     #[error("error obtaining random id - {0}")]
     RandError(#[from] getrandom::Error),
-    #[error("class name exclude pattern invalid - {0}")]
+    #[error("class name or selector exclude pattern invalid - {0}")]
     RegexError(#[from] regex::Error),
+    #[error(transparent)]
+    Salt(#[from] SaltError),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ScopedNameKind {
+    ContainerName,
+    CounterStyle,
+    ViewTransitionName,
+    Keyframes,
+    GridArea,
+}
+
+impl std::fmt::Display for ScopedNameKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScopedNameKind::ContainerName => write!(f, "container name"),
+            ScopedNameKind::CounterStyle => write!(f, "counter style"),
+            ScopedNameKind::ViewTransitionName => write!(f, "view transition name"),
+            ScopedNameKind::Keyframes => write!(f, "keyframes name"),
+            ScopedNameKind::GridArea => write!(f, "grid area name"),
+        }
+    }
+}
+
+/// Collects the names of all `@counter-style` and `@keyframes` rules and of all grid areas, so
+/// only counter styles, animations and grid areas defined in the style sheet itself are scoped
+#[derive(Default)]
+struct DefinedNameCollector {
+    counter_styles: HashSet<String>,
+    keyframes: HashSet<String>,
+    grid_areas: HashSet<String>,
+}
+
+impl<'i> Visitor<'i> for DefinedNameCollector {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(RULES | PROPERTIES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        match rule {
+            CssRule::CounterStyle(counter_style_rule) => {
+                self.counter_styles
+                    .insert(counter_style_rule.name.0.to_string());
+            }
+            CssRule::Keyframes(keyframes_rule) => {
+                self.keyframes
+                    .insert(keyframes_name(&keyframes_rule.name).to_owned());
+            }
+            _ => (),
+        }
+
+        rule.visit_children(self)
+    }
+
+    fn visit_property(&mut self, property: &mut Property<'i>) -> Result<(), Self::Error> {
+        if let Some(GridTemplateAreas::Areas { areas, .. }) = grid_template_areas(property) {
+            self.grid_areas.extend(areas.iter().flatten().cloned());
+        }
+
+        Ok(())
+    }
 }
 
 pub struct TransformationVisitor {
-    pub(crate) classes: HashMap<String, String>,
+    pub(crate) classes: BTreeMap<String, String>,
+    pub(crate) global_classes: BTreeMap<String, String>,
+    pub(crate) container_names: BTreeMap<String, String>,
+    pub(crate) counter_styles: BTreeMap<String, String>,
+    pub(crate) view_transition_names: BTreeMap<String, String>,
+    pub(crate) keyframes: BTreeMap<String, String>,
+    pub(crate) parts: BTreeMap<String, String>,
+    pub(crate) css_variables: BTreeMap<String, String>,
+    pub(crate) font_families: BTreeMap<String, String>,
+    pub(crate) grid_areas: BTreeMap<String, String>,
+    pub(crate) compositions: BTreeMap<String, Vec<ComposedClassName>>,
+    pub(crate) composition_error: Option<CompositionError>,
+    pub(crate) shadow_dom_incompatible_selectors: Vec<String>,
+    pub(crate) defined_counter_styles: HashSet<String>,
+    pub(crate) defined_keyframes: HashSet<String>,
+    pub(crate) defined_grid_areas: HashSet<String>,
+    pub(crate) scoping_directives: Vec<ScopingDirective>,
+    pub(crate) is_global_rule: bool,
+    pub(crate) locked_classes: BTreeMap<String, String>,
+    pub(crate) bem_blocks: BTreeMap<String, String>,
+    pub(crate) frequency_class_names: BTreeMap<String, String>,
     pub(crate) random_number_generator: oorandom::Rand32,
     pub(crate) class_name_template: String,
+    pub(crate) crate_prefix: Option<String>,
+    pub(crate) bem: bool,
+    pub(crate) class_name_strategy: ClassNameStrategy,
+    pub(crate) salt: String,
     pub(crate) class_name_exclude_patterns: RegexSet,
+    pub(crate) selector_exclude_patterns: RegexSet,
     pub(crate) style_sheet_hash: String,
+    pub(crate) scoping: Scoping,
+    pub(crate) scope_element_selectors: bool,
+    pub(crate) scope_grid_areas: bool,
+    pub(crate) scope_id: String,
     pub(crate) debug: bool,
 }
 
@@ -30,16 +155,427 @@ impl TransformationVisitor {
     fn try_new(
         settings: &crate::Settings,
         style_sheet_hash: &str,
+        locked_classes: BTreeMap<String, String>,
+        defined_names: DefinedNameCollector,
+        scoping_directives: Vec<ScopingDirective>,
     ) -> Result<Self, TransformationVisitorInitializationError> {
         let class_name_generation = settings.class_names.clone();
-        Ok(Self {
+        let mut visitor = Self {
             debug: settings.debug,
             classes: Default::default(),
+            global_classes: Default::default(),
+            container_names: Default::default(),
+            counter_styles: Default::default(),
+            view_transition_names: Default::default(),
+            keyframes: Default::default(),
+            parts: Default::default(),
+            css_variables: Default::default(),
+            font_families: Default::default(),
+            grid_areas: Default::default(),
+            compositions: Default::default(),
+            composition_error: None,
+            shadow_dom_incompatible_selectors: Default::default(),
+            defined_counter_styles: defined_names.counter_styles,
+            defined_keyframes: defined_names.keyframes,
+            defined_grid_areas: defined_names.grid_areas,
+            scoping_directives,
+            is_global_rule: false,
+            locked_classes,
+            bem_blocks: Default::default(),
+            frequency_class_names: Default::default(),
             random_number_generator: oorandom::Rand32::new(random_seed()?),
             class_name_template: class_name_generation.template,
+            crate_prefix: class_name_generation
+                .crate_prefix
+                .then(|| std::env::var("CARGO_PKG_NAME").ok())
+                .flatten()
+                .map(|package_name| sanitize_crate_prefix(&package_name)),
+            bem: class_name_generation.bem,
+            class_name_strategy: class_name_generation.strategy,
+            salt: match &class_name_generation.salt {
+                Some(salt) => salt.value()?,
+                None => String::new(),
+            },
             class_name_exclude_patterns: RegexSet::new(class_name_generation.excludes)?,
+            selector_exclude_patterns: RegexSet::new(class_name_generation.excludes_selectors)?,
             style_sheet_hash: String::from(style_sheet_hash),
-        })
+            scoping: settings.scoping,
+            scope_element_selectors: settings.scope_element_selectors,
+            scope_grid_areas: settings.scope_grid_areas,
+            scope_id: String::new(),
+        };
+        // The scope id is derived from the style sheet rather than randomized, so it is the same
+        // in every build and can be put into the class names by the `<scope_id>` placeholder
+        let scope_id = scope_id(style_sheet_hash, &visitor.salt);
+        visitor.class_name_template = visitor.class_name_template.replace("<scope_id>", &scope_id);
+        // The scope id is used as root class if selectors are nested under it
+        visitor.scope_id = if visitor.scoping == Scoping::Wrap
+            || visitor.scoping == Scoping::Prose
+            || visitor.scoping == Scoping::Classes && visitor.scope_element_selectors
+        {
+            visitor.prefixed_class_name(apply_template(
+                &visitor.class_name_template,
+                "scope",
+                &scope_id,
+                style_sheet_hash,
+                &visitor.salt,
+            ))
+        } else {
+            scope_id
+        };
+
+        Ok(visitor)
+    }
+
+    fn is_excluded(&self, name: &str) -> bool {
+        !self.class_name_exclude_patterns.is_empty()
+            && self.class_name_exclude_patterns.is_match(name)
+    }
+
+    fn is_excluded_selector(&self, selector: &Selector) -> bool {
+        !self.selector_exclude_patterns.is_empty()
+            && self.selector_exclude_patterns.is_match(
+                &selector
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default(),
+            )
+    }
+
+    fn scoped_names_mut(&mut self, kind: ScopedNameKind) -> &mut BTreeMap<String, String> {
+        match kind {
+            ScopedNameKind::ContainerName => &mut self.container_names,
+            ScopedNameKind::CounterStyle => &mut self.counter_styles,
+            ScopedNameKind::ViewTransitionName => &mut self.view_transition_names,
+            ScopedNameKind::Keyframes => &mut self.keyframes,
+            ScopedNameKind::GridArea => &mut self.grid_areas,
+        }
+    }
+
+    fn scoped_name(&mut self, kind: ScopedNameKind, name: &str) -> String {
+        if let Some(scoped_name) = self.scoped_names_mut(kind).get(name) {
+            return scoped_name.clone();
+        }
+
+        // Like class names, the names are kept if they aren't scoped by the class name strategy
+        let scoped_name =
+            if self.is_excluded(name) || self.class_name_strategy == ClassNameStrategy::None {
+                name.to_owned()
+            } else {
+                let id = self.randomized_class_id(6);
+                let scoped_name = apply_template(
+                    &self.class_name_template,
+                    name,
+                    &id,
+                    &self.style_sheet_hash,
+                    &self.salt,
+                );
+                match &self.crate_prefix {
+                    Some(crate_prefix) => format!("{crate_prefix}-{scoped_name}"),
+                    None => scoped_name,
+                }
+            };
+
+        if self.debug {
+            crate::compile_message(&format!("{kind} mapping - {name:?} = {scoped_name:?}"));
+        }
+
+        self.scoped_names_mut(kind)
+            .insert(name.to_owned(), scoped_name.clone());
+
+        scoped_name
+    }
+
+    fn scope_container_names(&mut self, container_names: &mut ContainerNameList) {
+        if let ContainerNameList::Names(names) = container_names {
+            for name in names.iter_mut() {
+                let scoped_name = self.scoped_name(ScopedNameKind::ContainerName, &name.0 .0);
+                name.0 = CustomIdent(scoped_name.into());
+            }
+        }
+    }
+
+    fn scope_counter_style(&mut self, list_style_type: &mut ListStyleType) {
+        if let ListStyleType::CounterStyle(CounterStyle::Name(name)) = list_style_type {
+            if self.defined_counter_styles.contains(name.0.as_ref()) {
+                let scoped_name = self.scoped_name(ScopedNameKind::CounterStyle, &name.0);
+                *name = CustomIdent(scoped_name.into());
+            }
+        }
+    }
+
+    fn scope_animation_name(&mut self, name: &mut AnimationName) {
+        match name {
+            AnimationName::Ident(ident) if self.defined_keyframes.contains(ident.0.as_ref()) => {
+                let scoped_name = self.scoped_name(ScopedNameKind::Keyframes, &ident.0);
+                *ident = CustomIdent(scoped_name.into());
+            }
+            AnimationName::String(string) if self.defined_keyframes.contains(string.as_ref()) => {
+                let scoped_name = self.scoped_name(ScopedNameKind::Keyframes, string);
+                *string = scoped_name.into();
+            }
+            _ => (),
+        }
+    }
+
+    fn scope_grid_template_areas(&mut self, template_areas: &mut GridTemplateAreas) {
+        if let GridTemplateAreas::Areas { areas, .. } = template_areas {
+            for area in areas.iter_mut().flatten() {
+                *area = match self.scope_grid_areas {
+                    true => self.scoped_name(ScopedNameKind::GridArea, area),
+                    false => {
+                        self.grid_areas.insert(area.clone(), area.clone());
+                        area.clone()
+                    }
+                };
+            }
+        }
+    }
+
+    /// Scopes the grid area a grid line refers to, either by the name of the area or by one of its
+    /// implicit `-start` and `-end` line names
+    fn scope_grid_line(&mut self, line: &mut GridLine) {
+        if !self.scope_grid_areas {
+            return;
+        }
+        let name = match line {
+            GridLine::Area { name } => name,
+            GridLine::Line {
+                name: Some(name), ..
+            }
+            | GridLine::Span {
+                name: Some(name), ..
+            } => name,
+            _ => return,
+        };
+
+        let area_line = ["", "-start", "-end"].into_iter().find_map(|suffix| {
+            let area = name.0.strip_suffix(suffix)?;
+            self.defined_grid_areas
+                .contains(area)
+                .then(|| (area.to_owned(), suffix))
+        });
+        if let Some((area, suffix)) = area_line {
+            let scoped_name = self.scoped_name(ScopedNameKind::GridArea, &area);
+            *name = CustomIdent(format!("{scoped_name}{suffix}").into());
+        }
+    }
+
+    fn scope_view_transition_name(&mut self, name: &mut CustomIdent) {
+        if !matches!(name.0.as_ref(), "none" | "auto") {
+            let scoped_name = self.scoped_name(ScopedNameKind::ViewTransitionName, &name.0);
+            *name = CustomIdent(scoped_name.into());
+        }
+    }
+
+    fn append_scope_attribute(&self, selector: &mut Selector) {
+        if !is_view_transition_selector(selector) {
+            selector.append(Component::AttributeInNoNamespace {
+                local_name: SCOPE_ATTRIBUTE.into(),
+                operator: AttrSelectorOperator::Includes,
+                value: self.scope_id.clone().into(),
+                case_sensitivity: ParsedCaseSensitivity::CaseSensitive,
+                never_matches: false,
+            });
+        }
+    }
+
+    fn wrap_selector<'i>(&self, selector: &mut Selector<'i>) {
+        let is_nested = selector
+            .iter_raw_match_order()
+            .any(|component| matches!(component, Component::Nesting));
+        if is_nested || is_view_transition_selector(selector) {
+            return;
+        }
+
+        let root_class: Component<'i> = Component::Class(self.scope_id.clone().into());
+        let mut is_root = false;
+        for component in selector.iter_mut_raw_match_order() {
+            if matches!(component, Component::Root) {
+                *component = root_class.clone();
+                is_root = true;
+            }
+        }
+
+        if !is_root {
+            self.prepend_root_class(selector);
+        }
+    }
+
+    fn prepend_root_class<'i>(&self, selector: &mut Selector<'i>) {
+        // Compound selectors are iterated from right to left, their components from left to right
+        let mut compound_selectors = Vec::new();
+        let mut iter = selector.iter();
+        loop {
+            let compound_selector: Vec<Component<'i>> = iter.by_ref().cloned().collect();
+            let combinator = iter.next_sequence();
+            compound_selectors.push((combinator, compound_selector));
+            if combinator.is_none() {
+                break;
+            }
+        }
+
+        let mut components = vec![
+            Component::Class(self.scope_id.clone().into()),
+            Component::Combinator(Combinator::Descendant),
+        ];
+        for (combinator, compound_selector) in compound_selectors.into_iter().rev() {
+            if let Some(combinator) = combinator {
+                components.push(Component::Combinator(combinator));
+            }
+            components.extend(compound_selector);
+        }
+        *selector = Selector::from(components);
+    }
+
+    fn check_shadow_dom_compatibility(&mut self, selector: &Selector) {
+        // These selectors only match the document or its root element and never match elements
+        // inside of a shadow tree
+        let is_incompatible = is_view_transition_selector(selector)
+            || selector
+                .iter_raw_match_order()
+                .any(|component| match component {
+                    Component::Root => true,
+                    Component::LocalName(local_name) => {
+                        matches!(local_name.lower_name.0.as_ref(), "html" | "body")
+                    }
+                    _ => false,
+                });
+
+        if is_incompatible {
+            self.shadow_dom_incompatible_selectors.push(
+                selector
+                    .to_css_string(PrinterOptions::default())
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    fn prefixed_class_name(&self, class_name: String) -> String {
+        match &self.crate_prefix {
+            Some(crate_prefix) => format!("{crate_prefix}-{class_name}"),
+            None => class_name,
+        }
+    }
+
+    /// Assigns the shortest ids to the most frequently used class names, skipping class names
+    /// that are already taken by other style sheets
+    fn assign_frequency_class_names(&mut self, class_name_counts: &BTreeMap<String, usize>) {
+        let mut index = 0;
+        for class_name in crate::class_name_frequency::by_frequency(class_name_counts) {
+            if self.is_excluded(class_name) {
+                continue;
+            }
+            if let Some(locked_class_name) = self.locked_classes.get(class_name) {
+                self.frequency_class_names
+                    .insert(class_name.to_owned(), locked_class_name.clone());
+                continue;
+            }
+
+            let new_class_name = loop {
+                let id = crate::class_name_frequency::short_id(index);
+                index += 1;
+                let new_class_name = self.prefixed_class_name(apply_template(
+                    &self.class_name_template,
+                    class_name,
+                    &id,
+                    &self.style_sheet_hash,
+                    &self.salt,
+                ));
+
+                let is_taken = self
+                    .frequency_class_names
+                    .values()
+                    .chain(self.locked_classes.values())
+                    .any(|value| *value == new_class_name)
+                    || crate::registry::is_class_name_registered(&new_class_name);
+                if !is_taken || !self.class_name_template.contains("<id>") {
+                    break new_class_name;
+                }
+            };
+            self.frequency_class_names
+                .insert(class_name.to_owned(), new_class_name);
+        }
+    }
+
+    /// Returns the size the css would have if the class names assigned by frequency used
+    /// randomized ids instead
+    fn randomized_css_size(
+        &self,
+        css_size: usize,
+        class_name_counts: &BTreeMap<String, usize>,
+    ) -> usize {
+        let randomized_id = "a".repeat(6);
+        let saved_bytes: usize = self
+            .frequency_class_names
+            .iter()
+            .filter(|(class_name, _)| !self.locked_classes.contains_key(*class_name))
+            .map(|(class_name, new_class_name)| {
+                let randomized_class_name = self.prefixed_class_name(apply_template(
+                    &self.class_name_template,
+                    class_name,
+                    &randomized_id,
+                    &self.style_sheet_hash,
+                    &self.salt,
+                ));
+                let count = class_name_counts.get(new_class_name).copied().unwrap_or(0);
+                count
+                    * randomized_class_name
+                        .len()
+                        .saturating_sub(new_class_name.len())
+            })
+            .sum();
+
+        css_size + saved_bytes
+    }
+
+    /// Collects a custom property without scoping it, keyed by its name without the leading `--`
+    fn collect_css_variable(&mut self, name: &str) {
+        if let Some(key) = name.strip_prefix("--").filter(|key| !key.is_empty()) {
+            self.css_variables.insert(key.to_owned(), name.to_owned());
+        }
+    }
+
+    fn bem_class_name(&mut self, class_name: &str) -> String {
+        if let Some(bem_class_name) = self
+            .classes
+            .get(class_name)
+            .or_else(|| self.locked_classes.get(class_name))
+        {
+            return bem_class_name.clone();
+        }
+
+        // Only the block is scoped, elements and modifiers keep their original suffix
+        let block = parse_bem_class_name(class_name).block;
+        let suffix = &class_name[block.len()..];
+
+        let scoped_block = match self.bem_blocks.get(block) {
+            Some(scoped_block) => scoped_block.clone(),
+            None => {
+                let scoped_block = match self.locked_bem_block(block) {
+                    Some(scoped_block) => scoped_block,
+                    None => {
+                        self.randomized_class_name(block.to_owned(), self.style_sheet_hash.clone())
+                    }
+                };
+                self.bem_blocks
+                    .insert(block.to_owned(), scoped_block.clone());
+                scoped_block
+            }
+        };
+
+        format!("{scoped_block}{suffix}")
+    }
+
+    fn locked_bem_block(&self, block: &str) -> Option<String> {
+        self.locked_classes
+            .iter()
+            .find_map(|(original_class_name, class_name)| {
+                let suffix = original_class_name.strip_prefix(block)?;
+                (parse_bem_class_name(original_class_name).block == block)
+                    .then(|| class_name.strip_suffix(suffix))
+                    .flatten()
+                    .map(ToOwned::to_owned)
+            })
     }
 
     fn randomized_class_id(&mut self, length: u32) -> String {
@@ -65,16 +601,39 @@ impl TransformationVisitor {
     }
 
     fn randomized_class_name(&mut self, class_name: String, style_sheet_hash: String) -> String {
-        match self.classes.get(&class_name) {
+        match self
+            .classes
+            .get(&class_name)
+            .or_else(|| self.locked_classes.get(&class_name))
+        {
             Some(random_class_name) => random_class_name.clone(),
             None => {
-                let id: String = self.randomized_class_id(6);
-                apply_template(
-                    &self.class_name_template,
-                    &class_name,
-                    &id,
-                    &style_sheet_hash,
-                )
+                let mut attempts = 0;
+                loop {
+                    let id: String = self.randomized_class_id(6);
+                    let mut new_class_name = apply_template(
+                        &self.class_name_template,
+                        &class_name,
+                        &id,
+                        &style_sheet_hash,
+                        &self.salt,
+                    );
+                    if let Some(crate_prefix) = &self.crate_prefix {
+                        new_class_name = format!("{crate_prefix}-{new_class_name}");
+                    }
+                    attempts += 1;
+
+                    // Regenerate the id if the class name is already taken by another class
+                    // or style sheet, as long as the template makes a different result possible
+                    let is_taken = self.classes.values().any(|value| *value == new_class_name)
+                        || crate::registry::is_class_name_registered(&new_class_name);
+                    if !is_taken
+                        || !self.class_name_template.contains("<id>")
+                        || attempts >= MAX_CLASS_NAME_GENERATION_ATTEMPTS
+                    {
+                        break new_class_name;
+                    }
+                }
             }
         }
     }
@@ -84,26 +643,229 @@ impl<'i> Visitor<'i> for TransformationVisitor {
     type Error = Infallible;
 
     fn visit_types(&self) -> VisitTypes {
-        visit_types!(SELECTORS)
+        visit_types!(SELECTORS | RULES | PROPERTIES)
+    }
+
+    fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Result<(), Self::Error> {
+        if let CssRule::Style(style_rule) = rule {
+            self.is_global_rule = is_global_at(
+                &self.scoping_directives,
+                style_rule.loc.line,
+                style_rule.loc.column,
+            );
+
+            // Compositions are collected before the selectors are visited, so they refer to the
+            // original class names
+            match crate::composition::take_compositions(style_rule) {
+                Ok(Some((class_name, composed))) => self
+                    .compositions
+                    .entry(class_name)
+                    .or_default()
+                    .extend(composed),
+                Ok(None) => (),
+                Err(error) => {
+                    self.composition_error.get_or_insert(error);
+                }
+            }
+        }
+
+        match rule {
+            CssRule::Container(container_rule) => {
+                if let Some(name) = &mut container_rule.name {
+                    let scoped_name = self.scoped_name(ScopedNameKind::ContainerName, &name.0 .0);
+                    name.0 = CustomIdent(scoped_name.into());
+                }
+            }
+            CssRule::Style(style_rule) if self.scoping == Scoping::Wrap && !self.is_global_rule => {
+                // The original selectors are visited first, so the root class is not collected
+                // as a class name of the style sheet
+                style_rule.visit_children(self)?;
+                for selector in style_rule.selectors.0.iter_mut() {
+                    if !self.is_excluded_selector(selector) {
+                        self.wrap_selector(selector);
+                    }
+                }
+                return Ok(());
+            }
+            CssRule::Style(style_rule)
+                if self.scoping == Scoping::Prose && !self.is_global_rule =>
+            {
+                // Wrapping the root class in `:where()` keeps the specificity of the original
+                // selectors, so class names of components within the content take precedence
+                let root_class =
+                    Selector::from(vec![Component::Class(self.scope_id.clone().into())]);
+                let prefixer = crate::selector_prefix::SelectorPrefixer {
+                    prefix: vec![Component::Where(Box::new([root_class]))],
+                };
+                style_rule.visit_children(self)?;
+                for selector in style_rule.selectors.0.iter_mut() {
+                    if !self.is_excluded_selector(selector) {
+                        prefixer.prefix_selector(selector);
+                    }
+                }
+                return Ok(());
+            }
+            CssRule::Style(style_rule) if self.scoping == Scoping::Shadow => {
+                for selector in style_rule.selectors.0.iter() {
+                    if !self.is_excluded_selector(selector) {
+                        self.check_shadow_dom_compatibility(selector);
+                    }
+                }
+            }
+            CssRule::Style(style_rule)
+                if self.scoping == Scoping::Classes
+                    && self.scope_element_selectors
+                    && !self.is_global_rule =>
+            {
+                // The original selectors are visited first, so the root class is not renamed
+                style_rule.visit_children(self)?;
+                for selector in style_rule.selectors.0.iter_mut() {
+                    if is_bare_element_selector(selector) && !self.is_excluded_selector(selector) {
+                        self.prepend_root_class(selector);
+                    }
+                }
+                return Ok(());
+            }
+            CssRule::Style(style_rule)
+                if self.scoping == Scoping::DataAttribute && !self.is_global_rule =>
+            {
+                for selector in style_rule.selectors.0.iter_mut() {
+                    if !self.is_excluded_selector(selector) {
+                        self.append_scope_attribute(selector);
+                    }
+                }
+            }
+            CssRule::CounterStyle(counter_style_rule) => {
+                let scoped_name =
+                    self.scoped_name(ScopedNameKind::CounterStyle, &counter_style_rule.name.0);
+                counter_style_rule.name = CustomIdent(scoped_name.into());
+            }
+            CssRule::Keyframes(keyframes_rule) => {
+                let scoped_name = self.scoped_name(
+                    ScopedNameKind::Keyframes,
+                    keyframes_name(&keyframes_rule.name),
+                );
+                keyframes_rule.name = KeyframesName::Ident(CustomIdent(scoped_name.into()));
+            }
+            CssRule::Property(property_rule) => self.collect_css_variable(&property_rule.name.0),
+            CssRule::FontFace(font_face_rule) => {
+                // Font families are referenced by their exact name and are therefore collected
+                // without being scoped
+                for property in &font_face_rule.properties {
+                    if let FontFaceProperty::FontFamily(FontFamily::FamilyName(name)) = property {
+                        self.font_families
+                            .insert(name.to_string(), name.to_string());
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        rule.visit_children(self)
+    }
+
+    fn visit_property(&mut self, property: &mut Property<'i>) -> Result<(), Self::Error> {
+        match property {
+            Property::ContainerName(container_names) => self.scope_container_names(container_names),
+            Property::Container(container) => self.scope_container_names(&mut container.name),
+            Property::ListStyleType(list_style_type) => self.scope_counter_style(list_style_type),
+            Property::ListStyle(list_style) => {
+                self.scope_counter_style(&mut list_style.list_style_type)
+            }
+            Property::ViewTransitionName(name) => self.scope_view_transition_name(name),
+            Property::GridTemplateAreas(areas) => self.scope_grid_template_areas(areas),
+            Property::GridTemplate(template) => self.scope_grid_template_areas(&mut template.areas),
+            Property::Grid(grid) => self.scope_grid_template_areas(&mut grid.areas),
+            Property::GridRowStart(line)
+            | Property::GridRowEnd(line)
+            | Property::GridColumnStart(line)
+            | Property::GridColumnEnd(line) => self.scope_grid_line(line),
+            Property::GridRow(grid_row) => {
+                self.scope_grid_line(&mut grid_row.start);
+                self.scope_grid_line(&mut grid_row.end);
+            }
+            Property::GridColumn(grid_column) => {
+                self.scope_grid_line(&mut grid_column.start);
+                self.scope_grid_line(&mut grid_column.end);
+            }
+            Property::GridArea(grid_area) => {
+                self.scope_grid_line(&mut grid_area.row_start);
+                self.scope_grid_line(&mut grid_area.column_start);
+                self.scope_grid_line(&mut grid_area.row_end);
+                self.scope_grid_line(&mut grid_area.column_end);
+            }
+            Property::Custom(CustomProperty {
+                name: CustomPropertyName::Custom(name),
+                ..
+            }) => self.collect_css_variable(&name.0),
+            Property::AnimationName(names, _) => {
+                for name in names.iter_mut() {
+                    self.scope_animation_name(name);
+                }
+            }
+            Property::Animation(animations, _) => {
+                for animation in animations.iter_mut() {
+                    self.scope_animation_name(&mut animation.name);
+                }
+            }
+            _ => (),
+        }
+
+        property.visit_children(self)
     }
 
     fn visit_selector(&mut self, selectors: &mut Selector<'i>) -> Result<(), Self::Error> {
+        if self.is_excluded_selector(selectors) {
+            // Class names of excluded selectors are kept like the class names of global rules
+            for selector in selectors.iter_raw_match_order() {
+                if let Component::Class(c) = selector {
+                    self.global_classes.insert(c.to_string(), c.to_string());
+                }
+            }
+
+            if self.debug {
+                crate::compile_message(&format!(
+                    "selector excluded - {:?}",
+                    selectors
+                        .to_css_string(PrinterOptions::default())
+                        .unwrap_or_default()
+                ));
+            }
+
+            return Ok(());
+        }
+
         for selector in selectors.iter_mut_raw_match_order() {
             match selector {
                 Component::Class(c) => {
                     let original_class_name = c.to_string();
 
-                    if self.class_name_exclude_patterns.is_empty()
-                        || !self
-                            .class_name_exclude_patterns
-                            .is_match(&original_class_name)
+                    if self.is_global_rule {
+                        self.global_classes
+                            .insert(original_class_name.clone(), original_class_name.clone());
+
+                        if self.debug {
+                            crate::compile_message(&format!(
+                                "class name global - {:?}",
+                                &original_class_name
+                            ));
+                        }
+                    } else if self.scoping == Scoping::Classes
+                        && self.class_name_strategy != ClassNameStrategy::None
+                        && !self.is_excluded(&original_class_name)
                     {
-                        let new_class_name = self
-                            .randomized_class_name(
+                        let new_class_name = if self.bem {
+                            self.bem_class_name(&original_class_name)
+                        } else if let Some(frequency_class_name) =
+                            self.frequency_class_names.get(&original_class_name)
+                        {
+                            frequency_class_name.clone()
+                        } else {
+                            self.randomized_class_name(
                                 original_class_name.clone(),
                                 self.style_sheet_hash.clone(),
                             )
-                            .to_string();
+                        };
                         self.classes
                             .insert(original_class_name.clone(), new_class_name.clone());
 
@@ -127,6 +889,23 @@ impl<'i> Visitor<'i> for TransformationVisitor {
                         }
                     }
                 }
+                Component::PseudoElement(
+                    PseudoElement::ViewTransitionGroup { part_name }
+                    | PseudoElement::ViewTransitionImagePair { part_name }
+                    | PseudoElement::ViewTransitionOld { part_name }
+                    | PseudoElement::ViewTransitionNew { part_name },
+                ) => {
+                    if let ViewTransitionPartName::Name(name) = part_name {
+                        self.scope_view_transition_name(name);
+                    }
+                }
+                Component::Part(names) => {
+                    // Part names are the public styling interface of a shadow tree and are
+                    // therefore collected without being scoped
+                    for name in names.iter() {
+                        self.parts.insert(name.to_string(), name.to_string());
+                    }
+                }
                 Component::Slotted(s) => s.visit(self)?,
                 Component::Host(Some(selector)) => selector.visit(self)?,
                 Component::Negation(s)
@@ -144,13 +923,107 @@ impl<'i> Visitor<'i> for TransformationVisitor {
     }
 }
 
+fn grid_template_areas<'a>(property: &'a Property) -> Option<&'a GridTemplateAreas> {
+    match property {
+        Property::GridTemplateAreas(areas) => Some(areas),
+        Property::GridTemplate(template) => Some(&template.areas),
+        Property::Grid(grid) => Some(&grid.areas),
+        _ => None,
+    }
+}
+
+fn keyframes_name<'a>(name: &'a KeyframesName) -> &'a str {
+    match name {
+        KeyframesName::Ident(ident) => ident.0.as_ref(),
+        KeyframesName::Custom(name) => name.as_ref(),
+    }
+}
+
+pub(crate) fn is_view_transition_selector(selector: &Selector) -> bool {
+    selector.iter_raw_match_order().any(|component| {
+        matches!(
+            component,
+            Component::PseudoElement(
+                PseudoElement::ViewTransition
+                    | PseudoElement::ViewTransitionGroup { .. }
+                    | PseudoElement::ViewTransitionImagePair { .. }
+                    | PseudoElement::ViewTransitionOld { .. }
+                    | PseudoElement::ViewTransitionNew { .. }
+            )
+        )
+    })
+}
+
+/// Returns whether a selector only consists of element selectors, which would otherwise style
+/// elements outside of the components using the style sheet
+fn is_bare_element_selector(selector: &Selector) -> bool {
+    !is_view_transition_selector(selector)
+        && selector
+            .iter_raw_match_order()
+            .all(|component| match component {
+                Component::LocalName(local_name) => {
+                    !matches!(local_name.lower_name.0.as_ref(), "html" | "body")
+                }
+                Component::Class(_)
+                | Component::ID(_)
+                | Component::AttributeInNoNamespaceExists { .. }
+                | Component::AttributeInNoNamespace { .. }
+                | Component::AttributeOther(_)
+                | Component::Root
+                | Component::Host(_)
+                | Component::Slotted(_)
+                | Component::Part(_)
+                | Component::Nesting => false,
+                _ => true,
+            })
+}
+
+fn sanitize_crate_prefix(package_name: &str) -> String {
+    let sanitized_prefix: String = package_name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '-',
+        })
+        .collect();
+
+    // Class names must not start with a digit, a hyphen followed by a digit or two hyphens
+    match sanitized_prefix.chars().next() {
+        Some('0'..='9' | '-') | None => format!("_{sanitized_prefix}"),
+        _ => sanitized_prefix,
+    }
+}
+
+/// Derives the scope id of a style sheet from its hash and the salt, like `randomized_class_id` the
+/// id consists of 6 characters and starts with a letter or `_`
+fn scope_id(style_sheet_hash: &str, salt: &str) -> String {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(style_sheet_hash.as_bytes());
+    let mut bits = hasher.digest();
+
+    let mut scope_id = String::new();
+    scope_id.push(CHARSET[10 + (bits % 53) as usize] as char);
+    bits /= 53;
+    for _ in 0..5 {
+        scope_id.push(CHARSET[(bits & 0x3F) as usize] as char);
+        bits >>= 6;
+    }
+    scope_id
+}
+
 fn apply_template(
     class_name_template: &str,
     original_class_name: &str,
     id: &str,
     style_sheet_hash: &str,
+    salt: &str,
 ) -> String {
-    let name_hash = xxhash_rust::xxh3::xxh3_128(original_class_name.as_bytes());
+    let mut name_hasher = xxhash_rust::xxh3::Xxh3::new();
+    name_hasher.update(salt.as_bytes());
+    name_hasher.update(original_class_name.as_bytes());
+    let name_hash = name_hasher.digest128();
     let name_hash_string = format!("{name_hash:x}");
 
     class_name_template
@@ -168,46 +1041,251 @@ pub enum TransformationError {
     Lightningcss(String),
     #[error("Initialization of css tranformer failed")]
     Initialization(#[from] TransformationVisitorInitializationError),
+    #[error("selectors never match inside of a shadow root - {0}")]
+    ShadowDomIncompatibleSelectors(String),
+    #[error(transparent)]
+    SelectorPrefix(#[from] crate::selector_prefix::SelectorPrefixError),
+    #[error(transparent)]
+    Composition(#[from] CompositionError),
+    #[error(transparent)]
+    MediaSheets(#[from] crate::media_sheets::MediaSheetsError),
+    #[error(
+        "css warnings fail the compilation because `strict` is set:\n{}",
+        .0.iter().map(|warning| format!("- {warning}")).collect::<Vec<_>>().join("\n")
+    )]
+    CssWarnings(Vec<String>),
+}
+
+#[derive(Debug)]
+pub struct TransformedStyleSheet {
+    pub css: String,
+    pub class_names: BTreeMap<String, String>,
+    pub container_names: BTreeMap<String, String>,
+    pub counter_styles: BTreeMap<String, String>,
+    pub view_transition_names: BTreeMap<String, String>,
+    pub keyframes: BTreeMap<String, String>,
+    pub parts: BTreeMap<String, String>,
+    pub css_variables: BTreeMap<String, String>,
+    pub font_families: BTreeMap<String, String>,
+    pub grid_areas: BTreeMap<String, String>,
+    pub compositions: BTreeMap<String, String>,
+    pub scope_id: String,
+    pub forced_colors_issues: Vec<ForcedColorsIssue>,
+    pub class_name_rules: BTreeMap<String, Vec<String>>,
+    pub class_name_metadata: BTreeMap<String, crate::ClassNameMetadata>,
+    pub media_sheets: Option<crate::MediaSheets>,
+    /// The css of the dark color scheme if it is split from the css
+    pub dark_css: Option<String>,
+    pub ast_json: Option<Vec<crate::ast_json::AstRule>>,
+    /// The size the css would have with randomized instead of frequency based class names
+    pub randomized_css_size: Option<usize>,
+    /// The source map of the css to the css before its transformation, whose source is unnamed
+    pub source_map: Option<parcel_sourcemap::SourceMap>,
 }
 
 pub fn transform_stylesheet(
     css: &str,
     hash: &str,
+    locked_class_names: BTreeMap<String, String>,
     settings: crate::Settings,
-) -> Result<(String, HashMap<String, String>), TransformationError> {
-    let mut stylesheet = StyleSheet::parse(css, ParserOptions::default())
-        .map_err(|e| e.to_string())
-        .map_err(TransformationError::Lightningcss)?;
+) -> Result<TransformedStyleSheet, TransformationError> {
+    // The prefix is parsed into selectors of the style sheet, so it has to outlive the style sheet
+    let selector_prefix = settings.selector_prefix.clone();
+    let parser_warnings = settings.strict.then(|| Arc::new(RwLock::new(Vec::new())));
+    let mut stylesheet = StyleSheet::parse(
+        css,
+        ParserOptions {
+            warnings: parser_warnings.clone(),
+            flags: (&settings.css_parser).into(),
+            ..ParserOptions::default()
+        },
+    )
+    .map_err(|e| e.to_string())
+    .map_err(TransformationError::Lightningcss)?;
+
+    // lightningcss only resolves custom media queries while minifying, which also merges the
+    // declarations of each rule, e.g. longhand properties into their shorthand
+    if settings.css_parser.custom_media {
+        stylesheet
+            .minify(MinifyOptions {
+                targets: Targets {
+                    browsers: settings
+                        .browser_targets
+                        .as_ref()
+                        .map(|browser_targets| browser_targets.0),
+                    include: Features::CustomMediaQueries,
+                    exclude: Features::empty(),
+                },
+                ..MinifyOptions::default()
+            })
+            .map_err(|e| e.to_string())
+            .map_err(TransformationError::Lightningcss)?;
+    }
+
+    if let Some(parser_warnings) = parser_warnings {
+        let parser_warnings = parser_warnings
+            .read()
+            .map(|warnings| warnings.clone())
+            .unwrap_or_default();
+        let warnings = crate::css_warnings::css_warnings(&mut stylesheet, parser_warnings);
+        if !warnings.is_empty() {
+            return Err(TransformationError::CssWarnings(warnings));
+        }
+    }
 
-    let mut visitor = TransformationVisitor::try_new(&settings, hash)?;
+    let mut defined_name_collector = DefinedNameCollector::default();
+    stylesheet
+        .visit(&mut defined_name_collector)
+        .expect("css visitor never fails");
+
+    let mut visitor = TransformationVisitor::try_new(
+        &settings,
+        hash,
+        locked_class_names,
+        defined_name_collector,
+        crate::scoping_directives::scoping_directives(css),
+    )?;
+
+    let is_frequency_strategy = settings.scoping == Scoping::Classes
+        && settings.class_names.strategy == ClassNameStrategy::Frequency
+        && !settings.class_names.bem;
+    if is_frequency_strategy {
+        let class_name_counts = crate::class_name_frequency::class_name_counts(&mut stylesheet);
+        visitor.assign_frequency_class_names(&class_name_counts);
+    }
 
     stylesheet
         .visit(&mut visitor)
         .expect("css visitor never fails");
 
-    let css_result = stylesheet
-        .to_css(settings.into())
-        .map_err(|e| e.to_string())
-        .map_err(TransformationError::Lightningcss)?;
+    // Class names of global rules are only exposed if they are not scoped by another rule
+    for (original_class_name, class_name) in std::mem::take(&mut visitor.global_classes) {
+        visitor
+            .classes
+            .entry(original_class_name)
+            .or_insert(class_name);
+    }
 
-    Ok((css_result.code, visitor.classes))
-}
+    if let Some(error) = visitor.composition_error.take() {
+        return Err(error.into());
+    }
+    let compositions =
+        crate::composition::composed_class_names(&visitor.compositions, &visitor.classes)?;
 
-fn random_seed() -> Result<u64, getrandom::Error> {
-    let mut buf = [0u8; 8];
-    getrandom::getrandom(&mut buf)?;
-    Ok(u64::from_ne_bytes(buf))
-}
+    if !visitor.shadow_dom_incompatible_selectors.is_empty() {
+        return Err(TransformationError::ShadowDomIncompatibleSelectors(
+            visitor.shadow_dom_incompatible_selectors.join(", "),
+        ));
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::settings::ClassNameGeneration;
+    if let Some(selector_prefix) = &selector_prefix {
+        crate::selector_prefix::prefix_selectors(&mut stylesheet, selector_prefix)?;
+    }
 
-    use super::transform_stylesheet;
+    if let Some(browser_targets) = &settings.browser_targets {
+        crate::color_fallbacks::add_color_fallbacks(&mut stylesheet, browser_targets.0);
+    }
 
-    #[test]
-    fn basic_visitor() {
-        let style = r#"
+    let forced_colors_issues =
+        if settings.audits.forced_colors || settings.audits.forced_colors_fallback {
+            crate::forced_colors::audit_forced_colors(&mut stylesheet)
+        } else {
+            Vec::new()
+        };
+
+    let class_name_rules =
+        crate::class_name_rules::class_name_rules(&mut stylesheet, &visitor.classes);
+
+    let class_name_metadata = match settings.codegen.class_name_metadata {
+        true => crate::class_name_metadata::class_name_metadata(&mut stylesheet, &visitor.classes),
+        false => BTreeMap::new(),
+    };
+
+    let ast_json = settings
+        .file_output
+        .as_ref()
+        .and_then(|file_output| file_output.ast_json_path.as_ref())
+        .map(|_| crate::ast_json::style_sheet_rules(&mut stylesheet));
+
+    let class_name_counts = is_frequency_strategy
+        .then(|| crate::class_name_frequency::class_name_counts(&mut stylesheet));
+
+    let dark_css = match settings.split_dark_color_scheme {
+        true => crate::color_scheme::split_dark_color_scheme(&mut stylesheet, &settings)
+            .map_err(TransformationError::Lightningcss)?,
+        false => None,
+    };
+
+    let media_sheets = match settings.codegen.media_sheets {
+        true => Some(crate::media_sheets::media_sheets(&stylesheet, &settings)?),
+        false => None,
+    };
+
+    let mut source_map = settings
+        .file_output
+        .as_ref()
+        .is_some_and(|file_output| file_output.source_maps || file_output.inline_source_maps)
+        .then(|| crate::source_maps::unnamed_source_map(css))
+        .transpose()
+        .map_err(|e| e.to_string())
+        .map_err(TransformationError::Lightningcss)?;
+
+    let css_result = stylesheet
+        .to_css(PrinterOptions {
+            source_map: source_map.as_mut(),
+            ..settings.into()
+        })
+        .map_err(|e| e.to_string())
+        .map_err(TransformationError::Lightningcss)?;
+
+    let randomized_css_size = class_name_counts.map(|class_name_counts| {
+        visitor.randomized_css_size(css_result.code.len(), &class_name_counts)
+    });
+
+    Ok(TransformedStyleSheet {
+        css: css_result.code,
+        class_names: visitor.classes,
+        container_names: visitor.container_names,
+        counter_styles: visitor.counter_styles,
+        view_transition_names: visitor.view_transition_names,
+        keyframes: visitor.keyframes,
+        parts: visitor.parts,
+        css_variables: visitor.css_variables,
+        font_families: visitor.font_families,
+        grid_areas: visitor.grid_areas,
+        compositions,
+        scope_id: visitor.scope_id,
+        forced_colors_issues,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        ast_json,
+        randomized_css_size,
+        source_map,
+    })
+}
+
+fn random_seed() -> Result<u64, getrandom::Error> {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf)?;
+    Ok(u64::from_ne_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        composition::CompositionError,
+        settings::{ClassNameGeneration, ClassNameStrategy, CssParser, Salt, Scoping},
+    };
+
+    use super::{sanitize_crate_prefix, transform_stylesheet, TransformationError};
+
+    #[test]
+    fn basic_visitor() {
+        let style = r#"
             .test {
                 color: red;
             }
@@ -215,15 +1293,16 @@ mod tests {
         let transformation_result = transform_stylesheet(
             style,
             "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
             crate::Settings::default(),
         )
         .unwrap();
 
-        assert!(transformation_result.0.starts_with(".class-"));
-        assert!(transformation_result.0.ends_with("{color:red}"));
-        assert!(transformation_result.0.starts_with(&format!(
+        assert!(transformation_result.css.starts_with(".class-"));
+        assert!(transformation_result.css.ends_with("{color:red}"));
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}",
-            transformation_result.1.get("test").unwrap()
+            transformation_result.class_names.get("test").unwrap()
         )));
     }
 
@@ -237,20 +1316,21 @@ mod tests {
         let transformation_result = transform_stylesheet(
             style,
             "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
             crate::Settings::default(),
         )
         .unwrap();
 
-        assert!(transformation_result.0.starts_with(".class-"));
-        assert!(transformation_result.0.ends_with("{color:red}"));
-        assert!(transformation_result.0.starts_with(&format!(
+        assert!(transformation_result.css.starts_with(".class-"));
+        assert!(transformation_result.css.ends_with("{color:red}"));
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}:not(.class-",
-            transformation_result.1.get("test").unwrap()
+            transformation_result.class_names.get("test").unwrap()
         )));
-        assert!(transformation_result.0.starts_with(&format!(
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}:not(.{})",
-            transformation_result.1.get("test").unwrap(),
-            transformation_result.1.get("withoutme").unwrap()
+            transformation_result.class_names.get("test").unwrap(),
+            transformation_result.class_names.get("withoutme").unwrap()
         )));
     }
 
@@ -269,16 +1349,21 @@ mod tests {
             class_names: class_name_generation,
             ..Default::default()
         };
-        let transformation_result =
-            transform_stylesheet(style, "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ", settings).unwrap();
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
 
         assert!(transformation_result
-            .0
+            .css
             .starts_with(".fancy_style-test-SGVsbG8g-"));
-        assert!(transformation_result.0.ends_with("{color:red}"));
-        assert!(transformation_result.0.starts_with(&format!(
+        assert!(transformation_result.css.ends_with("{color:red}"));
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}",
-            transformation_result.1.get("test").unwrap()
+            transformation_result.class_names.get("test").unwrap()
         )));
     }
 
@@ -297,13 +1382,18 @@ mod tests {
             class_names: class_name_generation,
             ..Default::default()
         };
-        let transformation_result =
-            transform_stylesheet(style, "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ", settings).unwrap();
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
 
-        assert_eq!(transformation_result.0, ".fancy_style-test{color:red}");
-        assert!(transformation_result.0.starts_with(&format!(
+        assert_eq!(transformation_result.css, ".fancy_style-test{color:red}");
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}",
-            transformation_result.1.get("test").unwrap()
+            transformation_result.class_names.get("test").unwrap()
         )));
     }
 
@@ -322,16 +1412,1144 @@ mod tests {
             class_names: class_name_generation,
             ..Default::default()
         };
-        let transformation_result =
-            transform_stylesheet(style, "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ", settings).unwrap();
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
 
         assert_eq!(
-            transformation_result.0,
+            transformation_result.css,
             ".SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ-SGVsbG8g-6c78e0e3bd51d358d01e758642b85fb8-6c78e-test{color:red}"
         );
-        assert!(transformation_result.0.starts_with(&format!(
+        assert!(transformation_result.css.starts_with(&format!(
             ".{}",
-            transformation_result.1.get("test").unwrap()
+            transformation_result.class_names.get("test").unwrap()
         )));
     }
+
+    #[test]
+    fn class_name_strategy_none() {
+        let style = r#"
+            .test:not(.other) {
+                color: red;
+            }
+        "#;
+        let class_name_generation = ClassNameGeneration {
+            strategy: ClassNameStrategy::None,
+            ..Default::default()
+        };
+        let settings = crate::Settings {
+            class_names: class_name_generation,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(transformation_result.css, ".test:not(.other){color:red}");
+        assert_eq!(
+            transformation_result.class_names.get("test").unwrap(),
+            "test"
+        );
+        assert_eq!(
+            transformation_result.class_names.get("other").unwrap(),
+            "other"
+        );
+    }
+
+    #[test]
+    fn class_name_strategy_none_keyframes() {
+        let style = r#"
+            @keyframes fade-in {
+                from { opacity: 0; }
+                to { opacity: 1; }
+            }
+
+            .test {
+                animation: fade-in 1s;
+                container-name: sidebar;
+            }
+        "#;
+        let settings = crate::Settings {
+            class_names: ClassNameGeneration {
+                strategy: ClassNameStrategy::None,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.keyframes.get("fade-in").unwrap(),
+            "fade-in"
+        );
+        assert_eq!(
+            transformation_result
+                .container_names
+                .get("sidebar")
+                .unwrap(),
+            "sidebar"
+        );
+        assert_eq!(
+            transformation_result.css,
+            "@keyframes fade-in{0%{opacity:0}to{opacity:1}}.test{animation:1s fade-in;container-name:sidebar}"
+        );
+    }
+
+    #[test]
+    fn class_name_strategy_frequency() {
+        let style = r#"
+            .rare {
+                color: red;
+            }
+
+            .common, .common:hover, .other .common {
+                color: blue;
+            }
+
+            .other {
+                color: green;
+            }
+        "#;
+        let class_name_generation = ClassNameGeneration {
+            template: String::from("f-<id>"),
+            strategy: ClassNameStrategy::Frequency,
+            ..Default::default()
+        };
+        let settings = crate::Settings {
+            class_names: class_name_generation,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.css,
+            ".f-c{color:red}.f-a,.f-a:hover,.f-b .f-a{color:#00f}.f-b{color:green}"
+        );
+        // Each of the 6 class name occurrences would be 5 characters longer with randomized ids
+        assert_eq!(
+            transformation_result.randomized_css_size,
+            Some(transformation_result.css.len() + 6 * 5)
+        );
+    }
+
+    #[test]
+    fn salted_name_hash() {
+        let style = r#"
+            .test {
+                color: red;
+            }
+        "#;
+        let class_name = |salt: Option<Salt>| {
+            let settings = crate::Settings {
+                class_names: ClassNameGeneration {
+                    template: String::from("<name_hash_short>"),
+                    salt,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            transform_stylesheet(
+                style,
+                "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+                BTreeMap::new(),
+                settings,
+            )
+            .unwrap()
+            .class_names
+            .remove("test")
+            .unwrap()
+        };
+
+        let unsalted = class_name(None);
+        let salted = class_name(Some(Salt::Value(String::from("variant-b"))));
+
+        assert_ne!(unsalted, salted);
+        assert_eq!(
+            salted,
+            class_name(Some(Salt::Value(String::from("variant-b"))))
+        );
+    }
+
+    #[test]
+    fn excluded_selectors() {
+        let style = r#"
+            .swiper-slide .title, .card {
+                color: red;
+            }
+
+            #app p {
+                color: blue;
+            }
+        "#;
+        let settings = crate::Settings {
+            class_names: ClassNameGeneration {
+                excludes_selectors: vec![String::from("\\.swiper-"), String::from("#app")],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let card = transformation_result.class_names.get("card").unwrap();
+
+        assert!(card.starts_with("class-"));
+        assert_eq!(
+            transformation_result.class_names.get("title").unwrap(),
+            "title"
+        );
+        assert_eq!(
+            transformation_result.css,
+            format!(".swiper-slide .title,.{card}{{color:red}}#app p{{color:#00f}}")
+        );
+    }
+
+    #[test]
+    fn selector_prefix() {
+        let style = r#"
+            .test, html .other > p, body:hover .test, :root, ::selection {
+                color: red;
+            }
+        "#;
+        let settings = crate::Settings {
+            selector_prefix: Some(String::from("#extension-root")),
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let test = transformation_result.class_names.get("test").unwrap();
+        let other = transformation_result.class_names.get("other").unwrap();
+
+        assert_eq!(
+            transformation_result.css,
+            format!("#extension-root .{test},#extension-root .{other}>p,#extension-root:hover .{test},#extension-root,#extension-root ::selection{{color:red}}")
+        );
+    }
+
+    #[test]
+    fn strict_css_warnings() {
+        let settings = crate::Settings {
+            strict: true,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            ".test {\n  colr: red;\n}",
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings.clone(),
+        );
+
+        assert!(matches!(
+            transformation_result,
+            Err(TransformationError::CssWarnings(warnings))
+                if warnings == vec!["unknown property `colr` in `.test` (line 1, column 1)"]
+        ));
+
+        let transformation_result = transform_stylesheet(
+            ".test { color: red; }",
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        );
+
+        assert!(transformation_result.is_ok());
+    }
+
+    #[test]
+    fn css_parser_flags() {
+        let css = "@custom-media --narrow (max-width: 30em);\n@media (--narrow) { .test { margin-top: 1px; margin-bottom: 1px; margin-left: 1px; margin-right: 1px; } }\n.outer >>> .inner { color: red; }";
+        assert!(transform_stylesheet(
+            css,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .is_err());
+
+        let settings = crate::Settings {
+            minify: false,
+            css_parser: CssParser {
+                custom_media: true,
+                deep_selector_combinator: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            css,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        assert!(!transformation_result.css.contains("--narrow"));
+        assert!(transformation_result.css.contains("@media (width <= 30em)"));
+        assert!(transformation_result.css.contains("margin: 1px;"));
+        assert!(transformation_result.css.contains(" >>> "));
+    }
+
+    #[test]
+    fn invalid_selector_prefix() {
+        let settings = crate::Settings {
+            selector_prefix: Some(String::from("#extension-root .content")),
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            ".test { color: red; }",
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        );
+
+        assert!(matches!(
+            transformation_result,
+            Err(TransformationError::SelectorPrefix(_))
+        ));
+    }
+
+    #[test]
+    fn excluded_selectors_wrap_scoping() {
+        let style = r#"
+            .swiper-slide, .card {
+                color: red;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::Wrap,
+            class_names: ClassNameGeneration {
+                excludes_selectors: vec![String::from("^\\.swiper-")],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let root_class = transformation_result.scope_id;
+
+        assert_eq!(
+            transformation_result.css,
+            format!(".swiper-slide,.{root_class} .card{{color:red}}")
+        );
+    }
+
+    #[test]
+    fn locked_class_names() {
+        let style = r#"
+            .test {
+                color: red;
+            }
+
+            .other {
+                color: blue;
+            }
+        "#;
+        let mut locked_class_names = BTreeMap::new();
+        locked_class_names.insert(String::from("test"), String::from("locked-test"));
+
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            locked_class_names,
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        assert!(transformation_result
+            .css
+            .starts_with(".locked-test{color:red}"));
+        assert_eq!(
+            transformation_result.class_names.get("test").unwrap(),
+            "locked-test"
+        );
+        assert!(transformation_result
+            .class_names
+            .get("other")
+            .unwrap()
+            .starts_with("class-"));
+    }
+
+    #[test]
+    fn crate_prefix_sanitization() {
+        assert_eq!(sanitize_crate_prefix("my-crate_name"), "my-crate_name");
+        assert_eq!(sanitize_crate_prefix("my.crate"), "my-crate");
+        assert_eq!(sanitize_crate_prefix("2d-ui"), "_2d-ui");
+        assert_eq!(sanitize_crate_prefix("-ui"), "_-ui");
+    }
+
+    #[test]
+    fn container_names() {
+        let style = r#"
+            .test {
+                container-name: sidebar;
+            }
+
+            .other {
+                container: sidebar / inline-size;
+            }
+
+            @container sidebar (min-width: 400px) {
+                .test {
+                    color: red;
+                }
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let container_name = transformation_result
+            .container_names
+            .get("sidebar")
+            .unwrap();
+
+        assert!(container_name.starts_with("class-"));
+        assert!(!transformation_result.css.contains("sidebar"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("container-name:{container_name}")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("container:{container_name}/inline-size")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("@container {container_name} (width>=400px)")));
+    }
+
+    #[test]
+    fn grid_areas() {
+        let style = r#"
+            .layout {
+                display: grid;
+                grid-template-areas: "header header" "sidebar main";
+            }
+
+            .sidebar {
+                grid-area: sidebar;
+            }
+
+            .main {
+                grid-column: main-start / main-end;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.grid_areas,
+            BTreeMap::from([
+                (String::from("header"), String::from("header")),
+                (String::from("main"), String::from("main")),
+                (String::from("sidebar"), String::from("sidebar")),
+            ])
+        );
+        assert!(transformation_result.css.contains("grid-area:sidebar"));
+
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings {
+                scope_grid_areas: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let sidebar = transformation_result.grid_areas.get("sidebar").unwrap();
+        let main = transformation_result.grid_areas.get("main").unwrap();
+        assert!(sidebar.starts_with("class-"));
+        assert!(!transformation_result.css.contains("sidebar;"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("grid-area:{sidebar}")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("grid-column:{main}-start/{main}-end")));
+    }
+
+    #[test]
+    fn counter_styles() {
+        let style = r#"
+            @counter-style thumbs {
+                system: cyclic;
+                symbols: "👍";
+                suffix: " ";
+            }
+
+            .test {
+                list-style-type: thumbs;
+            }
+
+            .other {
+                list-style: thumbs inside;
+            }
+
+            .global {
+                list-style-type: global-counter-style;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let counter_style = transformation_result.counter_styles.get("thumbs").unwrap();
+
+        assert!(counter_style.starts_with("class-"));
+        assert!(!transformation_result.css.contains("thumbs"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("@counter-style {counter_style}")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("list-style-type:{counter_style}")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("list-style:{counter_style} inside")));
+        assert!(transformation_result
+            .css
+            .contains("list-style-type:global-counter-style"));
+    }
+
+    #[test]
+    fn view_transition_names() {
+        let style = r#"
+            .test {
+                view-transition-name: hero;
+            }
+
+            .other {
+                view-transition-name: none;
+            }
+
+            ::view-transition-old(hero) {
+                animation-duration: 1s;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let view_transition_name = transformation_result
+            .view_transition_names
+            .get("hero")
+            .unwrap();
+
+        assert!(view_transition_name.starts_with("class-"));
+        assert!(!transformation_result.css.contains("hero"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("view-transition-name:{view_transition_name}")));
+        assert!(transformation_result
+            .css
+            .contains("view-transition-name:none"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("::view-transition-old({view_transition_name})")));
+    }
+
+    #[test]
+    fn keyframes() {
+        let style = r#"
+            @keyframes fade {
+                from { opacity: 0; }
+                to { opacity: 1; }
+            }
+
+            .test {
+                animation: fade 1s ease-in;
+            }
+
+            .other {
+                animation-name: fade, spin;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let keyframes = transformation_result.keyframes.get("fade").unwrap();
+
+        assert!(keyframes.starts_with("class-"));
+        assert!(!transformation_result.keyframes.contains_key("spin"));
+        assert!(!transformation_result.css.contains("fade"));
+        assert!(transformation_result
+            .css
+            .contains(&format!("@keyframes {keyframes}")));
+        assert!(transformation_result
+            .css
+            .contains(&format!("animation-name:{keyframes},spin")));
+    }
+
+    #[test]
+    fn parts() {
+        let style = r#"
+            .test::part(label) {
+                color: red;
+            }
+
+            :host(.active)::part(label icon) {
+                color: blue;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(transformation_result.parts.len(), 2);
+        assert_eq!(
+            transformation_result.parts.get("label"),
+            Some(&String::from("label"))
+        );
+        assert_eq!(
+            transformation_result.parts.get("icon"),
+            Some(&String::from("icon"))
+        );
+        assert!(transformation_result.css.contains("::part(label icon)"));
+    }
+
+    #[test]
+    fn css_variables() {
+        let style = r#"
+            @property --accent-color {
+                syntax: "<color>";
+                inherits: true;
+                initial-value: red;
+            }
+
+            .test {
+                --progress: 0%;
+                color: var(--accent-color);
+                width: var(--undeclared);
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.css_variables,
+            BTreeMap::from([
+                (String::from("accent-color"), String::from("--accent-color")),
+                (String::from("progress"), String::from("--progress")),
+            ])
+        );
+        assert!(transformation_result.css.contains("--progress:0%"));
+    }
+
+    #[test]
+    fn compositions() {
+        let style = r#"
+            .base {
+                padding: 0;
+            }
+
+            .button {
+                composes: base;
+                composes: focus-ring from global;
+                color: red;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let base = transformation_result.class_names.get("base").unwrap();
+        let button = transformation_result.class_names.get("button").unwrap();
+        assert_eq!(
+            transformation_result.compositions.get("button"),
+            Some(&format!("{button} {base} focus-ring"))
+        );
+        assert!(!transformation_result.css.contains("composes"));
+        assert!(transformation_result
+            .css
+            .contains(&format!(".{button}{{color:red}}")));
+    }
+
+    #[test]
+    fn invalid_composition() {
+        let style = r#"
+            .button:hover {
+                composes: base;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        );
+
+        assert!(matches!(
+            transformation_result,
+            Err(TransformationError::Composition(
+                CompositionError::InvalidSelector(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn font_families() {
+        let style = r#"
+            @font-face {
+                font-family: "Inter Variable";
+                src: url("inter.woff2") format("woff2");
+            }
+
+            @font-face {
+                font-family: Icons;
+                src: url("icons.woff2") format("woff2");
+            }
+
+            .test {
+                font-family: "Inter Variable", sans-serif;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.font_families,
+            BTreeMap::from([
+                (String::from("Icons"), String::from("Icons")),
+                (
+                    String::from("Inter Variable"),
+                    String::from("Inter Variable")
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn data_attribute_scoping() {
+        let style = r#"
+            .test .other, p {
+                color: red;
+            }
+
+            .test:hover::before {
+                color: blue;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::DataAttribute,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let scope_id = transformation_result.scope_id;
+
+        assert_eq!(
+            transformation_result.class_names.get("test").unwrap(),
+            "test"
+        );
+        assert_eq!(
+            transformation_result.css,
+            format!(
+                ".test .other[data-turf-scope~={scope_id}],p[data-turf-scope~={scope_id}]{{color:red}}.test:hover[data-turf-scope~={scope_id}]:before{{color:#00f}}"
+            )
+        );
+    }
+
+    #[test]
+    fn deterministic_scope_id() {
+        let style = ".test { color: red; }";
+        let transform = |salt: Option<&str>| {
+            let settings = crate::Settings {
+                class_names: ClassNameGeneration {
+                    template: String::from("<scope_id>-<original_name>"),
+                    salt: salt.map(|salt| Salt::Value(salt.to_owned())),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            transform_stylesheet(
+                style,
+                "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+                BTreeMap::new(),
+                settings,
+            )
+            .unwrap()
+        };
+
+        let first = transform(None);
+        let scope_id = first.scope_id.clone();
+        assert_eq!(scope_id.len(), 6);
+        assert_eq!(transform(None).scope_id, scope_id);
+        assert_ne!(transform(Some("other build")).scope_id, scope_id);
+        assert_eq!(
+            first.class_names.get("test").unwrap(),
+            &format!("{scope_id}-test")
+        );
+        assert_eq!(first.css, format!(".{scope_id}-test{{color:red}}"));
+    }
+
+    #[test]
+    fn wrap_scoping() {
+        let style = r#"
+            :root {
+                color: black;
+            }
+
+            .test .other, p {
+                color: red;
+            }
+
+            .test:hover::before {
+                color: blue;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::Wrap,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let root_class = transformation_result.scope_id;
+
+        assert!(root_class.starts_with("class-"));
+        assert_eq!(transformation_result.class_names.len(), 2);
+        assert_eq!(
+            transformation_result.class_names.get("test").unwrap(),
+            "test"
+        );
+        assert_eq!(
+            transformation_result.css,
+            format!(
+                ".{root_class}{{color:#000}}.{root_class} .test .other,.{root_class} p{{color:red}}.{root_class} .test:hover:before{{color:#00f}}"
+            )
+        );
+    }
+
+    #[test]
+    fn prose_scoping() {
+        let style = r#"
+            body {
+                line-height: 1.6;
+            }
+
+            h1, p a {
+                color: red;
+            }
+
+            .lead {
+                font-size: 1.25em;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::Prose,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let root_class = transformation_result.scope_id;
+
+        assert!(root_class.starts_with("class-"));
+        assert_eq!(
+            transformation_result.class_names.get("lead").unwrap(),
+            "lead"
+        );
+        assert_eq!(
+            transformation_result.css,
+            format!(
+                ":where(.{root_class}){{line-height:1.6}}:where(.{root_class}) h1,:where(.{root_class}) p a{{color:red}}:where(.{root_class}) .lead{{font-size:1.25em}}"
+            )
+        );
+    }
+
+    #[test]
+    fn shadow_scoping() {
+        let style = r#"
+            :host {
+                display: block;
+            }
+
+            .test::part(label), slot::slotted(p) {
+                color: red;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::Shadow,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation_result.class_names.get("test").unwrap(),
+            "test"
+        );
+        assert_eq!(
+            transformation_result.css,
+            ":host{display:block}.test::part(label),slot::slotted(p){color:red}"
+        );
+    }
+
+    #[test]
+    fn shadow_scoping_incompatible_selectors() {
+        let style = r#"
+            :root, .test {
+                color: black;
+            }
+
+            body .test {
+                color: red;
+            }
+        "#;
+        let settings = crate::Settings {
+            scoping: Scoping::Shadow,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        );
+
+        assert!(matches!(
+            transformation_result,
+            Err(TransformationError::ShadowDomIncompatibleSelectors(selectors))
+                if selectors == ":root, body .test"
+        ));
+    }
+
+    #[test]
+    fn bem_class_names() {
+        let style = r#"
+            .card {
+                color: red;
+            }
+
+            .card__title--active, .card--large {
+                color: blue;
+            }
+
+            .other__title {
+                color: green;
+            }
+        "#;
+        let mut locked_class_names = BTreeMap::new();
+        locked_class_names.insert(
+            String::from("other__title"),
+            String::from("class-locked__title"),
+        );
+        let settings = crate::Settings {
+            class_names: ClassNameGeneration {
+                bem: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            locked_class_names,
+            settings,
+        )
+        .unwrap();
+
+        let block = transformation_result.class_names.get("card").unwrap();
+
+        assert!(block.starts_with("class-"));
+        assert_eq!(
+            transformation_result
+                .class_names
+                .get("card__title--active")
+                .unwrap(),
+            &format!("{block}__title--active")
+        );
+        assert_eq!(
+            transformation_result
+                .class_names
+                .get("card--large")
+                .unwrap(),
+            &format!("{block}--large")
+        );
+        assert_eq!(
+            transformation_result
+                .class_names
+                .get("other__title")
+                .unwrap(),
+            "class-locked__title"
+        );
+    }
+
+    #[test]
+    fn scoping_directives() {
+        let style = r#"
+            .test {
+                color: red;
+            }
+
+            /* turf:global */
+            .global-class, .test:hover {
+                color: blue;
+            }
+
+            /* turf:scope */
+            .other {
+                color: green;
+            }
+        "#;
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            crate::Settings::default(),
+        )
+        .unwrap();
+
+        let test_class_name = transformation_result.class_names.get("test").unwrap();
+        let other_class_name = transformation_result.class_names.get("other").unwrap();
+
+        assert!(test_class_name.starts_with("class-"));
+        assert!(other_class_name.starts_with("class-"));
+        assert_eq!(
+            transformation_result
+                .class_names
+                .get("global-class")
+                .unwrap(),
+            "global-class"
+        );
+        assert_eq!(
+            transformation_result.css,
+            format!(
+                ".{test_class_name}{{color:red}}.global-class,.test:hover{{color:#00f}}.{other_class_name}{{color:green}}"
+            )
+        );
+    }
+
+    #[test]
+    fn scope_element_selectors() {
+        let style = r#"
+            p, ul li {
+                color: red;
+            }
+
+            .test a, a:hover, html {
+                color: blue;
+            }
+        "#;
+        let settings = crate::Settings {
+            scope_element_selectors: true,
+            ..Default::default()
+        };
+        let transformation_result = transform_stylesheet(
+            style,
+            "SGVsbG8gdHVyZiB3b3JsZCBvZiBzdHlsZQ",
+            BTreeMap::new(),
+            settings,
+        )
+        .unwrap();
+
+        let root_class = transformation_result.scope_id;
+        let test_class_name = transformation_result.class_names.get("test").unwrap();
+
+        assert_eq!(transformation_result.class_names.len(), 1);
+        assert_eq!(
+            transformation_result.css,
+            format!(
+                ".{root_class} p,.{root_class} ul li{{color:red}}.{test_class_name} a,.{root_class} a:hover,html{{color:#00f}}"
+            )
+        );
+    }
 }
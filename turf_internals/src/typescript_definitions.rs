@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, ffi::OsString, path::Path};
+
+use crate::{
+    file_output::{self, source_path_file_name, FileOutputError},
+    lockfile, registry,
+    settings::SeparateCssFileNaming,
+    StyleSheetKind,
+};
+
+/// The name of the object with the class names in the generated TypeScript files
+const CLASS_NAMES_OBJECT: &str = "classNames";
+
+/// Names a TypeScript file of a style sheet like its separate css file, with the extension instead
+/// of `.css`
+fn typescript_file_name(
+    naming: SeparateCssFileNaming,
+    style_sheet: &StyleSheetKind,
+    extension: &str,
+) -> OsString {
+    let mut file_name = match style_sheet {
+        StyleSheetKind::File(path) => {
+            let file_name = match naming {
+                SeparateCssFileNaming::FileName => path
+                    .file_name()
+                    .expect("current css file exists")
+                    .to_owned(),
+                SeparateCssFileNaming::SourcePath => source_path_file_name(path),
+            };
+            Path::new(&file_name).with_extension("").into_os_string()
+        }
+        StyleSheetKind::Inline(style_sheet) => {
+            format!("{:x?}", xxhash_rust::xxh3::xxh3_64(style_sheet.as_bytes())).into()
+        }
+    };
+    file_name.push(format!(".{extension}"));
+    file_name
+}
+
+fn generated_file_header(style_sheet: &StyleSheetKind) -> String {
+    format!(
+        "// This file is generated by turf from {}, do not edit it.\n\n",
+        lockfile::style_sheet_key(style_sheet)
+    )
+}
+
+/// Declares the class names object of a style sheet with the generated class names as literal
+/// types
+fn declaration(class_names: &BTreeMap<String, String>, style_sheet: &StyleSheetKind) -> String {
+    let mut declaration = generated_file_header(style_sheet);
+    declaration.push_str(&format!("declare const {CLASS_NAMES_OBJECT}: {{\n"));
+    for (original_name, class_name) in class_names {
+        declaration.push_str(&format!(
+            "  readonly {}: {};\n",
+            serde_json::json!(original_name),
+            serde_json::json!(class_name)
+        ));
+    }
+    declaration.push_str(&format!("}};\nexport default {CLASS_NAMES_OBJECT};\n"));
+    declaration
+}
+
+/// Defines the class names object of a style sheet as a const object
+fn const_object(class_names: &BTreeMap<String, String>, style_sheet: &StyleSheetKind) -> String {
+    let mut const_object = generated_file_header(style_sheet);
+    const_object.push_str(&format!("const {CLASS_NAMES_OBJECT} = {{\n"));
+    for (original_name, class_name) in class_names {
+        const_object.push_str(&format!(
+            "  {}: {},\n",
+            serde_json::json!(original_name),
+            serde_json::json!(class_name)
+        ));
+    }
+    const_object.push_str(&format!(
+        "}} as const;\nexport default {CLASS_NAMES_OBJECT};\n"
+    ));
+    const_object
+}
+
+/// Writes the `.d.ts` file with the class names of a style sheet to the directory, and the `.ts`
+/// file with the const object if enabled
+pub(crate) fn write_typescript_definitions(
+    directory: &Path,
+    const_objects: bool,
+    naming: SeparateCssFileNaming,
+    style_sheet: &StyleSheetKind,
+    class_names: &BTreeMap<String, String>,
+) -> Result<(), FileOutputError> {
+    let mut files = vec![(
+        directory.join(typescript_file_name(naming, style_sheet, "d.ts")),
+        declaration(class_names, style_sheet),
+    )];
+    if const_objects {
+        files.push((
+            directory.join(typescript_file_name(naming, style_sheet, "ts")),
+            const_object(class_names, style_sheet),
+        ));
+    }
+
+    for (path, content) in files {
+        registry::register_separate_output_file(&path, style_sheet)?;
+        file_output::own_output_file(&path)?;
+        file_output::write_atomically(&path, &content)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, ffi::OsString, path::PathBuf};
+
+    use crate::{settings::SeparateCssFileNaming, StyleSheetKind};
+
+    use super::{const_object, declaration, typescript_file_name};
+
+    #[test]
+    fn typescript_definitions() {
+        let style_sheet = StyleSheetKind::Inline(String::from(".button {}"));
+        let class_names = BTreeMap::from([
+            (String::from("button"), String::from("class-abc")),
+            (String::from("primary-button"), String::from("class-def")),
+        ]);
+
+        let declaration = declaration(&class_names, &style_sheet);
+        assert!(declaration.starts_with("// This file is generated by turf from inline:"));
+        assert!(declaration.ends_with(
+            "declare const classNames: {\n  readonly \"button\": \"class-abc\";\n  readonly \"primary-button\": \"class-def\";\n};\nexport default classNames;\n"
+        ));
+
+        let const_object = const_object(&class_names, &style_sheet);
+        assert!(const_object.ends_with(
+            "const classNames = {\n  \"button\": \"class-abc\",\n  \"primary-button\": \"class-def\",\n} as const;\nexport default classNames;\n"
+        ));
+    }
+
+    #[test]
+    fn typescript_file_names() {
+        let style_sheet = StyleSheetKind::File(PathBuf::from("src/components/button.module.scss"));
+
+        assert_eq!(
+            typescript_file_name(SeparateCssFileNaming::FileName, &style_sheet, "d.ts"),
+            OsString::from("button.module.d.ts")
+        );
+        assert_eq!(
+            typescript_file_name(SeparateCssFileNaming::SourcePath, &style_sheet, "ts"),
+            OsString::from("src_components_button.module.ts")
+        );
+    }
+}
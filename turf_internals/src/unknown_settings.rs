@@ -0,0 +1,306 @@
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+use crate::settings::{
+    Audits, BrowserVersions, ClassNameGeneration, Codegen, CssParser, FileOutput, Sass, Settings,
+    Themes,
+};
+
+/// A settings key that none of the settings accept
+#[derive(Debug, PartialEq)]
+pub struct UnknownKey {
+    pub key: String,
+    /// The dotted path of the table containing the key, empty for the settings table itself
+    pub table: String,
+    /// The valid key of the table that is closest to the unknown key
+    pub suggestion: Option<String>,
+}
+
+impl From<UnknownKey> for crate::settings::SettingsError {
+    fn from(value: UnknownKey) -> Self {
+        Self::UnknownKey {
+            key: value.key,
+            table: value.table,
+            suggestion: value.suggestion,
+        }
+    }
+}
+
+/// Returns the first key of a settings table that isn't a setting
+///
+/// The tables of named profiles in `profile` and of targets in `target` are checked as settings
+/// tables as well, with named profiles also accepting `inherits`. Nested tables are checked if
+/// they are tables of settings, e.g. `class_names` or `browser_targets`.
+pub fn unknown_key(table: &toml::Table) -> Option<UnknownKey> {
+    settings_table_unknown_key(table, "", &[])
+}
+
+fn settings_table_unknown_key(
+    table: &toml::Table,
+    path: &str,
+    extra_keys: &[&'static str],
+) -> Option<UnknownKey> {
+    let is_root = path.is_empty();
+    let mut keys = field_names::<Settings>().to_vec();
+    keys.extend(extra_keys);
+    keys.push(crate::settings::TARGETS_KEY);
+    if is_root {
+        keys.push(crate::settings::PROFILES_KEY);
+        keys.push(crate::settings_overrides::OVERRIDES_KEY);
+    }
+
+    if let Some(unknown_key) = table_unknown_key(table, path, &keys) {
+        return Some(unknown_key);
+    }
+
+    if let Some(toml::Value::Array(overrides)) = table
+        .get(crate::settings_overrides::OVERRIDES_KEY)
+        .filter(|_| is_root)
+    {
+        let unknown_key = overrides.iter().enumerate().find_map(|(index, value)| {
+            let toml::Value::Table(value) = value else {
+                return None;
+            };
+            override_unknown_key(
+                value,
+                &format!("{}[{index}]", crate::settings_overrides::OVERRIDES_KEY),
+            )
+        });
+        if unknown_key.is_some() {
+            return unknown_key;
+        }
+    }
+
+    table.iter().find_map(|(key, value)| {
+        let toml::Value::Table(value) = value else {
+            return None;
+        };
+        let table_path = nested_path(path, key);
+
+        match key.as_str() {
+            crate::settings::TARGETS_KEY | crate::settings::PROFILES_KEY
+                if key == crate::settings::TARGETS_KEY || is_root =>
+            {
+                let extra_keys: &[&'static str] = match key == crate::settings::PROFILES_KEY {
+                    true => &[crate::settings::INHERITS_KEY],
+                    false => &[],
+                };
+                value.iter().find_map(|(name, value)| match value {
+                    toml::Value::Table(value) => settings_table_unknown_key(
+                        value,
+                        &nested_path(&table_path, name),
+                        extra_keys,
+                    ),
+                    _ => None,
+                })
+            }
+            _ => {
+                nested_table_keys(key).and_then(|keys| table_unknown_key(value, &table_path, &keys))
+            }
+        }
+    })
+}
+
+/// Returns the first key of an override that isn't a setting or its pattern
+///
+/// Overrides only apply settings to style sheets, so they can't contain named profiles, targets
+/// or further overrides.
+fn override_unknown_key(table: &toml::Table, path: &str) -> Option<UnknownKey> {
+    let mut keys = field_names::<Settings>().to_vec();
+    keys.push(crate::settings_overrides::PATTERN_KEY);
+
+    table_unknown_key(table, path, &keys).or_else(|| {
+        table.iter().find_map(|(key, value)| match value {
+            toml::Value::Table(value) => nested_table_keys(key)
+                .and_then(|keys| table_unknown_key(value, &nested_path(path, key), &keys)),
+            _ => None,
+        })
+    })
+}
+
+/// Returns the keys of a nested settings table
+fn nested_table_keys(key: &str) -> Option<Vec<&'static str>> {
+    let keys = match key {
+        "file_output" => field_names::<FileOutput>().to_vec(),
+        "class_names" => field_names::<ClassNameGeneration>().to_vec(),
+        "themes" => field_names::<Themes>().to_vec(),
+        "audits" => field_names::<Audits>().to_vec(),
+        "css_parser" => field_names::<CssParser>().to_vec(),
+        "codegen" => field_names::<Codegen>().to_vec(),
+        "sass" => field_names::<Sass>().to_vec(),
+        "browser_targets" => {
+            let mut keys = field_names::<BrowserVersions>().to_vec();
+            keys.push("from_browserslist");
+            keys
+        }
+        _ => return None,
+    };
+
+    Some(keys)
+}
+
+fn table_unknown_key(table: &toml::Table, path: &str, keys: &[&str]) -> Option<UnknownKey> {
+    table
+        .keys()
+        .find(|key| !keys.contains(&key.as_str()))
+        .map(|key| UnknownKey {
+            key: key.clone(),
+            table: path.to_owned(),
+            suggestion: closest_key(key, keys).map(str::to_owned),
+        })
+}
+
+fn nested_path(path: &str, key: &str) -> String {
+    let key = match key
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '-')
+    {
+        true => key.to_owned(),
+        false => format!("'{key}'"),
+    };
+
+    match path.is_empty() {
+        true => key,
+        false => format!("{path}.{key}"),
+    }
+}
+
+/// Returns the key with the smallest edit distance to the unknown key, if it is close enough to
+/// be a typo
+fn closest_key<'a>(unknown_key: &str, keys: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (unknown_key.chars().count() / 3).max(1);
+
+    keys.iter()
+        .map(|key| (edit_distance(unknown_key, key), *key))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, key)| key)
+}
+
+/// The Levenshtein distance of two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+
+    previous_row[b.len()]
+}
+
+/// Returns the names of the fields of a struct as they are deserialized
+fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields = &[][..];
+    let _ = T::deserialize(FieldNamesDeserializer(&mut fields));
+    fields
+}
+
+/// A deserializer that only records the fields of the struct deserialized from it
+struct FieldNamesDeserializer<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNamesDeserializer<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("only structs have field names"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("field names recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unknown_key, UnknownKey};
+
+    fn unknown_key_of(settings: &str) -> Option<UnknownKey> {
+        unknown_key(&toml::from_str(settings).unwrap())
+    }
+
+    #[test]
+    fn unknown_settings_keys() {
+        assert_eq!(
+            unknown_key_of(
+                r#"
+                minify = false
+                load_paths = ["styles"]
+                browser_targets = { chrome = 100 }
+
+                [class_names]
+                template = "<id>"
+
+                [profile.bench]
+                inherits = "dev"
+                debug = true
+
+                [target.'wasm32-unknown-unknown'.codegen]
+                style = "enum"
+                "#
+            ),
+            None
+        );
+        assert_eq!(
+            unknown_key_of("minifiy = false"),
+            Some(UnknownKey {
+                key: String::from("minifiy"),
+                table: String::new(),
+                suggestion: Some(String::from("minify")),
+            })
+        );
+        assert_eq!(
+            unknown_key_of("[class_names]\ntempalte = \"<id>\""),
+            Some(UnknownKey {
+                key: String::from("tempalte"),
+                table: String::from("class_names"),
+                suggestion: Some(String::from("template")),
+            })
+        );
+        assert_eq!(
+            unknown_key_of("[target.'wasm32-unknown-unknown'.browser_targets]\nchorme = 100"),
+            Some(UnknownKey {
+                key: String::from("chorme"),
+                table: String::from("target.wasm32-unknown-unknown.browser_targets"),
+                suggestion: Some(String::from("chrome")),
+            })
+        );
+        assert_eq!(
+            unknown_key_of("[profile.bench]\ncolors = true"),
+            Some(UnknownKey {
+                key: String::from("colors"),
+                table: String::from("profile.bench"),
+                suggestion: None,
+            })
+        );
+        assert_eq!(
+            unknown_key_of(
+                "[[overrides]]\npattern = \"vendor/**\"\nminify = false\n\n[[overrides]]\npattern = \"legacy/**\"\n[overrides.class_names]\nexclude = [\".*\"]"
+            ),
+            Some(UnknownKey {
+                key: String::from("exclude"),
+                table: String::from("overrides[1].class_names"),
+                suggestion: Some(String::from("excludes")),
+            })
+        );
+    }
+}
@@ -1,10 +1,16 @@
 //! You're probably looking for `turf` instead.
 
 use convert_case::{Case, Casing};
-use std::{collections::HashMap, path::PathBuf};
-use turf_internals::{CompiledStyleSheet, StyleSheetKind};
+use std::{collections::BTreeMap, path::PathBuf};
+use turf_internals::{
+    parse_bem_class_name, parse_export_number, BemClassName, ClassNameMetadata, Codegen,
+    CodegenStyle, CompiledStyleSheet, Compression, CssArtifacts, IdentifierCase,
+    IdentifierSanitization, MediaSheets, RawCss, Scoping, SourceLocation, StyleSheetKind,
+    ThemeValue, Visibility,
+};
 
 use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, TokenTree};
 use quote::quote;
 
 #[proc_macro]
@@ -12,202 +18,1569 @@ pub fn style_sheet(input: TokenStream) -> TokenStream {
     let input = input.to_string();
     let sanitized_path = PathBuf::from(input.trim_matches('"'));
 
+    match handle_style_sheet(StyleSheetKind::File(sanitized_path)) {
+        Ok(result) => create_style_sheet_items(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn style_sheet_values(input: TokenStream) -> TokenStream {
+    let input = input.to_string();
+    let sanitized_path = PathBuf::from(input.trim_matches('"'));
+
+    match handle_style_sheet(StyleSheetKind::File(sanitized_path)) {
+        Ok(result) => create_style_sheet_values(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn inline_style_sheet(input: TokenStream) -> TokenStream {
+    let input = input.to_string();
+
+    match handle_style_sheet(StyleSheetKind::Inline(input)) {
+        Ok(result) => create_style_sheet_items(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn inline_style_sheet_values(input: TokenStream) -> TokenStream {
+    let input = input.to_string();
+
+    match handle_style_sheet(StyleSheetKind::Inline(input)) {
+        Ok(result) => create_style_sheet_values(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn raw_css(input: TokenStream) -> TokenStream {
+    let css = match syn::parse::<syn::LitStr>(input) {
+        Ok(literal) => literal.value(),
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    match handle_raw_css(StyleSheetKind::Inline(css)) {
+        Ok(result) => create_raw_css_items(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn raw_css_file(input: TokenStream) -> TokenStream {
+    let input = input.to_string();
+    let sanitized_path = PathBuf::from(input.trim_matches('"'));
+
+    match handle_raw_css(StyleSheetKind::File(sanitized_path)) {
+        Ok(result) => create_raw_css_items(result).into(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+#[proc_macro]
+pub fn check_settings(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        return quote! {
+            compile_error!("turf::check_settings! doesn't take arguments");
+        }
+        .into();
+    }
+
+    match turf_internals::check_settings() {
+        Ok(()) => TokenStream::new(),
+        Err(e) => to_compile_error(e),
+    }
+}
+
+fn create_style_sheet_items(style_sheet: ProcessedStyleSheet) -> proc_macro2::TokenStream {
     let ProcessedStyleSheet {
         untracked_load_paths,
         css,
         class_names,
-    } = match handle_style_sheet(StyleSheetKind::File(sanitized_path)) {
-        Ok(result) => result,
-        Err(e) => {
-            return match e {
-                Error::Turf(e) => to_compile_error(e),
-                Error::LoadPathTracking(e) => to_compile_error(e),
+        container_names,
+        counter_styles,
+        view_transition_names,
+        keyframes,
+        parts,
+        css_variables,
+        font_families,
+        grid_areas,
+        exports,
+        breakpoints,
+        theme_variables,
+        scope_id,
+        scoping,
+        bem,
+        partitions,
+        partition_urls,
+        theme_bootstrap,
+        forced_colors_fallback,
+        class_name_sources,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        css_artifacts,
+        compressed_css,
+        version,
+        css_hash,
+        css_file_name,
+        source,
+        location,
+        codegen,
+    } = style_sheet;
+
+    let artifacts = css_artifacts.as_ref();
+    let css = embedded_str(&css, artifacts.map(|artifacts| &artifacts.css));
+    let version = embedded_str(&version, artifacts.map(|artifacts| &artifacts.version));
+    let css_hash = embedded_str(&css_hash, artifacts.map(|artifacts| &artifacts.css_hash));
+    let scope_id = embedded_str(&scope_id, artifacts.map(|artifacts| &artifacts.scope_id));
+    let class_name_rules = match codegen.css_artifacts_path {
+        Some(_) => BTreeMap::new(),
+        None => class_name_rules,
+    };
+    let derives = match create_derives(&codegen) {
+        Ok(derives) => derives,
+        Err(error) => return error,
+    };
+    let extension_items = match parse_codegen_tokens(&codegen.items, "item", "items") {
+        Ok(items) => items,
+        Err(error) => return error,
+    };
+    let mut identifier_cases = vec![constant_case(&codegen)];
+    if codegen.values_function {
+        identifier_cases.push(field_case(&codegen));
+    }
+    for case in identifier_cases {
+        let sanitization = codegen.identifier_sanitization;
+        if let Err(error) = check_class_name_identifiers(&class_names, &source, case, sanitization)
+        {
+            return error;
+        }
+    }
+
+    let (mut out, style_sheet) = create_style_sheet_static(css, compressed_css, codegen.compress);
+    out.extend(quote! {
+        pub static STYLE_SHEET_VERSION: &'static str = #version;
+        pub const STYLE_SHEET_HASH: &'static str = #css_hash;
+        pub const SCOPE_ID: &'static str = #scope_id;
+    });
+    if let Some(css_file_name) = css_file_name {
+        let css_file_name = embedded_str(
+            &css_file_name,
+            artifacts.and_then(|artifacts| artifacts.css_file_name.as_ref()),
+        );
+        out.extend(quote! {
+            pub const CSS_FILE_NAME: &'static str = #css_file_name;
+        });
+    }
+    if !partitions.is_empty() {
+        out.extend(quote! {
+            pub static PARTITIONS: &'static [&'static str] = &[#(#partitions),*];
+        });
+    }
+    if !partition_urls.is_empty() {
+        out.extend(quote! {
+            pub static PARTITION_URLS: &'static [&'static str] = &[#(#partition_urls),*];
+        });
+        if cfg!(feature = "web") {
+            out.extend(create_partition_loading());
+        }
+    }
+    if let Some(theme_bootstrap) = theme_bootstrap {
+        out.extend(quote! {
+            pub static THEME_BOOTSTRAP: &'static str = #theme_bootstrap;
+        });
+    }
+    if let Some(dark_css) = dark_css {
+        let dark_css = embedded_str(
+            &dark_css,
+            artifacts.and_then(|artifacts| artifacts.dark_css.as_ref()),
+        );
+        out.extend(quote! {
+            pub static STYLE_SHEET_DARK: &'static str = #dark_css;
+        });
+    }
+    if let Some(forced_colors_fallback) = forced_colors_fallback {
+        let forced_colors_fallback = embedded_str(
+            &forced_colors_fallback,
+            artifacts.and_then(|artifacts| artifacts.forced_colors_fallback.as_ref()),
+        );
+        out.extend(quote! {
+            pub static FORCED_COLORS_FALLBACK: &'static str = #forced_colors_fallback;
+        });
+    }
+    if scoping == Scoping::Prose {
+        out.extend(create_prose_wrapping());
+    }
+    if cfg!(feature = "web") && scoping == Scoping::Shadow {
+        out.extend(create_constructable_style_sheet_registration(&style_sheet));
+    }
+    if cfg!(feature = "devtools") {
+        out.extend(create_devtools_registration(
+            &source,
+            &style_sheet,
+            quote! { SCOPE_ID },
+            &class_names,
+            &class_name_sources,
+        ));
+    }
+    let mut class_name_items = proc_macro2::TokenStream::new();
+    if codegen.class_name_lookup {
+        if let Err(error) = check_class_name_lookup(&class_names, &source, &codegen) {
+            return error;
+        }
+        let (class_name_lookup, lookup_functions) =
+            create_class_name_lookup(&class_names, codegen.style);
+        out.extend(class_name_lookup);
+        class_name_items.extend(lookup_functions);
+    }
+    if codegen.class_name_map {
+        out.extend(create_class_name_map(&class_names));
+    }
+    if codegen.class_name_metadata {
+        out.extend(create_class_name_metadata(&class_name_metadata));
+    }
+    if let Some(media_sheets) = media_sheets {
+        out.extend(create_media_sheets(&media_sheets));
+    }
+    if codegen.values_function {
+        warn_renamed_identifiers(
+            &source,
+            location.as_ref(),
+            class_names.keys(),
+            field_case(&codegen),
+            codegen.identifier_sanitization,
+        );
+        out.extend(create_values_function(
+            &class_names,
+            &source,
+            &style_sheet,
+            &codegen,
+            &derives,
+        ));
+    }
+    let sanitization = codegen.identifier_sanitization;
+    warn_renamed_identifiers(
+        &source,
+        location.as_ref(),
+        class_names.keys(),
+        constant_case(&codegen),
+        sanitization,
+    );
+
+    if codegen.debug_stats {
+        out.extend(create_debug_stats_style_sheet_accessor(
+            &source,
+            &style_sheet,
+        ));
+        class_name_items.extend(create_debug_stats_class_name_accessors(
+            &source,
+            &class_names,
+            sanitization,
+        ));
+    }
+    if bem {
+        match create_bem_modules(&class_names, &source, sanitization) {
+            Ok(bem_modules) => out.extend(bem_modules),
+            Err(error) => return error,
+        }
+    }
+    if codegen.class_name_sources {
+        class_name_items.extend(create_class_name_sources(class_name_sources, sanitization));
+    }
+    out.extend(create_classes_structure(
+        class_names,
+        &class_name_rules,
+        &codegen,
+        &derives,
+        class_name_items,
+    ));
+    for (struct_name, names) in [
+        ("ContainerName", container_names),
+        ("CounterStyle", counter_styles),
+        ("ViewTransitionName", view_transition_names),
+        ("Keyframes", keyframes),
+        ("Parts", parts),
+        ("CssVariables", css_variables),
+        ("FontFamilies", font_families),
+        ("GridAreas", grid_areas),
+    ] {
+        match create_optional_constants_structure(struct_name, names, &source, sanitization) {
+            Ok(constants) => out.extend(constants),
+            Err(error) => return error,
+        }
+    }
+    match create_exports_structure(exports, &source, sanitization) {
+        Ok(exports) => out.extend(exports),
+        Err(error) => return error,
+    }
+    match create_optional_constants_structure("Breakpoints", breakpoints, &source, sanitization) {
+        Ok(breakpoints) => out.extend(breakpoints),
+        Err(error) => return error,
+    }
+    match create_theme_structure(theme_variables, &source, sanitization) {
+        Ok(theme) => out.extend(theme),
+        Err(error) => return error,
+    }
+    out.extend(extension_items);
+    out.extend(create_include_bytes(untracked_load_paths));
+
+    apply_codegen_item_options(out, &codegen)
+}
+
+fn create_raw_css_items(raw_css: ProcessedRawCss) -> proc_macro2::TokenStream {
+    let ProcessedRawCss {
+        untracked_load_paths,
+        raw_css:
+            RawCss {
+                css,
+                version,
+                css_hash,
+                compressed_css,
+                css_file_name,
+                codegen,
+                ..
+            },
+        source,
+    } = raw_css;
+
+    let (mut out, style_sheet) =
+        create_style_sheet_static(quote! { #css }, compressed_css, codegen.compress);
+    out.extend(quote! {
+        pub static STYLE_SHEET_VERSION: &'static str = #version;
+        pub const STYLE_SHEET_HASH: &'static str = #css_hash;
+    });
+    if let Some(css_file_name) = css_file_name {
+        out.extend(quote! {
+            pub const CSS_FILE_NAME: &'static str = #css_file_name;
+        });
+    }
+    if cfg!(feature = "devtools") {
+        out.extend(create_devtools_registration(
+            &source,
+            &style_sheet,
+            quote! { "" },
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+        ));
+    }
+    out.extend(create_include_bytes(untracked_load_paths));
+
+    apply_codegen_item_options(out, &codegen)
+}
+
+/// Applies the `codegen.attributes` and `codegen.visibility` settings to the generated items
+fn apply_codegen_item_options(
+    out: proc_macro2::TokenStream,
+    codegen: &Codegen,
+) -> proc_macro2::TokenStream {
+    let attributes = match parse_codegen_tokens(&codegen.attributes, "attribute", "attributes") {
+        Ok(attributes) => attributes.into_iter().collect(),
+        Err(error) => return error,
+    };
+    let visibility = match codegen.visibility {
+        Visibility::Pub => quote! { pub },
+        Visibility::PubCrate => quote! { pub(crate) },
+        Visibility::Private => quote! {},
+    };
+
+    apply_item_options(out, &visibility, &attributes)
+}
+
+/// Parses the Rust code of a `codegen` setting, returning a compile error for invalid code
+fn parse_codegen_tokens(
+    values: &[String],
+    kind: &str,
+    setting: &str,
+) -> Result<Vec<proc_macro2::TokenStream>, proc_macro2::TokenStream> {
+    values
+        .iter()
+        .map(|value| {
+            value.parse::<proc_macro2::TokenStream>().map_err(|_| {
+                codegen_error(format!("invalid {kind} in `codegen.{setting}` - {value}"))
+            })
+        })
+        .collect()
+}
+
+/// The derive macros of `codegen.derives` for the generated `ClassName` and `ClassNames` types
+#[derive(Default)]
+struct Derives(Vec<proc_macro2::TokenStream>);
+
+impl Derives {
+    /// Creates the derive attribute of a generated type, leaving out the traits the type already
+    /// derives or implements, which would conflict with their derived implementations
+    fn attribute(&self, implemented: &[&str]) -> proc_macro2::TokenStream {
+        let derives: Vec<&proc_macro2::TokenStream> = self
+            .0
+            .iter()
+            .filter(|derive| {
+                let trait_name =
+                    (*derive)
+                        .clone()
+                        .into_iter()
+                        .fold(None, |name, token| match token {
+                            TokenTree::Ident(ident) => Some(ident.to_string()),
+                            _ => name,
+                        });
+                !trait_name.is_some_and(|trait_name| implemented.contains(&trait_name.as_str()))
+            })
+            .collect();
+
+        match derives.is_empty() {
+            true => proc_macro2::TokenStream::new(),
+            false => quote! { #[derive(#(#derives),*)] },
+        }
+    }
+}
+
+fn create_derives(codegen: &Codegen) -> Result<Derives, proc_macro2::TokenStream> {
+    parse_codegen_tokens(&codegen.derives, "derive", "derives").map(Derives)
+}
+
+/// Adds the configured attributes to every generated item and replaces the `pub` visibility of
+/// every public item with the configured visibility
+///
+/// Items end with a `;` or a braced block that isn't followed by a `;`, which holds for all items
+/// generated by this crate.
+fn apply_item_options(
+    items: proc_macro2::TokenStream,
+    visibility: &proc_macro2::TokenStream,
+    attributes: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut out = proc_macro2::TokenStream::new();
+    let mut item: Vec<TokenTree> = Vec::new();
+    let mut tokens = items.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        let is_item_end = match &token {
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            TokenTree::Group(group) => {
+                group.delimiter() == Delimiter::Brace
+                    && !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ';')
+            }
+            _ => false,
+        };
+        item.push(token);
+
+        if is_item_end {
+            out.extend(attributes.clone());
+
+            // Skips the item's own attributes, which are a `#` followed by a bracketed group
+            let mut visibility_index = 0;
+            while matches!(item.get(visibility_index), Some(TokenTree::Punct(punct)) if punct.as_char() == '#')
+            {
+                visibility_index += 2;
+            }
+
+            for (index, token) in item.drain(..).enumerate() {
+                match token {
+                    TokenTree::Ident(ident) if index == visibility_index && ident == "pub" => {
+                        out.extend(visibility.clone())
+                    }
+                    token => out.extend([token]),
+                }
             }
         }
+    }
+    out.extend(item);
+
+    out
+}
+
+fn create_style_sheet_values(style_sheet: ProcessedStyleSheet) -> proc_macro2::TokenStream {
+    let ProcessedStyleSheet {
+        untracked_load_paths,
+        css,
+        css_artifacts,
+        compressed_css,
+        class_names,
+        source,
+        location,
+        codegen,
+        ..
+    } = style_sheet;
+
+    let css = embedded_str(&css, css_artifacts.as_ref().map(|artifacts| &artifacts.css));
+    let derives = match create_derives(&codegen) {
+        Ok(derives) => derives,
+        Err(error) => return error,
     };
+    if let Err(error) = check_class_name_identifiers(
+        &class_names,
+        &source,
+        field_case(&codegen),
+        codegen.identifier_sanitization,
+    ) {
+        return error;
+    }
+
+    let includes = create_include_bytes(untracked_load_paths);
+    let debug_stats = codegen
+        .debug_stats
+        .then(|| create_debug_stats_injection_record(&source));
+    warn_renamed_identifiers(
+        &source,
+        location.as_ref(),
+        class_names.keys(),
+        field_case(&codegen),
+        codegen.identifier_sanitization,
+    );
+    let class_names_struct = create_class_names_struct(
+        class_names,
+        field_case(&codegen),
+        codegen.identifier_sanitization,
+        &derives,
+    );
+
+    let (style_sheet_static, style_sheet) =
+        create_style_sheet_static(css, compressed_css, codegen.compress);
+
+    quote! {{
+        #style_sheet_static
+        #includes
+        #debug_stats
+        #class_names_struct
+
+        (#style_sheet, ClassNames::new())
+    }}
+}
 
-    let mut out = quote! {
-        pub static STYLE_SHEET: &'static str = #css;
+/// Creates the `STYLE_SHEET` static, or the `STYLE_SHEET_COMPRESSED` static and the
+/// `decompressed_style_sheet` function with `codegen.compress`, and the expression the other
+/// generated items read the css with
+fn create_style_sheet_static(
+    css: proc_macro2::TokenStream,
+    compressed_css: Option<Vec<u8>>,
+    compression: Option<Compression>,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (Some(compressed_css), Some(compression)) = (compressed_css, compression) else {
+        return (
+            quote! { pub static STYLE_SHEET: &'static str = #css; },
+            quote! { STYLE_SHEET },
+        );
+    };
+
+    let compressed_css = proc_macro2::Literal::byte_string(&compressed_css);
+    let decompress = match compression {
+        Compression::Brotli => quote! { ::turf::compression::decompress_brotli },
+        Compression::Gzip => quote! { ::turf::compression::decompress_gzip },
     };
-    out.extend(create_classes_structure(class_names));
-    out.extend(create_include_bytes(untracked_load_paths));
 
-    out.into()
+    (
+        quote! {
+            pub static STYLE_SHEET_COMPRESSED: &'static [u8] = #compressed_css;
+            pub fn decompressed_style_sheet() -> &'static str {
+                static STYLE_SHEET: ::std::sync::OnceLock<::std::string::String> =
+                    ::std::sync::OnceLock::new();
+                STYLE_SHEET.get_or_init(|| #decompress(STYLE_SHEET_COMPRESSED))
+            }
+        },
+        quote! { decompressed_style_sheet() },
+    )
 }
 
-#[proc_macro]
-pub fn style_sheet_values(input: TokenStream) -> TokenStream {
-    let input = input.to_string();
-    let sanitized_path = PathBuf::from(input.trim_matches('"'));
+/// Embeds a value that changes with the css from its artifact if there is one, which keeps the
+/// generated tokens unchanged by edits that don't change the class names
+fn embedded_str(value: &str, artifact: Option<&String>) -> proc_macro2::TokenStream {
+    match artifact {
+        Some(path) => quote! { ::core::include_str!(#path) },
+        None => quote! { #value },
+    }
+}
+
+/// Creates the compile error of an error found while generating the items of a style sheet
+fn codegen_error(message: String) -> proc_macro2::TokenStream {
+    let message = format!("Error: {message}");
+    turf_internals::record_compile_error(&message);
+    quote! { compile_error!(#message); }
+}
+
+fn to_compile_error<E>(e: E) -> TokenStream
+where
+    E: std::error::Error,
+{
+    let mut message = format!("Error: {}", e);
+    let mut curr_err = e.source();
+
+    if curr_err.is_some() {
+        message.push_str("\nCaused by:");
+    }
+
+    while let Some(current_error) = curr_err {
+        message.push_str(&format!("\n    {}", current_error));
+        curr_err = current_error.source();
+    }
+
+    quote! {
+        compile_error!(#message);
+    }
+    .into()
+}
+
+fn create_classes_structure(
+    classes: BTreeMap<String, String>,
+    class_name_rules: &BTreeMap<String, Vec<String>>,
+    codegen: &Codegen,
+    derives: &Derives,
+    items: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let case = constant_case(codegen);
+    let sanitization = codegen.identifier_sanitization;
+    let docs: Vec<String> = classes
+        .iter()
+        .map(|(original_name, name)| {
+            let rules = class_name_rules.get(original_name);
+            match codegen.class_name_doc.as_deref() {
+                Some(doc_template) => {
+                    templated_class_name_doc(doc_template, original_name, name, rules)
+                }
+                None => class_name_doc(original_name, name, rules),
+            }
+        })
+        .collect();
+    // Constants of another case than the one Rust expects would trigger the lint in the crate
+    // calling the macro
+    let lint_allowance =
+        (case != Case::ScreamingSnake).then(|| quote! { #[allow(non_upper_case_globals)] });
+
+    match codegen.style {
+        CodegenStyle::Struct => {
+            let (original_names, randomized_names) =
+                constant_names_and_values(&classes, case, sanitization);
+
+            let derives = derives.attribute(&[]);
+
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
+                #derives
+                pub struct ClassName;
+                #lint_allowance
+                impl ClassName {
+                    #(
+                        #[doc=#docs]
+                        pub const #original_names: &'static str = #randomized_names;
+                    )*
+                    #items
+                }
+            }
+        }
+        CodegenStyle::Module => {
+            let (original_names, randomized_names) =
+                constant_names_and_values(&classes, case, sanitization);
+
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
+                #lint_allowance
+                pub mod class {
+                    #(
+                        #[doc=#docs]
+                        pub const #original_names: &'static str = #randomized_names;
+                    )*
+                    #items
+                }
+            }
+        }
+        CodegenStyle::Enum => {
+            let classes: Vec<(String, String, String)> = classes
+                .into_iter()
+                .zip(docs)
+                .map(|((original_name, name), doc)| (original_name, name, doc))
+                .collect();
+
+            let variants: Vec<proc_macro2::Ident> = classes
+                .iter()
+                .map(|(original_name, _, _)| identifier(original_name, case, sanitization))
+                .collect();
+            let randomized_names: Vec<&String> = classes.iter().map(|(_, name, _)| name).collect();
+            let docs: Vec<&String> = classes.iter().map(|(_, _, doc)| doc).collect();
+            let derives = derives.attribute(&["Debug", "Clone", "Copy", "PartialEq", "Eq", "Hash"]);
+
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                #derives
+                pub enum ClassName {
+                    #(
+                        #[doc=#docs]
+                        #variants,
+                    )*
+                }
+                impl ClassName {
+                    pub const ALL: &'static [ClassName] = &[#(ClassName::#variants),*];
+
+                    pub const fn as_str(&self) -> &'static str {
+                        match *self {
+                            #(ClassName::#variants => #randomized_names,)*
+                        }
+                    }
+
+                    #items
+                }
+                impl ::std::fmt::Display for ClassName {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.write_str(self.as_str())
+                    }
+                }
+                impl ::std::str::FromStr for ClassName {
+                    type Err = ::turf::ParseClassNameError;
+
+                    fn from_str(class_name: &str) -> Result<Self, Self::Err> {
+                        match class_name {
+                            #(#randomized_names => Ok(ClassName::#variants),)*
+                            _ => Err(::turf::ParseClassNameError(class_name.to_owned())),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Documents a class name with its original name, its generated name and the rules selecting it
+fn class_name_doc(original_name: &str, name: &str, rules: Option<&Vec<String>>) -> String {
+    let mut doc = format!("`{original_name}` = `\"{name}\"`");
+    if let Some(rules) = rules {
+        doc.push_str(&format!("\n\n```css\n{}\n```", rules.join("\n\n")));
+    }
+
+    doc
+}
+
+/// Documents a class name with the `codegen.class_name_doc` template
+fn templated_class_name_doc(
+    doc_template: &str,
+    original_name: &str,
+    name: &str,
+    rules: Option<&Vec<String>>,
+) -> String {
+    let rules = rules.map(|rules| rules.join("\n\n")).unwrap_or_default();
+
+    doc_template
+        .replace("<original_name>", original_name)
+        .replace("<name>", name)
+        .replace("<rules>", &rules)
+}
+
+/// The case of the identifiers set in the settings, or the default case if the setting isn't set
+fn case(identifier_case: Option<IdentifierCase>, default: Case) -> Case {
+    match identifier_case {
+        Some(IdentifierCase::ScreamingSnake) => Case::ScreamingSnake,
+        Some(IdentifierCase::Snake) => Case::Snake,
+        Some(IdentifierCase::Pascal) => Case::Pascal,
+        Some(IdentifierCase::Camel) => Case::Camel,
+        None => default,
+    }
+}
+
+/// The case of the constants or variants of the `ClassName` items
+fn constant_case(codegen: &Codegen) -> Case {
+    match codegen.style {
+        CodegenStyle::Struct | CodegenStyle::Module => {
+            case(codegen.const_case, Case::ScreamingSnake)
+        }
+        CodegenStyle::Enum => Case::Pascal,
+    }
+}
+
+/// The case of the fields of the `ClassNames` struct
+fn field_case(codegen: &Codegen) -> Case {
+    case(codegen.field_case, Case::Snake)
+}
+
+/// Converts a name of the style sheet to the given case without checking whether the result is a
+/// valid identifier
+fn converted_name(name: &str, case: Case) -> String {
+    match case {
+        Case::ScreamingSnake => name.to_case(case).to_uppercase(),
+        _ => name.to_case(case),
+    }
+}
+
+/// Converts a name of the style sheet to an identifier of the given case
+///
+/// Characters that can't be part of an identifier are replaced with `_` or stripped, names
+/// starting with a character that can't start an identifier, such as a digit, are prefixed with `_`
+/// and keywords are suffixed with `_`. Names without any valid characters consist of their code
+/// points.
+fn identifier_name(name: &str, case: Case, sanitization: IdentifierSanitization) -> String {
+    let is_identifier_char = |c: &char| unicode_ident::is_xid_continue(*c);
+    let converted_name = converted_name(name, case);
+    let mut identifier_name: String = match sanitization {
+        IdentifierSanitization::Replace => converted_name
+            .chars()
+            .map(|c| if is_identifier_char(&c) { c } else { '_' })
+            .collect(),
+        IdentifierSanitization::Strip => {
+            converted_name.chars().filter(is_identifier_char).collect()
+        }
+    };
+
+    // Names without any valid characters, such as emojis, are identified by their code points
+    if identifier_name.chars().all(|c| c == '_') {
+        identifier_name = name.chars().map(|c| format!("_{:X}", c as u32)).collect();
+    } else if !identifier_name.starts_with(|c| c == '_' || unicode_ident::is_xid_start(c)) {
+        identifier_name.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&identifier_name.as_str()) {
+        identifier_name.push('_');
+    }
+
+    identifier_name
+}
+
+fn identifier(name: &str, case: Case, sanitization: IdentifierSanitization) -> proc_macro2::Ident {
+    quote::format_ident!("{}", identifier_name(name, case, sanitization))
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "union", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+fn warn_renamed_identifiers<'a>(
+    source: &str,
+    location: Option<&SourceLocation>,
+    names: impl Iterator<Item = &'a String>,
+    case: Case,
+    sanitization: IdentifierSanitization,
+) {
+    let (renamed_names, renamed_identifiers): (Vec<&String>, Vec<String>) = names
+        .filter_map(|name| {
+            let identifier_name = identifier_name(name, case, sanitization);
+            (identifier_name != converted_name(name, case))
+                .then(|| (name, format!("{name:?} as {identifier_name}")))
+        })
+        .unzip();
+
+    if let Some(first_renamed_name) = renamed_names.first() {
+        let location = location
+            .map(|location| turf_internals::class_name_location(location, first_renamed_name));
+        turf_internals::compile_warning(
+            &format!(
+                "class names that aren't valid identifiers renamed - {} ({source})",
+                renamed_identifiers.join(", ")
+            ),
+            location.as_ref(),
+        );
+    }
+}
+
+fn constant_names_and_values(
+    names: &BTreeMap<String, String>,
+    case: Case,
+    sanitization: IdentifierSanitization,
+) -> (Vec<proc_macro2::Ident>, Vec<&String>) {
+    let original_names = names
+        .keys()
+        .map(|name| identifier(name, case, sanitization))
+        .collect();
+    let randomized_names = names.values().collect();
+
+    (original_names, randomized_names)
+}
+
+fn constants_doc(original_names: &[proc_macro2::Ident], randomized_names: &[&String]) -> String {
+    original_names.iter().zip(randomized_names.iter()).fold(
+        String::new(),
+        |mut doc, (variable, name)| {
+            doc.push_str(&format!("{} = \"{}\"\n", variable, name));
+            doc
+        },
+    )
+}
+
+fn create_optional_constants_structure(
+    struct_name: &str,
+    names: BTreeMap<String, String>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<Option<proc_macro2::TokenStream>, proc_macro2::TokenStream> {
+    match names.is_empty() {
+        true => Ok(None),
+        false => create_constants_structure(struct_name, names, source, sanitization).map(Some),
+    }
+}
+
+/// Creates a struct with a constant for every name, fails if two names are converted to the same
+/// constant, e.g. the keyframes `fade-in` and `fade_in`
+fn create_constants_structure(
+    struct_name: &str,
+    names: BTreeMap<String, String>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
+    let identifier_names = names.keys().map(|name| {
+        (
+            identifier_name(name, Case::ScreamingSnake, sanitization),
+            name.as_str(),
+        )
+    });
+    if let Some((identifier_name, name, other_name)) = identifier_collision(identifier_names) {
+        return Err(codegen_error(format!(
+            "the names `{name}` and `{other_name}` of {source} are both converted to the constant `{struct_name}::{identifier_name}` - rename one of them"
+        )));
+    }
+
+    let struct_name = quote::format_ident!("{}", struct_name);
+    let (original_names, randomized_names) =
+        constant_names_and_values(&names, Case::ScreamingSnake, sanitization);
+    let doc = constants_doc(&original_names, &randomized_names);
+
+    Ok(quote::quote! {
+        #[doc=#doc]
+        pub struct #struct_name;
+        impl #struct_name {
+            #(pub const #original_names: &'static str = #randomized_names;)*
+        }
+    })
+}
+
+/// Finds two names that are converted to the same identifier and would generate conflicting items,
+/// returning the identifier and both names
+fn identifier_collision<'a>(
+    names: impl IntoIterator<Item = (String, &'a str)>,
+) -> Option<(String, &'a str, &'a str)> {
+    let mut identifiers = BTreeMap::new();
+    for (identifier_name, name) in names {
+        if let Some(other_name) = identifiers.insert(identifier_name.clone(), name) {
+            return Some((identifier_name, other_name, name));
+        }
+    }
+
+    None
+}
+
+/// Fails if two class names are converted to the same identifier of the given case, e.g. `is-active`
+/// and `is_active`
+fn check_class_name_identifiers(
+    class_names: &BTreeMap<String, String>,
+    source: &str,
+    case: Case,
+    sanitization: IdentifierSanitization,
+) -> Result<(), proc_macro2::TokenStream> {
+    let identifier_names = class_names
+        .keys()
+        .map(|name| (identifier_name(name, case, sanitization), name.as_str()));
+
+    match identifier_collision(identifier_names) {
+        Some((identifier_name, name, other_name)) => Err(codegen_error(format!(
+            "the class names `{name}` and `{other_name}` of {source} are both converted to the identifier `{identifier_name}` - rename one of them"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Creates the `Exports` struct with the values of `:export` blocks and an additional `f64`
+/// constant for every value that is a number
+///
+/// Fails for numbers that don't fit into an `f64` and for exports whose constants have the same
+/// name.
+fn create_exports_structure(
+    exports: BTreeMap<String, String>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<Option<proc_macro2::TokenStream>, proc_macro2::TokenStream> {
+    if exports.is_empty() {
+        return Ok(None);
+    }
+
+    let mut constant_names: Vec<(String, &str)> = exports
+        .keys()
+        .map(|name| {
+            let constant_name = identifier_name(name, Case::ScreamingSnake, sanitization);
+            (constant_name, name.as_str())
+        })
+        .collect();
+    let mut number_names = Vec::new();
+    let mut numbers = Vec::new();
+    for (name, value) in &exports {
+        let Some(number) = parse_export_number(value) else {
+            continue;
+        };
+        if !number.is_finite() {
+            return Err(codegen_error(format!(
+                "the exported value `{name}: {value}` of {source} is out of the range of an `f64`"
+            )));
+        }
+        let number_name = identifier_name(
+            &format!("{name}_number"),
+            Case::ScreamingSnake,
+            sanitization,
+        );
+        number_names.push(quote::format_ident!("{}", number_name));
+        numbers.push(proc_macro2::Literal::f64_suffixed(number));
+        constant_names.push((number_name, name.as_str()));
+    }
+    if let Some((identifier_name, name, other_name)) = identifier_collision(constant_names) {
+        return Err(codegen_error(format!(
+            "the exports `{name}` and `{other_name}` of {source} both generate the `Exports::{identifier_name}` constant - rename one of them"
+        )));
+    }
+
+    let mut out = create_constants_structure("Exports", exports, source, sanitization)?;
+    if !numbers.is_empty() {
+        out.extend(quote! {
+            impl Exports {
+                #(pub const #number_names: f64 = #numbers;)*
+            }
+        });
+    }
+
+    Ok(Some(out))
+}
+
+/// Creates the `Theme` struct with a typed field for every top level variable of the style sheet
+/// and the `THEME` constant holding their values
+///
+/// Fails for numbers that don't fit into an `f32`.
+fn create_theme_structure(
+    theme_variables: BTreeMap<String, String>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<Option<proc_macro2::TokenStream>, proc_macro2::TokenStream> {
+    if theme_variables.is_empty() {
+        return Ok(None);
+    }
+
+    let f32_literal = |name: &str, value: &str, number: f64| {
+        let number = number as f32;
+        match number.is_finite() {
+            true => Ok(proc_macro2::Literal::f32_suffixed(number)),
+            false => Err(codegen_error(format!(
+                "the theme variable `${name}: {value}` of {source} is out of the range of an `f32`"
+            ))),
+        }
+    };
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    let mut field_values = Vec::new();
+    for (name, value) in &theme_variables {
+        let (field_type, field_value) = match ThemeValue::parse(value) {
+            ThemeValue::Number(number) => {
+                let number = f32_literal(name, value, number)?;
+                (quote! { f32 }, quote! { #number })
+            }
+            ThemeValue::Length(number, unit) => {
+                let number = f32_literal(name, value, number)?;
+                (
+                    quote! { ::turf::Length },
+                    quote! { ::turf::Length { value: #number, unit: #unit } },
+                )
+            }
+            ThemeValue::Text(text) => (quote! { &'static str }, quote! { #text }),
+        };
+        field_names.push(identifier(name, Case::Snake, sanitization));
+        field_types.push(field_type);
+        field_values.push(field_value);
+    }
+    let doc = theme_variables
+        .iter()
+        .fold(String::new(), |mut doc, (name, value)| {
+            doc.push_str(&format!("${name}: {value}\n"));
+            doc
+        });
+
+    Ok(Some(quote! {
+        #[doc=#doc]
+        pub struct Theme {
+            #(pub #field_names: #field_types,)*
+        }
+        pub const THEME: Theme = Theme {
+            #(#field_names: #field_values,)*
+        };
+    }))
+}
+
+#[derive(Default)]
+struct BemModule<'a> {
+    class_name: Option<&'a str>,
+    modifiers: BTreeMap<&'a str, &'a str>,
+    elements: BTreeMap<&'a str, BemModule<'a>>,
+}
+
+impl<'a> BemModule<'a> {
+    /// Fails if a modifier is converted to the `BLOCK` or `ELEMENT` constant of the module, or if
+    /// two modifiers or elements are converted to the same identifier
+    fn into_token_stream(
+        self,
+        name: &str,
+        constant_name: &str,
+        source: &str,
+        sanitization: IdentifierSanitization,
+    ) -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
+        let module_name = identifier(name, Case::Snake, sanitization);
+        if self.class_name.is_some() {
+            if let Some(modifier) = self.modifiers.keys().find(|modifier| {
+                identifier_name(modifier, Case::ScreamingSnake, sanitization) == constant_name
+            }) {
+                return Err(codegen_error(format!(
+                    "the BEM modifier `{modifier}` of `{name}` in {source} is converted to the identifier `{constant_name}` of `{name}` itself - rename the modifier"
+                )));
+            }
+        }
+        check_bem_identifiers(
+            "modifiers",
+            self.modifiers.keys().copied(),
+            Case::ScreamingSnake,
+            Some(name),
+            source,
+            sanitization,
+        )?;
+        check_bem_identifiers(
+            "elements",
+            self.elements.keys().copied(),
+            Case::Snake,
+            Some(name),
+            source,
+            sanitization,
+        )?;
+
+        let class_name = self.class_name.map(|class_name| {
+            let constant_name = quote::format_ident!("{}", constant_name);
+            quote::quote! {
+                pub const #constant_name: &'static str = #class_name;
+            }
+        });
+        let modifier_names: Vec<proc_macro2::Ident> = self
+            .modifiers
+            .keys()
+            .map(|modifier| identifier(modifier, Case::ScreamingSnake, sanitization))
+            .collect();
+        let modifier_class_names: Vec<&str> = self.modifiers.values().copied().collect();
+        let elements = self
+            .elements
+            .into_iter()
+            .map(|(element, module)| {
+                module.into_token_stream(element, "ELEMENT", source, sanitization)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(quote::quote! {
+            pub mod #module_name {
+                #class_name
+                #(pub const #modifier_names: &'static str = #modifier_class_names;)*
+                #(#elements)*
+            }
+        })
+    }
+}
+
+/// Fails if two blocks, elements or modifiers of the same parent are converted to the same
+/// identifier of the `class_name` module, e.g. `is-active` and `is_active`
+fn check_bem_identifiers<'a>(
+    kind: &str,
+    names: impl Iterator<Item = &'a str>,
+    case: Case,
+    parent: Option<&str>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<(), proc_macro2::TokenStream> {
+    let identifier_names = names.map(|name| (identifier_name(name, case, sanitization), name));
+
+    match identifier_collision(identifier_names) {
+        Some((identifier_name, name, other_name)) => Err(codegen_error(format!(
+            "the BEM {kind} `{name}` and `{other_name}`{} in {source} are both converted to the identifier `{identifier_name}` - rename one of them",
+            parent.map(|parent| format!(" of `{parent}`")).unwrap_or_default()
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn create_bem_modules(
+    classes: &BTreeMap<String, String>,
+    source: &str,
+    sanitization: IdentifierSanitization,
+) -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
+    let mut blocks: BTreeMap<&str, BemModule> = BTreeMap::new();
+
+    for (original_class_name, class_name) in classes {
+        let BemClassName {
+            block,
+            element,
+            modifier,
+        } = parse_bem_class_name(original_class_name);
+
+        let mut module = blocks.entry(block).or_default();
+        if let Some(element) = element {
+            module = module.elements.entry(element).or_default();
+        }
+
+        match modifier {
+            Some(modifier) => {
+                module.modifiers.insert(modifier, class_name);
+            }
+            None => module.class_name = Some(class_name),
+        }
+    }
+
+    check_bem_identifiers(
+        "blocks",
+        blocks.keys().copied(),
+        Case::Snake,
+        None,
+        source,
+        sanitization,
+    )?;
+    let blocks = blocks
+        .into_iter()
+        .map(|(block, module)| module.into_token_stream(block, "BLOCK", source, sanitization))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote::quote! {
+        pub mod class_name {
+            #(#blocks)*
+        }
+    })
+}
+
+fn create_class_names_struct(
+    classes: BTreeMap<String, String>,
+    case: Case,
+    sanitization: IdentifierSanitization,
+    derives: &Derives,
+) -> proc_macro2::TokenStream {
+    let original_class_names: Vec<proc_macro2::Ident> = classes
+        .keys()
+        .map(|class| identifier(class, case, sanitization))
+        .collect();
+    let lint_allowance = (case != Case::Snake).then(|| quote! { #[allow(non_snake_case)] });
+
+    let randomized_class_names: Vec<&String> = classes.values().collect();
+
+    let doc = original_class_names
+        .iter()
+        .zip(randomized_class_names.iter())
+        .fold(String::new(), |mut doc, (variable, class_name)| {
+            doc.push_str(&format!("{} = \"{}\"\n", variable, class_name));
+            doc
+        });
+    let derives = derives.attribute(&["Debug", "Clone", "Copy", "PartialEq", "Eq", "Default"]);
+
+    quote::quote! {
+        #[doc=#doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #derives
+        #lint_allowance
+        pub struct ClassNames {
+            #(pub #original_class_names: &'static str,)*
+        }
+        impl ClassNames {
+            pub const fn new() -> Self {
+                Self {
+                    #(#original_class_names: #randomized_class_names,)*
+                }
+            }
+        }
+        impl ::std::default::Default for ClassNames {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }
+}
+
+/// Creates the `ClassNames` struct of the `*_values` macros and a `values()` function returning
+/// the same tuple as these macros
+fn create_values_function(
+    class_names: &BTreeMap<String, String>,
+    source: &str,
+    style_sheet: &proc_macro2::TokenStream,
+    codegen: &Codegen,
+    derives: &Derives,
+) -> proc_macro2::TokenStream {
+    let class_names_struct = create_class_names_struct(
+        class_names.clone(),
+        field_case(codegen),
+        codegen.identifier_sanitization,
+        derives,
+    );
+    let debug_stats = codegen
+        .debug_stats
+        .then(|| create_debug_stats_injection_record(source));
+
+    quote! {
+        #class_names_struct
+        pub fn values() -> (&'static str, ClassNames) {
+            #debug_stats
+            (#style_sheet, ClassNames::new())
+        }
+    }
+}
+
+/// The functions generated next to the class name constants by `codegen.class_name_lookup`
+const CLASS_NAME_LOOKUP_FUNCTIONS: [&str; 3] = ["lookup", "get", "iter"];
+
+/// Fails if a class name is converted to the name of a lookup function, which is generated in the
+/// same namespace as the class name constants and the accessor functions of `debug_stats`
+fn check_class_name_lookup(
+    class_names: &BTreeMap<String, String>,
+    source: &str,
+    codegen: &Codegen,
+) -> Result<(), proc_macro2::TokenStream> {
+    let mut cases = Vec::new();
+    if codegen.style != CodegenStyle::Enum {
+        cases.push(constant_case(codegen));
+    }
+    if codegen.debug_stats {
+        cases.push(Case::Snake);
+    }
 
-    let ProcessedStyleSheet {
-        untracked_load_paths,
-        css,
-        class_names,
-    } = match handle_style_sheet(StyleSheetKind::File(sanitized_path)) {
-        Ok(result) => result,
-        Err(e) => {
-            return match e {
-                Error::Turf(e) => to_compile_error(e),
-                Error::LoadPathTracking(e) => to_compile_error(e),
+    for name in class_names.keys() {
+        for case in &cases {
+            let identifier_name = identifier_name(name, *case, codegen.identifier_sanitization);
+            if CLASS_NAME_LOOKUP_FUNCTIONS.contains(&identifier_name.as_str()) {
+                return Err(codegen_error(format!(
+                    "the class name `{name}` of {source} is converted to the identifier `{identifier_name}`, which is the name of a function generated by `codegen.class_name_lookup` - rename the class name or disable the option"
+                )));
             }
         }
-    };
-
-    let includes = create_include_bytes(untracked_load_paths);
-    let inlines = create_inline_classes_instance(class_names);
-    let out = quote! {{
-        pub static STYLE_SHEET: &'static str = #css;
-        #includes
-        #inlines
-    }};
+    }
 
-    out.into()
+    Ok(())
 }
 
-#[proc_macro]
-pub fn inline_style_sheet(input: TokenStream) -> TokenStream {
-    let input = input.to_string();
+fn create_class_name_lookup(
+    classes: &BTreeMap<String, String>,
+    style: CodegenStyle,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let mut map = phf_codegen::Map::new();
+    map.phf_path("::turf::__private::phf");
+    for (original_class_name, class_name) in classes {
+        map.entry(
+            original_class_name.as_str(),
+            &quote! { #class_name }.to_string(),
+        );
+    }
+    let map: proc_macro2::TokenStream = map
+        .build()
+        .to_string()
+        .parse()
+        .expect("phf_codegen generates valid tokens");
 
-    let ProcessedStyleSheet {
-        untracked_load_paths,
-        css,
-        class_names,
-    } = match handle_style_sheet(StyleSheetKind::Inline(input)) {
-        Ok(result) => result,
-        Err(e) => {
-            return match e {
-                Error::Turf(e) => to_compile_error(e),
-                Error::LoadPathTracking(e) => to_compile_error(e),
-            }
-        }
-    };
+    let original_class_names = classes.keys();
+    let class_names = classes.values();
 
-    let mut out = quote! {
-        pub static STYLE_SHEET: &'static str = #css;
+    // The entries of the phf map are in hash order, so `iter` reads the pairs from a slice that is
+    // sorted by the original name instead
+    let class_name_lookup = quote::quote! {
+        static CLASS_NAME_LOOKUP: ::turf::__private::phf::Map<&'static str, &'static str> = #map;
+        static CLASS_NAME_ENTRIES: &[(&str, &str)] =
+            &[#((#original_class_names, #class_names)),*];
     };
-    out.extend(create_classes_structure(class_names));
-    out.extend(create_include_bytes(untracked_load_paths));
-
-    out.into()
-}
+    // The lookup functions of the `class` module are generated one level below the lookup
+    let parent = match style {
+        CodegenStyle::Module => quote! { super:: },
+        CodegenStyle::Struct | CodegenStyle::Enum => proc_macro2::TokenStream::new(),
+    };
+    let lookup_functions = quote::quote! {
+        /// Returns the generated class name for an original class name of the style sheet
+        pub fn lookup(original_class_name: &str) -> Option<&'static str> {
+            #parent CLASS_NAME_LOOKUP.get(original_class_name).copied()
+        }
 
-#[proc_macro]
-pub fn inline_style_sheet_values(input: TokenStream) -> TokenStream {
-    let input = input.to_string();
+        /// Returns the generated class name for an original class name of the style sheet, an
+        /// alias of `lookup`
+        pub fn get(original_class_name: &str) -> Option<&'static str> {
+            #parent CLASS_NAME_LOOKUP.get(original_class_name).copied()
+        }
 
-    let ProcessedStyleSheet {
-        untracked_load_paths,
-        css,
-        class_names,
-    } = match handle_style_sheet(StyleSheetKind::Inline(input)) {
-        Ok(result) => result,
-        Err(e) => {
-            return match e {
-                Error::Turf(e) => to_compile_error(e),
-                Error::LoadPathTracking(e) => to_compile_error(e),
-            }
+        /// Returns all pairs of original and generated class names of the style sheet, sorted by
+        /// the original name
+        pub fn iter() -> impl Iterator<Item = (&'static str, &'static str)> {
+            #parent CLASS_NAME_ENTRIES.iter().copied()
         }
     };
 
-    let includes = create_include_bytes(untracked_load_paths);
-    let inlines = create_inline_classes_instance(class_names);
-    let out = quote! {{
-        pub static STYLE_SHEET: &'static str = #css;
-        #includes
-        #inlines
-    }};
+    (class_name_lookup, lookup_functions)
+}
 
-    out.into()
+fn create_prose_wrapping() -> proc_macro2::TokenStream {
+    quote::quote! {
+        /// Wraps rendered HTML, e.g. of markdown or CMS content, in a `div` with the root class of
+        /// the style sheet
+        pub fn wrap_prose(html: &str) -> ::std::string::String {
+            ::std::format!("<div class=\"{}\">{}</div>", SCOPE_ID, html)
+        }
+    }
 }
 
-fn to_compile_error<E>(e: E) -> TokenStream
-where
-    E: std::error::Error,
-{
-    let mut message = format!("Error: {}", e);
-    let mut curr_err = e.source();
+fn create_class_name_map(classes: &BTreeMap<String, String>) -> proc_macro2::TokenStream {
+    let original_class_names = classes.keys();
+    let class_names = classes.values();
 
-    if curr_err.is_some() {
-        message.push_str("\nCaused by:");
+    quote::quote! {
+        pub static CLASS_NAME_MAP: &'static [(&'static str, &'static str)] =
+            &[#((#original_class_names, #class_names)),*];
     }
+}
 
-    while let Some(current_error) = curr_err {
-        message.push_str(&format!("\n    {}", current_error));
-        curr_err = current_error.source();
+fn create_class_name_metadata(
+    class_name_metadata: &BTreeMap<String, ClassNameMetadata>,
+) -> proc_macro2::TokenStream {
+    let entries = class_name_metadata.iter().map(|(class_name, metadata)| {
+        let media_queries = metadata.media_queries.iter();
+        let pseudo_classes = metadata.pseudo_classes.iter();
+
+        quote::quote! {
+            ::turf::ClassNameMetadata {
+                class_name: #class_name,
+                media_queries: &[#(#media_queries),*],
+                pseudo_classes: &[#(#pseudo_classes),*],
+            }
+        }
+    });
+
+    quote::quote! {
+        pub static CLASS_NAME_METADATA: &'static [::turf::ClassNameMetadata] = &[#(#entries),*];
     }
+}
 
-    quote! {
-        compile_error!(#message);
+fn create_media_sheets(media_sheets: &MediaSheets) -> proc_macro2::TokenStream {
+    let base = &media_sheets.base;
+    let queries = media_sheets.media.iter().map(|(query, _)| query);
+    let sheets = media_sheets.media.iter().map(|(_, css)| css);
+
+    quote::quote! {
+        pub static STYLE_SHEET_BASE: &'static str = #base;
+        pub static MEDIA_SHEETS: &'static [(&'static str, &'static str)] =
+            &[#((#queries, #sheets)),*];
     }
-    .into()
 }
 
-fn create_classes_structure(classes: HashMap<String, String>) -> proc_macro2::TokenStream {
-    let original_class_names: Vec<proc_macro2::Ident> = classes
+fn create_class_name_sources(
+    class_name_sources: BTreeMap<String, String>,
+    sanitization: IdentifierSanitization,
+) -> proc_macro2::TokenStream {
+    let source_names: Vec<proc_macro2::Ident> = class_name_sources
         .keys()
-        .map(|class| class.to_case(Case::ScreamingSnake))
-        .map(|class| quote::format_ident!("{}", class.as_str().to_uppercase()))
+        .map(|name| identifier_name(name, Case::ScreamingSnake, sanitization))
+        .map(|name| quote::format_ident!("{}_SOURCE", name))
         .collect();
+    let sources: Vec<&String> = class_name_sources.values().collect();
 
-    let randomized_class_names: Vec<&String> = classes.values().collect();
+    quote::quote! {
+        #(
+            #[cfg(debug_assertions)]
+            pub const #source_names: &'static str = #sources;
+        )*
+    }
+}
 
-    let doc = original_class_names
-        .iter()
-        .zip(randomized_class_names.iter())
-        .fold(String::new(), |mut doc, (variable, class_name)| {
-            doc.push_str(&format!("{} = \"{}\"\n", variable, class_name));
-            doc
-        });
+fn create_debug_stats_injection_record(source: &str) -> proc_macro2::TokenStream {
+    quote::quote! {
+        #[cfg(debug_assertions)]
+        ::turf::__private::record_style_sheet_injection(#source);
+    }
+}
+
+fn create_debug_stats_style_sheet_accessor(
+    source: &str,
+    style_sheet: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let injection_record = create_debug_stats_injection_record(source);
 
     quote::quote! {
-        #[doc=#doc]
-        pub struct ClassName;
-        impl ClassName {
-            #(pub const #original_class_names: &'static str = #randomized_class_names;)*
+        #[cfg(debug_assertions)]
+        pub fn style_sheet() -> &'static str {
+            #injection_record
+            #style_sheet
         }
     }
 }
 
-fn create_inline_classes_instance(classes: HashMap<String, String>) -> proc_macro2::TokenStream {
-    let original_class_names: Vec<proc_macro2::Ident> = classes
+fn create_debug_stats_class_name_accessors(
+    source: &str,
+    classes: &BTreeMap<String, String>,
+    sanitization: IdentifierSanitization,
+) -> proc_macro2::TokenStream {
+    let original_class_names: Vec<&String> = classes.keys().collect();
+    let accessor_names: Vec<proc_macro2::Ident> = classes
         .keys()
-        .map(|class| class.to_case(Case::Snake))
-        .map(|class| quote::format_ident!("{}", class.as_str()))
+        .map(|class| identifier(class, Case::Snake, sanitization))
         .collect();
-
     let randomized_class_names: Vec<&String> = classes.values().collect();
 
-    let doc = original_class_names
-        .iter()
-        .zip(randomized_class_names.iter())
-        .fold(String::new(), |mut doc, (variable, class_name)| {
-            doc.push_str(&format!("{} = \"{}\"\n", variable, class_name));
-            doc
-        });
+    quote::quote! {
+        #(
+            #[cfg(debug_assertions)]
+            pub fn #accessor_names() -> &'static str {
+                ::turf::__private::record_class_name_reference(#source, #original_class_names);
+                #randomized_class_names
+            }
+        )*
+    }
+}
 
+fn create_constructable_style_sheet_registration(
+    style_sheet: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
     quote::quote! {
-        #[doc=#doc]
-        pub struct ClassNames {
-            #(pub #original_class_names: &'static str,)*
+        pub fn register_constructable_stylesheet(
+            root: &impl AsRef<::turf::web::JsValue>,
+        ) -> Result<(), ::turf::web::JsValue> {
+            ::turf::web::adopt_style_sheet(root.as_ref(), #style_sheet)
         }
-        impl ClassNames {
-            pub fn new() -> Self {
-                Self {
-                    #(#original_class_names: #randomized_class_names,)*
-                }
-            }
+    }
+}
+
+fn create_devtools_registration(
+    source: &str,
+    style_sheet: &proc_macro2::TokenStream,
+    scope_id: proc_macro2::TokenStream,
+    classes: &BTreeMap<String, String>,
+    class_name_sources: &BTreeMap<String, String>,
+) -> proc_macro2::TokenStream {
+    let original_class_names: Vec<&String> = classes.keys().collect();
+    let randomized_class_names: Vec<&String> = classes.values().collect();
+    let source_class_names: Vec<&String> = class_name_sources.keys().collect();
+    let sources: Vec<&String> = class_name_sources.values().collect();
+
+    quote::quote! {
+        pub fn register_devtools() {
+            #[cfg(debug_assertions)]
+            ::turf::devtools::register_style_sheet(::turf::devtools::StyleSheetEntry {
+                style_sheet: #source,
+                css: #style_sheet,
+                scope_id: #scope_id,
+                class_names: &[#((#original_class_names, #randomized_class_names)),*],
+                class_name_sources: &[#((#source_class_names, #sources)),*],
+            });
         }
+    }
+}
 
-        (STYLE_SHEET, ClassNames::new())
+fn create_partition_loading() -> proc_macro2::TokenStream {
+    quote::quote! {
+        pub fn load_partitions() -> ::turf::web::Promise {
+            ::turf::web::load_style_sheets(PARTITION_URLS)
+        }
     }
 }
 
 fn create_include_bytes(untracked_load_paths: Vec<PathBuf>) -> proc_macro2::TokenStream {
-    let untracked_load_path_values: Vec<String> = untracked_load_paths
-        .into_iter()
-        .map(|item| format!("{}", item.as_path().display()))
-        .collect();
+    // Paths are passed on unchanged instead of through their lossy display representation. String
+    // literals can't represent paths that aren't valid UTF-8, so changes to such files don't
+    // trigger a rebuild on their own, which is reported as a warning.
+    let mut untracked_load_path_values: Vec<&str> = Vec::new();
+    for untracked_load_path in &untracked_load_paths {
+        match untracked_load_path.to_str() {
+            Some(untracked_load_path) => untracked_load_path_values.push(untracked_load_path),
+            None => turf_internals::compile_warning(
+                &format!(
+                    "the loaded file {} isn't tracked for changes since its path isn't valid UTF-8 - changes to it only take effect once the crate is rebuilt for another reason",
+                    untracked_load_path.display()
+                ),
+                None,
+            ),
+        }
+    }
 
     quote::quote! {
         #(const _: &[u8] = include_bytes!(#untracked_load_path_values);)*
@@ -219,18 +1592,122 @@ enum Error {
     LoadPathTracking(turf_internals::LoadPathTrackingError),
 }
 
+impl Error {
+    fn into_compile_error(self) -> TokenStream {
+        match self {
+            Error::Turf(e) => to_compile_error(e),
+            Error::LoadPathTracking(e) => {
+                turf_internals::record_compile_error(&e.to_string());
+                to_compile_error(e)
+            }
+        }
+    }
+}
+
 struct ProcessedStyleSheet {
     untracked_load_paths: Vec<PathBuf>,
     css: String,
-    class_names: HashMap<String, String>,
+    class_names: BTreeMap<String, String>,
+    container_names: BTreeMap<String, String>,
+    counter_styles: BTreeMap<String, String>,
+    view_transition_names: BTreeMap<String, String>,
+    keyframes: BTreeMap<String, String>,
+    parts: BTreeMap<String, String>,
+    css_variables: BTreeMap<String, String>,
+    font_families: BTreeMap<String, String>,
+    grid_areas: BTreeMap<String, String>,
+    exports: BTreeMap<String, String>,
+    breakpoints: BTreeMap<String, String>,
+    theme_variables: BTreeMap<String, String>,
+    scope_id: String,
+    scoping: Scoping,
+    bem: bool,
+    partitions: Vec<String>,
+    partition_urls: Vec<String>,
+    theme_bootstrap: Option<String>,
+    forced_colors_fallback: Option<String>,
+    class_name_sources: BTreeMap<String, String>,
+    class_name_rules: BTreeMap<String, Vec<String>>,
+    class_name_metadata: BTreeMap<String, ClassNameMetadata>,
+    media_sheets: Option<MediaSheets>,
+    dark_css: Option<String>,
+    css_artifacts: Option<CssArtifacts>,
+    compressed_css: Option<Vec<u8>>,
+    version: String,
+    css_hash: String,
+    css_file_name: Option<String>,
+    source: String,
+    location: Option<SourceLocation>,
+    codegen: Codegen,
+}
+
+struct ProcessedRawCss {
+    untracked_load_paths: Vec<PathBuf>,
+    raw_css: RawCss,
+    source: String,
+}
+
+fn handle_raw_css(style_sheet: StyleSheetKind) -> Result<ProcessedRawCss, Error> {
+    let raw_css = turf_internals::raw_css(style_sheet).map_err(Error::Turf)?;
+    let source = raw_css.original_style_sheet.to_string();
+
+    let mut untracked_load_paths =
+        turf_internals::get_untracked_load_paths().map_err(Error::LoadPathTracking)?;
+    if let StyleSheetKind::File(current_file_path) = &raw_css.original_style_sheet {
+        untracked_load_paths.push(current_file_path.clone());
+    }
+
+    Ok(ProcessedRawCss {
+        untracked_load_paths,
+        raw_css,
+        source,
+    })
 }
 
 fn handle_style_sheet(style_sheet: StyleSheetKind) -> Result<ProcessedStyleSheet, Error> {
+    processed_style_sheet(turf_internals::style_sheet(style_sheet).map_err(Error::Turf)?)
+}
+
+fn processed_style_sheet(
+    compiled_style_sheet: CompiledStyleSheet,
+) -> Result<ProcessedStyleSheet, Error> {
     let CompiledStyleSheet {
         css,
         class_names,
+        container_names,
+        counter_styles,
+        view_transition_names,
+        keyframes,
+        parts,
+        css_variables,
+        font_families,
+        grid_areas,
+        exports,
+        breakpoints,
+        theme_variables,
+        scope_id,
+        scoping,
+        bem,
+        partitions,
+        partition_urls,
+        theme_bootstrap,
+        forced_colors_fallback,
+        class_name_sources,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        css_artifacts,
+        compressed_css,
+        version,
+        css_hash,
+        css_file_name,
         original_style_sheet,
-    } = turf_internals::style_sheet(style_sheet).map_err(Error::Turf)?;
+        codegen,
+    } = compiled_style_sheet;
+
+    let source = original_style_sheet.to_string();
+    let location = turf_internals::style_sheet_location(&original_style_sheet);
 
     let untracked_load_paths = {
         let mut values =
@@ -247,27 +1724,240 @@ fn handle_style_sheet(style_sheet: StyleSheetKind) -> Result<ProcessedStyleSheet
         untracked_load_paths,
         css,
         class_names,
+        container_names,
+        counter_styles,
+        view_transition_names,
+        keyframes,
+        parts,
+        css_variables,
+        font_families,
+        grid_areas,
+        exports,
+        breakpoints,
+        theme_variables,
+        scope_id,
+        scoping,
+        bem,
+        partitions,
+        partition_urls,
+        theme_bootstrap,
+        forced_colors_fallback,
+        class_name_sources,
+        class_name_rules,
+        class_name_metadata,
+        media_sheets,
+        dark_css,
+        css_artifacts,
+        compressed_css,
+        version,
+        css_hash,
+        css_file_name,
+        source,
+        location,
+        codegen,
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
+
+    use convert_case::Case;
+    use turf_internals::{
+        Codegen, CodegenStyle, Compression, IdentifierCase, IdentifierSanitization, Settings,
+        StyleSheetKind,
+    };
 
-    use super::create_classes_structure;
+    use super::{
+        apply_item_options, check_class_name_identifiers, check_class_name_lookup,
+        create_bem_modules, create_class_name_lookup, create_classes_structure,
+        create_constants_structure, create_exports_structure, create_style_sheet_items,
+        create_style_sheet_static, create_theme_structure, create_values_function, identifier_name,
+        processed_style_sheet, Derives,
+    };
 
     #[test]
     fn test() {
-        let mut class_names = HashMap::new();
+        let mut class_names = BTreeMap::new();
         class_names.insert(String::from("test-class"), String::from("abc-123"));
 
-        let out = create_classes_structure(class_names);
+        let out = create_classes_structure(
+            class_names,
+            &BTreeMap::new(),
+            &Codegen::default(),
+            &Derives::default(),
+            proc_macro2::TokenStream::new(),
+        );
 
         assert_eq!(
             out.to_string(),
             quote::quote! {
-                #[doc="TEST_CLASS = \"abc-123\"\n"]
+                #[doc="The class names of the style sheet"]
+                pub struct ClassName;
+                impl ClassName {
+                    #[doc="`test-class` = `\"abc-123\"`"]
+                    pub const TEST_CLASS: &'static str = "abc-123";
+                }
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn sorted_constants() {
+        let mut class_names = BTreeMap::new();
+        class_names.insert(String::from("second"), String::from("abc-2"));
+        class_names.insert(String::from("first"), String::from("abc-1"));
+
+        let out = create_classes_structure(
+            class_names,
+            &BTreeMap::new(),
+            &Codegen::default(),
+            &Derives::default(),
+            proc_macro2::TokenStream::new(),
+        );
+
+        assert_eq!(
+            out.to_string(),
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
+                pub struct ClassName;
+                impl ClassName {
+                    #[doc="`first` = `\"abc-1\"`"]
+                    pub const FIRST: &'static str = "abc-1";
+                    #[doc="`second` = `\"abc-2\"`"]
+                    pub const SECOND: &'static str = "abc-2";
+                }
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn module_style() {
+        let mut class_names = BTreeMap::new();
+        class_names.insert(String::from("test-class"), String::from("abc-123"));
+        let mut class_name_rules = BTreeMap::new();
+        class_name_rules.insert(
+            String::from("test-class"),
+            vec![String::from(".abc-123 {\n  color: red;\n}")],
+        );
+
+        let out = create_classes_structure(
+            class_names,
+            &class_name_rules,
+            &Codegen {
+                style: CodegenStyle::Module,
+                ..Default::default()
+            },
+            &Derives::default(),
+            proc_macro2::TokenStream::new(),
+        );
+
+        assert_eq!(
+            out.to_string(),
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
+                pub mod class {
+                    #[doc="`test-class` = `\"abc-123\"`\n\n```css\n.abc-123 {\n  color: red;\n}\n```"]
+                    pub const TEST_CLASS: &'static str = "abc-123";
+                }
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn constant_case() {
+        let mut class_names = BTreeMap::new();
+        class_names.insert(String::from("btn-primary"), String::from("abc-123"));
+
+        let out = create_classes_structure(
+            class_names,
+            &BTreeMap::new(),
+            &Codegen {
+                const_case: Some(IdentifierCase::Camel),
+                ..Default::default()
+            },
+            &Derives::default(),
+            proc_macro2::TokenStream::new(),
+        );
+
+        assert_eq!(
+            out.to_string(),
+            quote::quote! {
+                #[doc="The class names of the style sheet"]
                 pub struct ClassName;
+                #[allow(non_upper_case_globals)]
+                impl ClassName {
+                    #[doc="`btn-primary` = `\"abc-123\"`"]
+                    pub const btnPrimary: &'static str = "abc-123";
+                }
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn sanitize_identifiers() {
+        use IdentifierSanitization::{Replace, Strip};
+
+        assert_eq!(
+            identifier_name("is-active!", Case::ScreamingSnake, Replace),
+            "IS_ACTIVE_"
+        );
+        assert_eq!(
+            identifier_name("is-active!", Case::ScreamingSnake, Strip),
+            "IS_ACTIVE"
+        );
+        assert_eq!(
+            identifier_name("2col", Case::ScreamingSnake, Replace),
+            "_2_COL"
+        );
+        assert_eq!(identifier_name("🔥", Case::Snake, Strip), "_1F525");
+        assert_eq!(identifier_name("a·b", Case::Snake, Replace), "a·b");
+        assert_eq!(identifier_name("½-col", Case::Snake, Replace), "__col");
+        assert_eq!(
+            identifier_name("\u{301}col", Case::Snake, Replace),
+            "_\u{301}col"
+        );
+        assert_eq!(identifier_name("type", Case::Snake, Replace), "type_");
+        assert_eq!(identifier_name("self", Case::Pascal, Replace), "Self_");
+        assert_eq!(
+            identifier_name("test-class", Case::ScreamingSnake, Replace),
+            "TEST_CLASS"
+        );
+    }
+
+    #[test]
+    fn item_options() {
+        let items = quote::quote! {
+            pub static STYLE_SHEET: &'static str = "";
+            static CLASS_NAME_LOOKUP: Map = Map { key: 0 };
+            #[doc="TEST_CLASS = \"abc-123\"\n"]
+            pub struct ClassName;
+            impl ClassName {
+                pub const TEST_CLASS: &'static str = "abc-123";
+            }
+        };
+
+        let out = apply_item_options(
+            items,
+            &quote::quote! { pub(crate) },
+            &quote::quote! { #[allow(dead_code)] },
+        );
+
+        assert_eq!(
+            out.to_string(),
+            quote::quote! {
+                #[allow(dead_code)]
+                pub(crate) static STYLE_SHEET: &'static str = "";
+                #[allow(dead_code)]
+                static CLASS_NAME_LOOKUP: Map = Map { key: 0 };
+                #[allow(dead_code)]
+                #[doc="TEST_CLASS = \"abc-123\"\n"]
+                pub(crate) struct ClassName;
+                #[allow(dead_code)]
                 impl ClassName {
                     pub const TEST_CLASS: &'static str = "abc-123";
                 }
@@ -275,4 +1965,284 @@ mod tests {
             .to_string()
         )
     }
+
+    #[test]
+    fn css_artifacts_tokens() {
+        let directory =
+            std::env::temp_dir().join(format!("turf_macros_css_artifacts_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let style_sheet_path = directory.join("button.scss");
+        let settings = || {
+            Settings::builder()
+                .class_name_template("artifacts-<original_name>")
+                .codegen(Codegen {
+                    css_artifacts_path: Some(directory.join("artifacts")),
+                    ..Default::default()
+                })
+                .build()
+                .unwrap()
+        };
+        let tokens = |color: &str| {
+            std::fs::write(&style_sheet_path, format!(".button {{ color: {color}; }}")).unwrap();
+            let compiled_style_sheet = turf_internals::style_sheet_with_settings(
+                StyleSheetKind::File(style_sheet_path.clone()),
+                settings(),
+            )
+            .unwrap();
+            let Ok(style_sheet) = processed_style_sheet(compiled_style_sheet) else {
+                panic!("the style sheet is processed");
+            };
+            create_style_sheet_items(style_sheet).to_string()
+        };
+
+        let red_tokens = tokens("red");
+        let blue_tokens = tokens("blue");
+        assert!(red_tokens.contains("include_str"));
+        assert!(!red_tokens.contains("red"));
+        assert_eq!(red_tokens, blue_tokens);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn bem_identifier_collisions() {
+        let bem_modules = |names: &[&str]| {
+            let classes = names
+                .iter()
+                .map(|name| (name.to_string(), format!("abc-{name}")))
+                .collect();
+            create_bem_modules(&classes, "card.scss", IdentifierSanitization::Replace)
+                .map(|tokens| tokens.to_string())
+                .map_err(|error| error.to_string())
+        };
+
+        assert!(bem_modules(&["card", "card--large", "card__title", "card__title--block"]).is_ok());
+        assert!(bem_modules(&["card__title", "card--block"]).is_ok());
+        assert!(bem_modules(&["card", "card--block"])
+            .unwrap_err()
+            .contains("the BEM modifier `block` of `card` in card.scss is converted to the identifier `BLOCK` of `card` itself"));
+        assert!(bem_modules(&["card__title", "card__title--element"])
+            .unwrap_err()
+            .contains("identifier `ELEMENT` of `title` itself"));
+        assert!(bem_modules(&["card--is-active", "card--is_active"])
+            .unwrap_err()
+            .contains("the BEM modifiers `is-active` and `is_active` of `card` in card.scss are both converted to the identifier `IS_ACTIVE`"));
+        assert!(bem_modules(&["my-card", "my_card"])
+            .unwrap_err()
+            .contains("the BEM blocks `my-card` and `my_card` in card.scss"));
+    }
+
+    #[test]
+    fn constant_identifier_collisions() {
+        let keyframes = |names: &[&str]| {
+            let names = names
+                .iter()
+                .map(|name| (name.to_string(), format!("abc-{name}")))
+                .collect();
+            create_constants_structure(
+                "Keyframes",
+                names,
+                "card.scss",
+                IdentifierSanitization::Replace,
+            )
+            .map(|tokens| tokens.to_string())
+            .map_err(|error| error.to_string())
+        };
+
+        assert!(keyframes(&["fade-in", "fade-out"]).is_ok());
+        assert!(keyframes(&["fade-in", "fade_in"])
+            .unwrap_err()
+            .contains("the names `fade-in` and `fade_in` of card.scss are both converted to the constant `Keyframes::FADE_IN`"));
+    }
+
+    #[test]
+    fn class_name_lookup() {
+        let class_names = BTreeMap::from([
+            (String::from("button"), String::from("abc-1")),
+            (String::from("iter"), String::from("abc-2")),
+            (String::from("lookup"), String::from("abc-3")),
+        ]);
+        let codegen = |style, debug_stats| Codegen {
+            class_name_lookup: true,
+            style,
+            debug_stats,
+            ..Default::default()
+        };
+
+        assert!(
+            check_class_name_lookup(&class_names, "", &codegen(CodegenStyle::Struct, false))
+                .is_ok()
+        );
+        assert!(
+            check_class_name_lookup(&class_names, "", &codegen(CodegenStyle::Enum, false)).is_ok()
+        );
+        assert!(check_class_name_lookup(
+            &class_names,
+            "style.scss",
+            &codegen(CodegenStyle::Struct, true)
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("the class name `iter` of style.scss is converted to the identifier `iter`"));
+        assert!(check_class_name_lookup(
+            &BTreeMap::from([(String::from("lookup"), String::from("abc-3"))]),
+            "style.scss",
+            &codegen(CodegenStyle::Struct, true)
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("is converted to the identifier `lookup`"));
+        assert!(check_class_name_lookup(
+            &class_names,
+            "",
+            &Codegen {
+                const_case: Some(IdentifierCase::Snake),
+                ..codegen(CodegenStyle::Module, false)
+            }
+        )
+        .is_err());
+
+        let (class_name_lookup, _) = create_class_name_lookup(&class_names, CodegenStyle::Struct);
+        let class_name_lookup = class_name_lookup.to_string();
+        assert!(class_name_lookup.contains(":: turf :: __private :: phf :: Map {"));
+        assert!(class_name_lookup.contains(r#"("button" , "abc-1")"#));
+        assert!(
+            create_class_name_lookup(&BTreeMap::new(), CodegenStyle::Struct)
+                .0
+                .to_string()
+                .contains("entries : & []")
+        );
+    }
+
+    #[test]
+    fn compressed_style_sheet() {
+        let (items, style_sheet) = create_style_sheet_static(
+            quote::quote! { ".button{color:red}" },
+            Some(vec![1, 2, 3]),
+            Some(Compression::Brotli),
+        );
+        let items = items.to_string();
+        assert!(items.contains(r#"STYLE_SHEET_COMPRESSED : & 'static [u8] = b"\x01\x02\x03""#));
+        assert!(items.contains(":: turf :: compression :: decompress_brotli"));
+        assert!(!items.contains("color:red"));
+        assert_eq!(style_sheet.to_string(), "decompressed_style_sheet ()");
+
+        let (items, style_sheet) =
+            create_style_sheet_static(quote::quote! { ".button{color:red}" }, None, None);
+        assert_eq!(
+            items.to_string(),
+            r#"pub static STYLE_SHEET : & 'static str = ".button{color:red}" ;"#
+        );
+        assert_eq!(style_sheet.to_string(), "STYLE_SHEET");
+    }
+
+    #[test]
+    fn derives() {
+        let derives = Derives(vec![
+            quote::quote! { Default },
+            quote::quote! { ::std::hash::Hash },
+            quote::quote! { ::core::fmt::Debug },
+        ]);
+        let class_names = BTreeMap::from([(String::from("button"), String::from("abc-1"))]);
+        let codegen = Codegen {
+            values_function: true,
+            ..Default::default()
+        };
+
+        let class_name = create_classes_structure(
+            class_names.clone(),
+            &BTreeMap::new(),
+            &codegen,
+            &derives,
+            proc_macro2::TokenStream::new(),
+        );
+        assert!(class_name.to_string().contains(
+            &quote::quote! { #[derive(Default, ::std::hash::Hash, ::core::fmt::Debug)] }
+                .to_string()
+        ));
+
+        let values_function = create_values_function(
+            &class_names,
+            "",
+            &quote::quote! { STYLE_SHEET },
+            &codegen,
+            &derives,
+        );
+        let values_function = values_function.to_string();
+        assert!(values_function.contains(
+            &quote::quote! {
+                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                #[derive(::std::hash::Hash)]
+                pub struct ClassNames
+            }
+            .to_string()
+        ));
+        assert_eq!(values_function.matches("derive").count(), 2);
+    }
+
+    #[test]
+    fn invalid_exports() {
+        let exports = |exports: &[(&str, &str)]| {
+            let exports = exports
+                .iter()
+                .map(|(name, value)| (String::from(*name), String::from(*value)))
+                .collect();
+            create_exports_structure(exports, "style.scss", IdentifierSanitization::Replace)
+                .map(|out| out.unwrap().to_string())
+                .map_err(|error| error.to_string())
+        };
+
+        let out = exports(&[("gap", "8px"), ("primary", "#ff0000")]).unwrap();
+        assert!(out.contains("pub const GAP_NUMBER : f64 = 8f64"));
+
+        let error = exports(&[("big", "1e999")]).unwrap_err();
+        assert!(error.contains("`big: 1e999` of style.scss is out of the range of an `f64`"));
+
+        let error = exports(&[("gap", "8px"), ("gap-number", "#ff0000")]).unwrap_err();
+        assert!(error.contains("both generate the `Exports::GAP_NUMBER` constant"));
+
+        let error = exports(&[("gap-size", "8px"), ("gap_size", "8px")]).unwrap_err();
+        assert!(error.contains("both generate the `Exports::GAP_SIZE` constant"));
+    }
+
+    #[test]
+    fn theme_numbers_out_of_range() {
+        let theme = |value: &str| {
+            let theme_variables = BTreeMap::from([(String::from("gap"), String::from(value))]);
+            create_theme_structure(
+                theme_variables,
+                "theme.scss",
+                IdentifierSanitization::Replace,
+            )
+            .map(|out| out.unwrap().to_string())
+            .map_err(|error| error.to_string())
+        };
+
+        assert!(theme("8px").unwrap().contains("8f32"));
+        for value in ["1e39", "1e39px"] {
+            let error = theme(value).unwrap_err();
+            assert!(error.contains(&format!(
+                "`$gap: {value}` of theme.scss is out of the range of an `f32`"
+            )));
+        }
+    }
+
+    #[test]
+    fn class_name_identifier_collisions() {
+        let class_names = BTreeMap::from([
+            (String::from("is-active"), String::from("abc-1")),
+            (String::from("is_active"), String::from("abc-2")),
+        ]);
+
+        let error = check_class_name_identifiers(
+            &class_names,
+            "style.scss",
+            Case::ScreamingSnake,
+            IdentifierSanitization::Replace,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains(
+            "the class names `is-active` and `is_active` of style.scss are both converted to the identifier `IS_ACTIVE`"
+        ));
+    }
 }
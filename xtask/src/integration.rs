@@ -0,0 +1,260 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The class names of a lockfile, keyed by style sheet and original class name
+type ClassNames = BTreeMap<String, BTreeMap<String, String>>;
+
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+#[derive(Debug, Clone, Copy)]
+enum Build {
+    /// A server side application built with cargo
+    Cargo,
+    /// A client side application built with trunk
+    Trunk,
+}
+
+/// An example application and the artifacts it is expected to generate
+struct Example {
+    directory: &'static str,
+    package: &'static str,
+    build: Build,
+    /// The prefix every generated class name of the example starts with
+    class_name_prefix: &'static str,
+}
+
+/// The supported setups, each of them is an example application in the `examples` directory
+const EXAMPLES: &[Example] = &[
+    Example {
+        directory: "axum-askama-htmx",
+        package: "axum-askama-htmx",
+        build: Build::Cargo,
+        class_name_prefix: "askama-example_",
+    },
+    Example {
+        directory: "dioxus-example",
+        package: "dioxus-example",
+        build: Build::Trunk,
+        class_name_prefix: "dioxus-example_",
+    },
+    Example {
+        directory: "leptos-example",
+        package: "leptos-example",
+        build: Build::Trunk,
+        class_name_prefix: "leptos-example_",
+    },
+    Example {
+        directory: "leptos-hash-example",
+        package: "leptos-hash-example",
+        build: Build::Trunk,
+        class_name_prefix: "leptos-hash-example_",
+    },
+    Example {
+        directory: "yew-example",
+        package: "yew-example",
+        build: Build::Trunk,
+        class_name_prefix: "yew-example_",
+    },
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrationError {
+    #[error("unknown example '{0}'")]
+    UnknownExample(String),
+    #[error("could not run '{0}' - {1}")]
+    Spawn(String, std::io::Error),
+    #[error("'{0}' failed for {1}")]
+    Command(String, &'static str),
+    #[error("could not create the directory '{0}' - {1}")]
+    CreateDir(PathBuf, std::io::Error),
+    #[error("could not read '{0}' - {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("could not parse the class name lockfile '{0}' - {1}")]
+    Lockfile(PathBuf, toml::de::Error),
+    #[error("{example} - {message}")]
+    Assertion {
+        example: &'static str,
+        message: String,
+    },
+}
+
+/// Builds the examples against the local turf and checks the artifacts they generate
+///
+/// The file output and the class name lockfile are enabled with `TURF_` environment variables,
+/// so the examples don't need any settings for the test. All examples are built if `filter` is
+/// empty.
+pub fn test_integration(filter: &[String]) -> Result<(), IntegrationError> {
+    if let Some(unknown) = filter
+        .iter()
+        .find(|name| !EXAMPLES.iter().any(|example| example.directory == *name))
+    {
+        return Err(IntegrationError::UnknownExample(unknown.clone()));
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("the xtask crate is in the root of the repository");
+
+    for example in EXAMPLES
+        .iter()
+        .filter(|example| filter.is_empty() || filter.iter().any(|name| name == example.directory))
+    {
+        println!("testing {}", example.directory);
+        test_example(root, example)?;
+    }
+
+    Ok(())
+}
+
+fn test_example(root: &Path, example: &Example) -> Result<(), IntegrationError> {
+    let example_dir = root.join("examples").join(example.directory);
+    let artifacts_dir = root.join("target/xtask").join(example.directory);
+    let global_css_path = artifacts_dir.join("global.css");
+    let lockfile_path = artifacts_dir.join("turf.lock");
+    // Class names of a previous run would be reused from the lockfile
+    let _ = std::fs::remove_dir_all(&artifacts_dir);
+    std::fs::create_dir_all(&artifacts_dir)
+        .map_err(|error| IntegrationError::CreateDir(artifacts_dir.clone(), error))?;
+
+    // Macros only write the artifacts when they are expanded, which cargo skips for cached builds
+    let mut clean = Command::new("cargo");
+    clean.args(["clean", "--package", example.package]);
+    if let Build::Trunk = example.build {
+        clean.args(["--target", WASM_TARGET]);
+    }
+    run(clean.current_dir(&example_dir), example)?;
+
+    let mut build = match example.build {
+        Build::Cargo => {
+            let mut build = Command::new("cargo");
+            build.arg("build");
+            build
+        }
+        Build::Trunk => {
+            let mut build = Command::new("trunk");
+            build.arg("build");
+            build
+        }
+    };
+    build
+        .current_dir(&example_dir)
+        .env("TURF_FILE_OUTPUT__GLOBAL_CSS_FILE_PATH", &global_css_path)
+        .env("TURF_CLASS_NAMES__LOCKFILE", &lockfile_path);
+    run(&mut build, example)?;
+
+    let global_css = std::fs::read_to_string(&global_css_path)
+        .map_err(|error| IntegrationError::Read(global_css_path.clone(), error))?;
+    let lockfile = std::fs::read_to_string(&lockfile_path)
+        .map_err(|error| IntegrationError::Read(lockfile_path.clone(), error))?;
+    let class_names: ClassNames = toml::from_str(&lockfile)
+        .map_err(|error| IntegrationError::Lockfile(lockfile_path.clone(), error))?;
+    check_class_names(&class_names, &global_css, example.class_name_prefix).map_err(|message| {
+        IntegrationError::Assertion {
+            example: example.directory,
+            message,
+        }
+    })?;
+
+    if let Build::Trunk = example.build {
+        check_trunk_output(&example_dir.join("dist")).map_err(|message| {
+            IntegrationError::Assertion {
+                example: example.directory,
+                message,
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+fn run(command: &mut Command, example: &Example) -> Result<(), IntegrationError> {
+    let description = format!("{command:?}");
+    let status = command
+        .status()
+        .map_err(|error| IntegrationError::Spawn(description.clone(), error))?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(IntegrationError::Command(description, example.directory)),
+    }
+}
+
+/// Checks that the example generated class names with its template and that every class name is
+/// part of the global css file
+fn check_class_names(
+    class_names: &ClassNames,
+    global_css: &str,
+    prefix: &str,
+) -> Result<(), String> {
+    if class_names.values().all(BTreeMap::is_empty) {
+        return Err(String::from("no class names were generated"));
+    }
+
+    for (style_sheet, style_sheet_class_names) in class_names {
+        for (original_name, name) in style_sheet_class_names {
+            if !name.starts_with(prefix) {
+                return Err(format!(
+                    "the class name '{name}' of '{original_name}' in {style_sheet} doesn't start with '{prefix}'"
+                ));
+            }
+            if !global_css.contains(&format!(".{name}")) {
+                return Err(format!(
+                    "the class name '{name}' of '{original_name}' in {style_sheet} is missing from the global css file"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that trunk wrote the page and the WebAssembly binary of the example
+fn check_trunk_output(dist_dir: &Path) -> Result<(), String> {
+    if !dist_dir.join("index.html").is_file() {
+        return Err(format!("'{}' has no index.html", dist_dir.display()));
+    }
+
+    let has_wasm = std::fs::read_dir(dist_dir)
+        .map_err(|error| format!("could not read '{}' - {error}", dist_dir.display()))?
+        .filter_map(Result::ok)
+        .any(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "wasm")
+        });
+
+    match has_wasm {
+        true => Ok(()),
+        false => Err(format!(
+            "'{}' has no WebAssembly binary",
+            dist_dir.display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_class_names, ClassNames};
+
+    #[test]
+    fn class_name_checks() {
+        let class_names: ClassNames = toml::from_str(
+            "[\"src/button.scss\"]\nbutton = \"app_button_abc\"\nicon = \"app_icon_def\"",
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_class_names(&class_names, ".app_button_abc{}.app_icon_def{}", "app_"),
+            Ok(())
+        );
+        assert!(check_class_names(&class_names, ".app_button_abc{}", "app_").is_err());
+        assert!(
+            check_class_names(&class_names, ".app_button_abc{}.app_icon_def{}", "web_").is_err()
+        );
+        assert!(check_class_names(&ClassNames::new(), "", "app_").is_err());
+    }
+}
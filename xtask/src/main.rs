@@ -0,0 +1,32 @@
+//! Development tasks of turf, run with `cargo xtask <task>`.
+
+mod integration;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: cargo xtask <task>
+
+Tasks:
+    test-integration [example...]    Builds the examples against the local turf and checks their generated artifacts";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("test-integration") => {
+            let examples: Vec<String> = args.collect();
+
+            match integration::test_integration(&examples) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(error) => {
+                    eprintln!("error: {error}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}